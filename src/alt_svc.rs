@@ -0,0 +1,196 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `Alt-Svc` header, alternative services (RFC 7838)
+//!
+//! ```
+//! use std::time::Duration;
+//! use wrequest::{Response, HTTP_200_OK};
+//! use wrequest::alt_svc::AltService;
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.insert_header("Alt-Svc", "h3=\":443\"; ma=3600, h2=\"alt.example.com:8443\"; persist=1");
+//!
+//! let services = response.alt_services();
+//! assert_eq!(services[0].protocol_id, "h3");
+//! assert_eq!(services[0].port(), Some(443));
+//! assert_eq!(services[0].max_age, Some(Duration::from_secs(3600)));
+//! assert_eq!(services[1].host(), "alt.example.com");
+//! assert!(services[1].persist);
+//!
+//! response.set_alt_services(&[AltService::new("h3", ":443").with_max_age(Duration::from_secs(86400))]);
+//! assert_eq!(response.headers().get("Alt-Svc"), Some("h3=\":443\"; ma=86400"));
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+use crate::{Response, uri};
+use crate::header_list::split_quoted;
+
+/// `Alt-Svc` header name
+pub const ALT_SVC: &str = "Alt-Svc";
+
+/// `Alt-Svc` value that invalidates all the alternative services of the origin
+pub const CLEAR: &str = "clear";
+
+/// Default freshness lifetime of an alternative service without `ma` parameter, 24 hours
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 3600);
+
+/// Alternative service of an `Alt-Svc` header
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct AltService {
+    /// ALPN protocol identifier, like `h3` or `h2`
+    pub protocol_id: String,
+    /// Alternative authority, `host:port` with an empty host for the origin host
+    pub authority: String,
+    /// Freshness lifetime (`ma` parameter)
+    pub max_age: Option<Duration>,
+    /// The entry survives network configuration changes (`persist=1`)
+    pub persist: bool,
+    /// Other parameters, with lowercase names
+    pub params: Vec<(String, String)>
+}
+
+impl AltService {
+    /// Constructor of an alternative service for a protocol and authority, like `:443`
+    pub fn new<P: Into<String>, A: Into<String>>(protocol_id: P, authority: A) -> AltService {
+        AltService {
+            protocol_id: protocol_id.into(),
+            authority: authority.into(),
+            max_age: None,
+            persist: false,
+            params: Vec::new()
+        }
+    }
+
+    /// Sets the freshness lifetime
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets if the entry survives network configuration changes
+    pub fn with_persist(mut self, persist: bool) -> Self {
+        self.persist = persist;
+        self
+    }
+
+    /// Gets the host of the authority, empty for the origin host
+    pub fn host(&self) -> &str {
+        match self.authority.rfind(':') {
+            Some(colon) if !self.authority[colon..].contains(']') => &self.authority[..colon],
+            _ => &self.authority
+        }
+    }
+
+    /// Gets the port of the authority
+    pub fn port(&self) -> Option<u16> {
+        match self.authority.rfind(':') {
+            Some(colon) if !self.authority[colon..].contains(']') => self.authority[colon + 1..].parse().ok(),
+            _ => None
+        }
+    }
+
+    /// Gets the freshness lifetime, 24 hours if not set
+    pub fn max_age_or_default(&self) -> Duration {
+        self.max_age.unwrap_or(DEFAULT_MAX_AGE)
+    }
+
+    /// Parses an `Alt-Svc` header value, skipping malformed entries. `clear` gives no entries.
+    pub fn parse_list(value: &str) -> Vec<AltService> {
+        split_quoted(value, ',').into_iter().filter_map(AltService::parse).collect()
+    }
+
+    fn parse(entry: &str) -> Option<AltService> {
+        let mut params = split_quoted(entry, ';').into_iter();
+        let (protocol_id, authority) = params.next()?.split_once('=')?;
+        let authority = unquote(authority.trim())?;
+        let mut result = AltService::new(uri::percent_decode(protocol_id.trim()), authority);
+        for param in params {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            let name = name.trim().to_ascii_lowercase();
+            let value = value.trim();
+            let value = unquote(value).unwrap_or_else(|| value.to_string());
+            match name.as_str() {
+                "ma" => result.max_age = Some(Duration::from_secs(value.parse().ok()?)),
+                "persist" => result.persist = value == "1",
+                _ => result.params.push((name, value))
+            }
+        }
+        Some(result)
+    }
+}
+
+impl fmt::Display for AltService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Protocol identifiers are tokens, other characters are percent-encoded
+        let protocol_id: String = self.protocol_id.bytes()
+            .map(|c| if c.is_ascii_alphanumeric() || b"!#$&'*+-.^_`|~".contains(&c) {
+                (c as char).to_string()
+            } else {
+                format!("%{:02X}", c)
+            })
+            .collect();
+        write!(f, "{}=\"{}\"", protocol_id, self.authority.replace('\\', "\\\\").replace('"', "\\\""))?;
+        if let Some(max_age) = self.max_age {
+            write!(f, "; ma={}", max_age.as_secs())?;
+        }
+        if self.persist {
+            write!(f, "; persist=1")?;
+        }
+        for (name, value) in self.params.iter() {
+            write!(f, "; {}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Gets the content of a quoted string, `None` if it is not quoted
+fn unquote(value: &str) -> Option<String> {
+    let inner = value.strip_prefix('"')?.strip_suffix('"')?;
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => result.extend(chars.next()),
+            c => result.push(c)
+        }
+    }
+    Some(result)
+}
+
+impl Response {
+    /// Gets the alternative services of the `Alt-Svc` headers
+    pub fn alt_services(&self) -> Vec<AltService> {
+        self.headers().get(ALT_SVC).map(AltService::parse_list).unwrap_or_default()
+    }
+
+    /// Checks the `Alt-Svc` header invalidates all the alternative services of the origin
+    pub fn clears_alt_services(&self) -> bool {
+        self.headers().get(ALT_SVC).is_some_and(|value| value.trim() == CLEAR)
+    }
+
+    /// Sets the `Alt-Svc` header with the alternative services, in order of preference. No services
+    /// set the header to `clear`.
+    pub fn set_alt_services(&mut self, services: &[AltService]) -> &mut Self {
+        let value = if services.is_empty() {
+            CLEAR.to_string()
+        } else {
+            services.iter().map(|service| service.to_string()).collect::<Vec<String>>().join(", ")
+        };
+        self.insert_header(ALT_SVC, value);
+        self
+    }
+}
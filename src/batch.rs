@@ -0,0 +1,129 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Batch messages: `multipart/mixed` bodies whose parts are whole HTTP messages (`application/http`), as used by
+//! OData and Google batch APIs to send many calls in a single request.
+//!
+//! Each message is serialized in the HTTP/1.1 [wire](crate::wire) format and framed as a multipart part with a
+//! `Content-ID` header with its position, starting at 1.
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK, HTTP_404_NOT_FOUND};
+//!
+//! let calls = vec![Request::get("https://service.com/users/1"), Request::delete("https://service.com/users/2")];
+//! let batch = Request::batch("https://service.com/batch", &calls).unwrap();
+//!
+//! // Server side
+//! let received = batch.batch_requests().unwrap();
+//! assert_eq!(received[1].url(), "/users/2");
+//! let answer = Response::batch(&[Response::new(HTTP_200_OK), Response::new(HTTP_404_NOT_FOUND)]).unwrap();
+//!
+//! // Client side
+//! let responses = answer.batch_responses().unwrap();
+//! assert_eq!(responses[1].status_code(), HTTP_404_NOT_FOUND);
+//! ```
+
+use std::io::{Error, ErrorKind};
+use crate::{HeaderMap, HttpMessage, HttpMethod, Request, Response, CONTENT_TYPE, HTTP_200_OK};
+use crate::multipart::{Event, MultipartReader, MultipartWriter, Part};
+use crate::wire;
+
+/// `Content-Type` header value of batch bodies
+pub const MULTIPART_MIXED: &str = "multipart/mixed";
+/// `Content-Type` header value of the parts of batch bodies
+pub const APPLICATION_HTTP: &str = "application/http";
+/// `Content-ID` header name
+pub const CONTENT_ID: &str = "Content-ID";
+
+/// Frames the serialized messages as a `multipart/mixed` body, returning its `Content-Type` and data
+fn write_batch<I>(messages: I) -> Result<(String, Vec<u8>), Error>
+where I: Iterator<Item = Result<Vec<u8>, Error>> {
+    let mut writer = MultipartWriter::new(Vec::new());
+    let content_type = format!("{}; boundary={}", MULTIPART_MIXED, writer.boundary());
+    for (i, message) in messages.enumerate() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, APPLICATION_HTTP);
+        headers.insert(CONTENT_ID, format!("<{}>", i + 1));
+        writer.write_part(&Part::new(headers), &mut message?.as_slice())?;
+    }
+    Ok((content_type, writer.finish()?))
+}
+
+/// Splits a `multipart/mixed` body into the data of its `application/http` parts
+fn read_batch(message: &HttpMessage) -> Result<Vec<Vec<u8>>, Error> {
+    let content_type = message.headers().get(CONTENT_TYPE).unwrap_or("");
+    let media_type = content_type.split(';').next().unwrap_or("").trim();
+    if !media_type.eq_ignore_ascii_case(MULTIPART_MIXED) {
+        return Err(Error::new(ErrorKind::InvalidData, format!("Content-Type {:?} is not a batch", content_type)));
+    }
+    let mut reader = MultipartReader::from_content_type(content_type)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    reader.feed(message.body().unwrap_or(&[]));
+
+    let mut parts = Vec::new();
+    while let Some(event) = reader.next_event()? {
+        match event {
+            Event::PartStart(part) => {
+                let part_type = part.headers().get(CONTENT_TYPE).unwrap_or("");
+                if !part_type.split(';').next().unwrap_or("").trim().eq_ignore_ascii_case(APPLICATION_HTTP) {
+                    return Err(Error::new(ErrorKind::InvalidData, format!("Batch part of type {:?}", part_type)));
+                }
+                parts.push(Vec::new());
+            },
+            Event::Data(data) => if let Some(current) = parts.last_mut() {
+                current.extend_from_slice(&data);
+            },
+            Event::PartEnd => {},
+            Event::End => break
+        }
+    }
+    reader.finish()?;
+    Ok(parts)
+}
+
+impl Request {
+    /// Creates a `POST` request to `url` whose body is the batch of `requests`. The requests are serialized
+    /// with their target in origin form and a `Host` header, see [wire::write_request].
+    pub fn batch<S>(url: S, requests: &[Request]) -> Result<Request, Error>
+    where S: Into<String> {
+        let (content_type, body) = write_batch(requests.iter().map(wire::request_to_bytes))?;
+        let mut result = Request::new(HttpMethod::POST, url);
+        result.insert_header(CONTENT_TYPE, content_type)
+              .set_body(body)?;
+        Ok(result)
+    }
+
+    /// Parses the requests of a batch body. Fails with an `ErrorKind::InvalidData` error if the `Content-Type`
+    /// is not `multipart/mixed`, a part is not `application/http` or cannot be parsed.
+    pub fn batch_requests(&self) -> Result<Vec<Request>, Error> {
+        read_batch(self)?.iter().map(|data| wire::parse_request(data)).collect()
+    }
+}
+
+impl Response {
+    /// Creates a `200 OK` response whose body is the batch of `responses`, in the order of the requests
+    pub fn batch(responses: &[Response]) -> Result<Response, Error> {
+        let (content_type, body) = write_batch(responses.iter().map(wire::response_to_bytes))?;
+        let mut result = Response::new(HTTP_200_OK);
+        result.insert_header(CONTENT_TYPE, content_type)
+              .set_body(body)?;
+        Ok(result)
+    }
+
+    /// Parses the responses of a batch body, see [Request::batch_requests]
+    pub fn batch_responses(&self) -> Result<Vec<Response>, Error> {
+        read_batch(self)?.iter().map(|data| wire::parse_response(data)).collect()
+    }
+}
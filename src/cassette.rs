@@ -0,0 +1,225 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Record and replay of HTTP interactions (VCR-style testing)
+//!
+//! Cassettes are stored as HTTP Archive (HAR) files, see [crate::har].
+//!
+//! ```no_run
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::cassette::Cassette;
+//!
+//! let mut cassette = Cassette::open("tests/fixtures/users.har").unwrap();
+//! let request = Request::get("https://service.com/users");
+//!
+//! let response = if cassette.is_recording() {
+//!     // Perform the real exchange and keep it
+//!     let response = Response::new(HTTP_200_OK);
+//!     cassette.record(&request, &response);
+//!     cassette.save().unwrap();
+//!     response
+//! } else {
+//!     cassette.replay(&request).expect("interaction not recorded")
+//! };
+//! ```
+
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::{Request, Response};
+use crate::equivalence::{headers_equivalent, key_values_equivalent};
+use crate::har::{self, Timings};
+
+/// Comparison of request bodies when looking up an interaction
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BodyMatch {
+    /// Bodies must be byte-for-byte equal
+    Exact,
+    /// JSON bodies must be semantically equal (ignoring formatting and key order), other text bodies
+    /// must be equal ignoring whitespace, binary ones must be equal
+    Fuzzy,
+    /// Bodies are not compared
+    Ignore
+}
+
+/// Options to find the recorded interaction of a request
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MatchOptions {
+    /// Headers ignored in the comparison, names are case-insensitive
+    pub ignore_headers: Vec<String>,
+    /// Request params ignored in the comparison
+    pub ignore_params: Vec<String>,
+    /// Compare the request headers
+    pub match_headers: bool,
+    /// Compare the request cookies
+    pub match_cookies: bool,
+    /// Comparison of the request bodies
+    pub body: BodyMatch
+}
+
+impl Default for MatchOptions {
+    /// Compares method, URL, params, headers (but volatile ones), cookies and bodies (fuzzy)
+    fn default() -> Self {
+        MatchOptions {
+            ignore_headers: ["Date", "User-Agent", "Authorization", "Cookie", "X-Request-Id", "Traceparent",
+                "Tracestate", "Content-Length"].iter().map(|h| h.to_string()).collect(),
+            ignore_params: Vec::new(),
+            match_headers: true,
+            match_cookies: true,
+            body: BodyMatch::Fuzzy
+        }
+    }
+}
+
+impl MatchOptions {
+    /// Checks a request matches a recorded one
+    pub fn matches(&self, recorded: &Request, request: &Request) -> bool {
+        recorded.method() == request.method()
+            && recorded.url() == request.url()
+            && key_values_equivalent(recorded.params(), request.params(), &self.ignore_params)
+            && (!self.match_cookies || key_values_equivalent(recorded.cookies(), request.cookies(), &[]))
+            && (!self.match_headers || headers_equivalent(recorded.headers(), request.headers(), &self.ignore_headers))
+            && self.bodies_match(recorded.body(), request.body())
+    }
+
+    fn bodies_match(&self, recorded: Option<&[u8]>, request: Option<&[u8]>) -> bool {
+        let (recorded, request) = match (recorded, request) {
+            (None, None) => return true,
+            (Some(recorded), Some(request)) => (recorded, request),
+            _ => return self.body == BodyMatch::Ignore
+        };
+        match self.body {
+            BodyMatch::Exact => recorded == request,
+            BodyMatch::Ignore => true,
+            BodyMatch::Fuzzy => match (std::str::from_utf8(recorded), std::str::from_utf8(request)) {
+                (Ok(recorded), Ok(request)) => match (json::parse(recorded), json::parse(request)) {
+                    (Ok(recorded), Ok(request)) => recorded == request,
+                    _ => recorded.split_whitespace().eq(request.split_whitespace())
+                },
+                _ => recorded == request
+            }
+        }
+    }
+}
+
+/// Sequence of recorded interactions stored in a file
+pub struct Cassette {
+    /// Cassette file
+    path: PathBuf,
+    /// Recorded interactions
+    interactions: Vec<(Request, Response, Timings)>,
+    /// Interactions already replayed
+    replayed: Vec<bool>,
+    /// Cassette is recording, not replaying
+    recording: bool,
+    /// Request matching options
+    options: MatchOptions
+}
+
+impl Cassette {
+    /// Opens a cassette: if the file exists its interactions are loaded for replaying,
+    /// otherwise an empty cassette is created for recording.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Cassette, Error> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(document) => Self::load(path, &document),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::new(path)),
+            Err(e) => Err(e)
+        }
+    }
+
+    /// Creates an empty cassette for recording, stored at `path` when it is saved
+    pub fn new<P: AsRef<Path>>(path: P) -> Cassette {
+        Cassette {
+            path: path.as_ref().to_path_buf(),
+            interactions: Vec::new(),
+            replayed: Vec::new(),
+            recording: true,
+            options: MatchOptions::default()
+        }
+    }
+
+    fn load(path: &Path, document: &str) -> Result<Cassette, Error> {
+        let interactions: Vec<(Request, Response, Timings)> = har::import(document)?
+            .into_iter()
+            .map(|(request, response)| (request, response, Timings::new(SystemTime::UNIX_EPOCH)))
+            .collect();
+        Ok(Cassette {
+            path: path.to_path_buf(),
+            replayed: vec![false; interactions.len()],
+            interactions,
+            recording: false,
+            options: MatchOptions::default()
+        })
+    }
+
+    /// Sets the request matching options
+    pub fn with_options(mut self, options: MatchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Gets the request matching options
+    pub fn options(&self) -> &MatchOptions {
+        &self.options
+    }
+
+    /// Checks the cassette was created empty, for recording
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Number of interactions in the cassette
+    pub fn len(&self) -> usize {
+        self.interactions.len()
+    }
+
+    /// Checks the cassette has no interactions
+    pub fn is_empty(&self) -> bool {
+        self.interactions.is_empty()
+    }
+
+    /// Adds an interaction at the end of the cassette
+    pub fn record(&mut self, request: &Request, response: &Response) {
+        self.interactions.push((request.clone(), response.clone(), Timings::new(SystemTime::now())));
+        self.replayed.push(false);
+    }
+
+    /// Writes the interactions to the cassette file as a HAR document
+    pub fn save(&self) -> Result<(), Error> {
+        fs::write(&self.path, har::export(&self.interactions))
+    }
+
+    /// Finds the recorded response of a request. Interactions are replayed in order: the first matching
+    /// interaction not replayed yet is used, and once all of them are used the last matching one is repeated.
+    pub fn replay(&mut self, request: &Request) -> Option<Response> {
+        let mut last = None;
+        for (i, (recorded, response, _)) in self.interactions.iter().enumerate() {
+            if self.options.matches(recorded, request) {
+                if !self.replayed[i] {
+                    self.replayed[i] = true;
+                    return Some(response.clone());
+                }
+                last = Some(response);
+            }
+        }
+        last.cloned()
+    }
+
+    /// Checks all the interactions have been replayed
+    pub fn is_exhausted(&self) -> bool {
+        self.replayed.iter().all(|replayed| *replayed)
+    }
+}
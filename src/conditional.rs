@@ -0,0 +1,138 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Entity tags and the `If-Match` precondition for optimistic concurrency (RFC 9110, sections 8.8.3 and 13.1.1)
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK, HTTP_412_PRECONDITION_FAILED};
+//! use wrequest::conditional::EntityTag;
+//!
+//! let mut fetched = Response::new(HTTP_200_OK);
+//! fetched.set_etag(&EntityTag::strong("v1"));
+//!
+//! let mut update = Request::put("https://service.com/docs/1");
+//! update.require_etag_match(&fetched.etag().unwrap());
+//! assert_eq!(update.headers().get("If-Match"), Some("\"v1\""));
+//!
+//! // Server side, the document changed meanwhile
+//! let current = EntityTag::strong("v2");
+//! assert!(!update.if_match_satisfied(Some(&current)));
+//! assert_eq!(Response::precondition_failed().status_code(), HTTP_412_PRECONDITION_FAILED);
+//! ```
+
+use std::fmt;
+use crate::{Request, Response, HTTP_412_PRECONDITION_FAILED};
+use crate::header_list::split_quoted;
+
+/// `ETag` header name
+pub const ETAG: &str = "ETag";
+/// `If-Match` header name
+pub const IF_MATCH: &str = "If-Match";
+
+/// Entity tag of a representation, like `"v1"` or `W/"v1"`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EntityTag {
+    /// Weak tags only mark semantically equivalent representations
+    pub weak: bool,
+    /// Opaque tag, without quotes
+    pub tag: String
+}
+
+impl EntityTag {
+    /// Constructor of a strong tag
+    pub fn strong<S: Into<String>>(tag: S) -> EntityTag {
+        EntityTag { weak: false, tag: tag.into() }
+    }
+
+    /// Constructor of a weak tag
+    pub fn weak<S: Into<String>>(tag: S) -> EntityTag {
+        EntityTag { weak: true, tag: tag.into() }
+    }
+
+    /// Parses an entity tag, `None` if it is not quoted or has invalid characters
+    pub fn parse(value: &str) -> Option<EntityTag> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, value)
+        };
+        let tag = quoted.strip_prefix('"')?.strip_suffix('"')?;
+        if tag.bytes().any(|c| c == b'"' || c <= b' ' || c == 0x7F) {
+            return None;
+        }
+        Some(EntityTag { weak, tag: tag.to_string() })
+    }
+
+    /// Strong comparison: both tags are strong and equal
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison: both tags are equal, whether weak or not
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl Request {
+    /// Sets the `If-Match` header so the request only applies if the target still has the `etag`, for example
+    /// the one of a previously fetched response. Weak tags never match, as `If-Match` uses the strong comparison.
+    pub fn require_etag_match(&mut self, etag: &EntityTag) -> &mut Self {
+        self.insert_header(IF_MATCH, etag.to_string());
+        self
+    }
+
+    /// Evaluates the `If-Match` precondition against the `current` entity tag of the target, `None` if it has no
+    /// current representation. It is satisfied if there is no `If-Match` header, if it is `*` and there is a
+    /// current representation, or if one of its tags matches the current one with the strong comparison.
+    /// Servers respond [Response::precondition_failed] otherwise.
+    pub fn if_match_satisfied(&self, current: Option<&EntityTag>) -> bool {
+        let Some(value) = self.headers().get(IF_MATCH) else {
+            return true;
+        };
+        let Some(current) = current else {
+            return false;
+        };
+        split_quoted(value, ',').into_iter().any(|member| member == "*"
+            || EntityTag::parse(member).is_some_and(|tag| tag.strong_eq(current)))
+    }
+}
+
+impl Response {
+    /// Gets the entity tag of the `ETag` header, if any and valid
+    pub fn etag(&self) -> Option<EntityTag> {
+        self.headers().get(ETAG).and_then(EntityTag::parse)
+    }
+
+    /// Sets the `ETag` header
+    pub fn set_etag(&mut self, etag: &EntityTag) -> &mut Self {
+        self.insert_header(ETAG, etag.to_string());
+        self
+    }
+
+    /// Creates a `412 Precondition Failed` response, for requests whose preconditions are not satisfied
+    pub fn precondition_failed() -> Response {
+        Response::new(HTTP_412_PRECONDITION_FAILED)
+    }
+}
@@ -0,0 +1,518 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Connection management and hop-by-hop headers
+//!
+//! ```
+//! use wrequest::{HttpVersion, Request};
+//!
+//! let mut request = Request::get("http://service.com/users");
+//! request.set_version(HttpVersion::Http10);
+//! request.insert_header("Connection", "keep-alive, X-Trace")
+//!        .insert_header("Keep-Alive", "timeout=5, max=100")
+//!        .insert_header("X-Trace", "1")
+//!        .insert_header("Accept", "application/json");
+//!
+//! assert!(request.is_keep_alive());
+//! assert_eq!(request.keep_alive().unwrap().max, Some(100));
+//!
+//! // Before forwarding the request
+//! request.strip_hop_by_hop_headers();
+//! assert_eq!(request.headers().len(), 1);
+//! ```
+//!
+//! A [Connection] follows the HTTP/1.1 exchanges of a connection without doing any I/O: it is fed the
+//! received bytes and returns the bytes to send, so it can run over any socket, TLS stack or in-memory
+//! channel.
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::connection::Connection;
+//!
+//! let mut client = Connection::client();
+//! let mut server = Connection::server();
+//!
+//! // Pipelined requests
+//! let mut sent = client.send_request(&Request::get("http://service.com/users/1")).unwrap();
+//! sent.extend(client.send_request(&Request::get("http://service.com/users/2")).unwrap());
+//!
+//! server.receive(&sent);
+//! while let Some(request) = server.next_request().unwrap() {
+//!     let mut response = Response::new(HTTP_200_OK);
+//!     response.set_body(request.url().as_bytes().to_vec()).unwrap();
+//!     client.receive(&server.send_response(&response).unwrap());
+//! }
+//!
+//! assert_eq!(client.next_response().unwrap().unwrap().body().unwrap(), b"/users/1");
+//! assert_eq!(client.next_response().unwrap().unwrap().body().unwrap(), b"/users/2");
+//! assert!(client.next_response().unwrap().is_none());
+//! ```
+
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+use std::time::Duration;
+use crate::{HttpMessage, HttpMethod, HttpVersion, Request, Response, HTTP_101_SWITCHING_PROTOCOLS};
+use crate::upgrade::CONNECTION;
+use crate::wire;
+
+/// `Keep-Alive` header name
+pub const KEEP_ALIVE: &str = "Keep-Alive";
+
+/// Default maximum size of the message heads received by a [Connection], 64 KiB
+pub const DEFAULT_MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// Headers that only apply to a single connection, removed by intermediaries before forwarding messages
+pub const HOP_BY_HOP_HEADERS: &[&str] = &["Connection", "Keep-Alive", "Proxy-Connection", "Proxy-Authenticate",
+    "Proxy-Authorization", "TE", "Trailer", "Transfer-Encoding", "Upgrade"];
+
+/// Parameters of a `Keep-Alive` header
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct KeepAlive {
+    /// Time the idle connection is kept open (`timeout`)
+    pub timeout: Option<Duration>,
+    /// Maximum number of requests of the connection (`max`)
+    pub max: Option<u32>
+}
+
+impl KeepAlive {
+    /// Parses a `Keep-Alive` header value, ignoring unknown and malformed parameters
+    pub fn parse(value: &str) -> KeepAlive {
+        let mut result = KeepAlive::default();
+        for param in value.split(',') {
+            let Some((name, value)) = param.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches('"');
+            match name.trim().to_ascii_lowercase().as_str() {
+                "timeout" => result.timeout = value.parse().ok().map(Duration::from_secs),
+                "max" => result.max = value.parse().ok(),
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+impl HttpMessage {
+    /// Gets the options of the `Connection` header, in lowercase
+    pub fn connection_options(&self) -> Vec<String> {
+        self.headers().get(CONNECTION)
+            .map(|options| options.split(',')
+                .map(|option| option.trim().to_ascii_lowercase())
+                .filter(|option| !option.is_empty())
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks the connection is kept open after this message: `Connection: close` closes it,
+    /// `Connection: keep-alive` keeps it and otherwise HTTP/1.0 connections are closed and later versions
+    /// kept (see [HttpMessage::version])
+    pub fn is_keep_alive(&self) -> bool {
+        let options = self.connection_options();
+        if options.iter().any(|option| option == "close") {
+            false
+        } else if options.iter().any(|option| option == "keep-alive") {
+            true
+        } else {
+            self.version() > HttpVersion::Http10
+        }
+    }
+
+    /// Gets the parameters of the `Keep-Alive` header
+    pub fn keep_alive(&self) -> Option<KeepAlive> {
+        self.headers().get(KEEP_ALIVE).map(KeepAlive::parse)
+    }
+
+    /// Removes the hop-by-hop headers, [HOP_BY_HOP_HEADERS] and the ones listed by the `Connection` header,
+    /// before forwarding the message
+    pub fn strip_hop_by_hop_headers(&mut self) -> &mut Self {
+        for option in self.connection_options() {
+            self.headers.remove(&option);
+        }
+        for name in HOP_BY_HOP_HEADERS {
+            self.headers.remove(name);
+        }
+        self
+    }
+}
+
+impl Response {
+    /// Removes the hop-by-hop headers, see [HttpMessage::strip_hop_by_hop_headers], and the proxy
+    /// authorization guides
+    pub fn strip_hop_by_hop_headers(&mut self) -> &mut Self {
+        self.base.strip_hop_by_hop_headers();
+        self.proxy_auth.clear();
+        self
+    }
+}
+
+/// Side of a [Connection]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Sends requests and receives responses
+    Client,
+    /// Receives requests and sends responses
+    Server
+}
+
+/// State of a [Connection]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum State {
+    /// More messages may be exchanged
+    Open,
+    /// The last request has been exchanged, its response is pending
+    Closing,
+    /// No more messages are exchanged, the transport must be closed
+    Closed,
+    /// The connection switched to another protocol or became a tunnel
+    Upgraded
+}
+
+/// Progress of the message being received, so that each parsing attempt only scans the new bytes
+#[derive(Default)]
+struct Progress {
+    /// Bytes of the buffer searched for the end of the head
+    scanned: usize,
+    /// Length of the head, once its end is received
+    head_len: Option<usize>,
+    /// Rest of the message awaited, once it has been parsed as incomplete
+    pending: Option<Pending>
+}
+
+/// Rest of an incomplete message
+enum Pending {
+    /// The message is complete with this length of the buffer
+    Until(usize),
+    /// Chunked body, with the offset of the next chunk and the size of the chunks received
+    Chunks { cursor: usize, size: usize }
+}
+
+/// Sans-I/O HTTP/1.1 connection.
+///
+/// The received bytes are fed with [Connection::receive] (and the end of the stream with
+/// [Connection::receive_eof]), then the messages parsed from them are taken in order. Messages are delimited
+/// by their `Content-Length` or chunked `Transfer-Encoding` framing, so several pipelined messages may be
+/// received at once or a message in several parts. The connection is kept open unless a message is not
+/// [keep-alive](HttpMessage::is_keep_alive), after its response the connection is closed.
+///
+/// The connection never writes: sending a message returns its bytes, the caller writes them to the transport.
+///
+/// Received message heads are limited to [DEFAULT_MAX_HEAD_SIZE] bytes (see [Connection::set_max_head_size]) and
+/// their bodies to the [default maximum body size](crate::default_max_body_size).
+///
+/// Requests and responses are exchanged in order: a server must answer the requests in the order they are
+/// received and a client matches the responses with its requests in the order they were sent. Interim
+/// responses (`1xx`) do not answer a request, but `101 Switching Protocols` and the successful responses to
+/// `CONNECT` requests upgrade the connection, then the remaining bytes belong to the new protocol, see
+/// [Connection::take_buffered].
+pub struct Connection {
+    role: Role,
+    state: State,
+    /// Bytes received and not parsed yet
+    buffer: Vec<u8>,
+    /// Progress of the message at the start of the buffer
+    progress: Progress,
+    max_head_size: usize,
+    /// The end of the stream has been received
+    eof: bool,
+    /// Methods of the requests sent (client) or received (server) and not answered yet
+    pending: VecDeque<HttpMethod>
+}
+
+impl Connection {
+    /// Creates the connection of a `role`
+    pub fn new(role: Role) -> Connection {
+        Connection {
+            role,
+            state: State::Open,
+            buffer: Vec::new(),
+            progress: Progress::default(),
+            max_head_size: DEFAULT_MAX_HEAD_SIZE,
+            eof: false,
+            pending: VecDeque::new()
+        }
+    }
+
+    /// Creates a client connection
+    pub fn client() -> Connection {
+        Connection::new(Role::Client)
+    }
+
+    /// Creates a server connection
+    pub fn server() -> Connection {
+        Connection::new(Role::Server)
+    }
+
+    /// Gets the role of the connection
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    /// Sets the maximum size of the received message heads. Larger heads fail with an `ErrorKind::InvalidData`
+    /// error and close the connection.
+    pub fn set_max_head_size(&mut self, bytes: usize) -> &mut Self {
+        self.max_head_size = bytes;
+        self
+    }
+
+    /// Gets the maximum size of the received message heads
+    pub fn max_head_size(&self) -> usize {
+        self.max_head_size
+    }
+
+    /// Checks more requests may be exchanged
+    pub fn is_open(&self) -> bool {
+        self.state == State::Open
+    }
+
+    /// Checks the connection is done, so the transport must be closed
+    pub fn is_closed(&self) -> bool {
+        self.state == State::Closed
+    }
+
+    /// Checks the connection switched to another protocol or became a tunnel
+    pub fn is_upgraded(&self) -> bool {
+        self.state == State::Upgraded
+    }
+
+    /// Number of requests sent (client) or received (server) that have not been answered yet. Requests
+    /// pending when a client connection is closed were not processed and may be retried.
+    pub fn pending_requests(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Adds bytes received from the transport
+    pub fn receive(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Notifies the end of the received stream: the peer closed the connection
+    pub fn receive_eof(&mut self) {
+        self.eof = true;
+    }
+
+    /// Takes the received bytes that have not been parsed, like the first bytes of the new protocol of an
+    /// upgraded connection
+    pub fn take_buffered(&mut self) -> Vec<u8> {
+        self.progress = Progress::default();
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Serializes a `request` to be sent by a client connection, see [wire::write_request].
+    ///
+    /// Fails with an `ErrorKind::InvalidInput` error if the connection is not a client one or the request
+    /// cannot be written, or with an `ErrorKind::NotConnected` error if the connection is not open.
+    pub fn send_request(&mut self, request: &Request) -> Result<Vec<u8>, Error> {
+        self.check_role(Role::Client)?;
+        if self.state != State::Open {
+            return Err(Error::new(ErrorKind::NotConnected, "Connection is not open"));
+        }
+        let data = wire::request_to_bytes(request)?;
+        self.pending.push_back(request.method());
+        if !request.is_keep_alive() {
+            self.state = State::Closing;
+        }
+        Ok(data)
+    }
+
+    /// Takes the next response received by a client connection, `None` if it is not complete yet or there
+    /// is no request waiting for it. Interim responses are returned too, before the final one.
+    ///
+    /// Responses without framing headers end with the connection, they are returned once
+    /// [the end of the stream](Connection::receive_eof) is received. Invalid or truncated responses close
+    /// the connection.
+    pub fn next_response(&mut self) -> Result<Option<Response>, Error> {
+        self.check_role(Role::Client)?;
+        let method = match self.pending.front() {
+            Some(method) if matches!(self.state, State::Open | State::Closing) => *method,
+            _ => return Ok(None)
+        };
+        let head_request = method == HttpMethod::HEAD;
+        let (response, end) = match self.parse(|data| wire::parse_response_at(data, head_request))? {
+            Some(parsed) => parsed,
+            None => return Ok(None)
+        };
+        let close_delimited = !head_request && wire::is_close_delimited(&response);
+        if close_delimited && !self.eof {
+            return Ok(None);
+        }
+        self.consume(end);
+
+        self.answer(method, &response, close_delimited);
+        Ok(Some(response))
+    }
+
+    /// Takes the next request received by a server connection, `None` if it is not complete yet or the
+    /// connection is not open. Invalid or truncated requests close the connection.
+    pub fn next_request(&mut self) -> Result<Option<Request>, Error> {
+        self.check_role(Role::Server)?;
+        if self.state != State::Open {
+            return Ok(None);
+        }
+        // Empty lines before a request are ignored (RFC 9112, section 2.2)
+        let blank = self.buffer.iter().position(|c| *c != b'\r' && *c != b'\n').unwrap_or(self.buffer.len());
+        if blank > 0 {
+            self.consume(blank);
+        }
+        if self.buffer.is_empty() {
+            if self.eof {
+                self.state = State::Closed;
+            }
+            return Ok(None);
+        }
+        let (request, end) = match self.parse(wire::parse_request_at)? {
+            Some(parsed) => parsed,
+            None => return Ok(None)
+        };
+        self.consume(end);
+        self.pending.push_back(request.method());
+        if !request.is_keep_alive() {
+            self.state = State::Closing;
+        }
+        Ok(Some(request))
+    }
+
+    /// Serializes a `response` to be sent by a server connection, answering the oldest request received and
+    /// not answered yet. Responses to `HEAD` requests are sent without body. Interim responses do not answer
+    /// the request, but `101 Switching Protocols` and the successful responses to `CONNECT` requests upgrade
+    /// the connection.
+    ///
+    /// Fails with an `ErrorKind::InvalidInput` error if the connection is not a server one, there is no
+    /// request to answer or the response cannot be written, or with an `ErrorKind::NotConnected` error if
+    /// the connection is closed or upgraded.
+    pub fn send_response(&mut self, response: &Response) -> Result<Vec<u8>, Error> {
+        self.check_role(Role::Server)?;
+        if !matches!(self.state, State::Open | State::Closing) {
+            return Err(Error::new(ErrorKind::NotConnected, "Connection is closed"));
+        }
+        let method = *self.pending.front()
+            .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "No request to respond"))?;
+        let data = match method {
+            HttpMethod::HEAD => {
+                response.check_framing().map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))?;
+                wire::format_response_head(response)?.into_bytes()
+            },
+            _ => wire::response_to_bytes(response)?
+        };
+
+        self.answer(method, response, false);
+        Ok(data)
+    }
+
+    /// Updates the state with the `response` to the oldest pending request, of `method`
+    fn answer(&mut self, method: HttpMethod, response: &Response, close: bool) {
+        let status = response.status_code();
+        if status == HTTP_101_SWITCHING_PROTOCOLS || (method == HttpMethod::CONNECT && (200..300).contains(&status)) {
+            self.pending.pop_front();
+            self.state = State::Upgraded;
+        } else if !wire::is_interim(status) {
+            self.pending.pop_front();
+            if close || !response.is_keep_alive() || (self.state == State::Closing && self.pending.is_empty()) {
+                self.state = State::Closed;
+            }
+        }
+    }
+
+    fn check_role(&self, role: Role) -> Result<(), Error> {
+        if self.role != role {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Not a {:?} connection", role)));
+        }
+        Ok(())
+    }
+
+    /// Removes the first `length` bytes of the buffer, with the progress of the message they held
+    fn consume(&mut self, length: usize) {
+        self.buffer.drain(..length);
+        self.progress = Progress::default();
+    }
+
+    /// Parses the message at the start of the buffer, `None` if it is incomplete and more data may be received.
+    /// The message is only parsed once the bytes it awaits are received. Parsing errors close the connection.
+    fn parse<T, F>(&mut self, parse: F) -> Result<Option<(T, usize)>, Error>
+    where F: FnOnce(&[u8]) -> Result<(T, usize), Error> {
+        let result = match self.eof || self.is_ready()? {
+            true => parse(&self.buffer),
+            false => return Ok(None)
+        };
+        match result {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof && !self.eof => {
+                if self.progress.pending.is_none() {
+                    self.progress.pending = Some(self.pending_body());
+                }
+                Ok(None)
+            },
+            Err(e) => {
+                self.state = State::Closed;
+                Err(e)
+            }
+        }
+    }
+
+    /// Checks the message at the start of the buffer may be complete, scanning only the bytes received since the
+    /// last check. Heads larger than the maximum close the connection.
+    fn is_ready(&mut self) -> Result<bool, Error> {
+        let head_len = match self.progress.head_len {
+            Some(head_len) => head_len,
+            None => {
+                let from = self.progress.scanned.saturating_sub(3);
+                match self.buffer[from..].windows(4).position(|w| w == b"\r\n\r\n") {
+                    Some(pos) => *self.progress.head_len.insert(from + pos + 4),
+                    None => {
+                        self.progress.scanned = self.buffer.len();
+                        self.check_head_size(self.buffer.len())?;
+                        return Ok(false);
+                    }
+                }
+            }
+        };
+        self.check_head_size(head_len)?;
+
+        match self.progress.pending {
+            None => Ok(true),
+            Some(Pending::Until(end)) => Ok(self.buffer.len() >= end),
+            Some(Pending::Chunks { ref mut cursor, ref mut size }) => loop {
+                let limit = crate::default_max_body_size();
+                match wire::next_chunk(&self.buffer[*cursor..], limit.saturating_sub(*size)) {
+                    Ok((Some(chunk), read)) => {
+                        *cursor += read;
+                        *size += chunk.len();
+                    },
+                    Ok((None, read)) => return Ok(!matches!(wire::trailer_len(&self.buffer[*cursor + read..]),
+                        Err(ref e) if e.kind() == ErrorKind::UnexpectedEof)),
+                    Err(e) => return Ok(e.kind() != ErrorKind::UnexpectedEof)
+                }
+            }
+        }
+    }
+
+    fn check_head_size(&mut self, size: usize) -> Result<(), Error> {
+        if size > self.max_head_size {
+            self.state = State::Closed;
+            return Err(Error::new(ErrorKind::InvalidData,
+                format!("Message head exceeds the maximum of {} bytes", self.max_head_size)));
+        }
+        Ok(())
+    }
+
+    /// Rest of the incomplete message at the start of the buffer, any new byte if its framing is not known
+    fn pending_body(&self) -> Pending {
+        match wire::pending_body(&self.buffer) {
+            Ok(Some(wire::PendingBody::Length(end))) => Pending::Until(end),
+            Ok(Some(wire::PendingBody::Chunked(start))) => Pending::Chunks { cursor: start, size: 0 },
+            _ => Pending::Until(self.buffer.len() + 1)
+        }
+    }
+}
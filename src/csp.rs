@@ -0,0 +1,297 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Content Security Policy (CSP Level 3)
+//!
+//! ```
+//! use wrequest::{Response, HTTP_200_OK};
+//! use wrequest::csp::{Csp, Source::{self, Self_, UnsafeInline}};
+//!
+//! let policy = Csp::new()
+//!     .default_src([Self_])
+//!     .script_src([Self_, Source::from("https://cdn.example.com")])
+//!     .style_src([Self_, UnsafeInline])
+//!     .upgrade_insecure_requests()
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(policy, "default-src 'self'; script-src 'self' https://cdn.example.com; \
+//!                     style-src 'self' 'unsafe-inline'; upgrade-insecure-requests");
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.insert_header("Content-Security-Policy", policy);
+//!
+//! let parsed = response.content_security_policy().unwrap();
+//! assert!(parsed.sources("style-src").unwrap().contains(&UnsafeInline));
+//! ```
+
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use crate::Response;
+
+/// `Content-Security-Policy` header name
+pub const CONTENT_SECURITY_POLICY: &str = "Content-Security-Policy";
+
+/// Source expression of a fetch directive
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Source {
+    /// `'self'`, the origin of the document
+    Self_,
+    /// `'none'`, no source is allowed
+    None,
+    /// `'unsafe-inline'`
+    UnsafeInline,
+    /// `'unsafe-eval'`
+    UnsafeEval,
+    /// `'unsafe-hashes'`
+    UnsafeHashes,
+    /// `'wasm-unsafe-eval'`
+    WasmUnsafeEval,
+    /// `'strict-dynamic'`
+    StrictDynamic,
+    /// `'report-sample'`
+    ReportSample,
+    /// `'nonce-<value>'`
+    Nonce(String),
+    /// `'<algorithm>-<value>'`, like `'sha256-...'`
+    Hash(String, String),
+    /// Host or scheme source, like `https://cdn.example.com`, `*.example.com` or `data:`
+    Host(String)
+}
+
+impl Source {
+    /// Checks the source can be rendered without breaking the policy syntax
+    fn is_valid(&self) -> bool {
+        let valid = |value: &str| !value.is_empty()
+            && value.bytes().all(|b| b.is_ascii_graphic() && b != b';' && b != b',' && b != b'\'');
+        match self {
+            Source::Nonce(value) => valid(value),
+            Source::Hash(algorithm, value) => valid(algorithm) && valid(value),
+            Source::Host(value) => valid(value),
+            _ => true
+        }
+    }
+}
+
+impl From<&str> for Source {
+    /// Parses a source expression, unknown quoted keywords are kept as hosts
+    fn from(value: &str) -> Self {
+        let keyword = match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+            Some(keyword) => keyword,
+            None => return Source::Host(value.to_string())
+        };
+        match keyword.to_ascii_lowercase().as_str() {
+            "self" => Source::Self_,
+            "none" => Source::None,
+            "unsafe-inline" => Source::UnsafeInline,
+            "unsafe-eval" => Source::UnsafeEval,
+            "unsafe-hashes" => Source::UnsafeHashes,
+            "wasm-unsafe-eval" => Source::WasmUnsafeEval,
+            "strict-dynamic" => Source::StrictDynamic,
+            "report-sample" => Source::ReportSample,
+            lower => match lower.split_once('-') {
+                Some(("nonce", _)) => Source::Nonce(keyword[6..].to_string()),
+                Some((algorithm @ ("sha256" | "sha384" | "sha512"), _)) =>
+                    Source::Hash(algorithm.to_string(), keyword[algorithm.len() + 1..].to_string()),
+                _ => Source::Host(value.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Self_ => write!(f, "'self'"),
+            Source::None => write!(f, "'none'"),
+            Source::UnsafeInline => write!(f, "'unsafe-inline'"),
+            Source::UnsafeEval => write!(f, "'unsafe-eval'"),
+            Source::UnsafeHashes => write!(f, "'unsafe-hashes'"),
+            Source::WasmUnsafeEval => write!(f, "'wasm-unsafe-eval'"),
+            Source::StrictDynamic => write!(f, "'strict-dynamic'"),
+            Source::ReportSample => write!(f, "'report-sample'"),
+            Source::Nonce(value) => write!(f, "'nonce-{}'", value),
+            Source::Hash(algorithm, value) => write!(f, "'{}-{}'", algorithm, value),
+            Source::Host(value) => write!(f, "{}", value)
+        }
+    }
+}
+
+/// Content Security Policy builder and parser
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Csp {
+    /// Directives in order, names in lowercase
+    directives: Vec<(String, Vec<Source>)>
+}
+
+impl Csp {
+    /// Constructor of an empty policy
+    pub fn new() -> Csp {
+        Csp::default()
+    }
+
+    /// Sets a directive, replacing a previous one with the same name
+    pub fn directive<I, S>(mut self, name: &str, sources: I) -> Self
+    where I: IntoIterator<Item = S>,
+          S: Into<Source> {
+        let name = name.to_ascii_lowercase();
+        let sources: Vec<Source> = sources.into_iter().map(Into::into).collect();
+        match self.directives.iter_mut().find(|(n, _)| *n == name) {
+            Some(directive) => directive.1 = sources,
+            None => self.directives.push((name, sources))
+        }
+        self
+    }
+
+    /// Sets the `default-src` directive
+    pub fn default_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("default-src", sources)
+    }
+
+    /// Sets the `script-src` directive
+    pub fn script_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("script-src", sources)
+    }
+
+    /// Sets the `style-src` directive
+    pub fn style_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("style-src", sources)
+    }
+
+    /// Sets the `img-src` directive
+    pub fn img_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("img-src", sources)
+    }
+
+    /// Sets the `connect-src` directive
+    pub fn connect_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("connect-src", sources)
+    }
+
+    /// Sets the `font-src` directive
+    pub fn font_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("font-src", sources)
+    }
+
+    /// Sets the `object-src` directive
+    pub fn object_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("object-src", sources)
+    }
+
+    /// Sets the `media-src` directive
+    pub fn media_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("media-src", sources)
+    }
+
+    /// Sets the `frame-src` directive
+    pub fn frame_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("frame-src", sources)
+    }
+
+    /// Sets the `worker-src` directive
+    pub fn worker_src<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("worker-src", sources)
+    }
+
+    /// Sets the `frame-ancestors` directive
+    pub fn frame_ancestors<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("frame-ancestors", sources)
+    }
+
+    /// Sets the `base-uri` directive
+    pub fn base_uri<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("base-uri", sources)
+    }
+
+    /// Sets the `form-action` directive
+    pub fn form_action<I: IntoIterator<Item = S>, S: Into<Source>>(self, sources: I) -> Self {
+        self.directive("form-action", sources)
+    }
+
+    /// Sets the `upgrade-insecure-requests` directive
+    pub fn upgrade_insecure_requests(self) -> Self {
+        self.directive("upgrade-insecure-requests", Vec::<Source>::new())
+    }
+
+    /// Sets the `report-to` directive with a reporting endpoint group
+    pub fn report_to(self, group: &str) -> Self {
+        self.directive("report-to", [Source::Host(group.to_string())])
+    }
+
+    /// Gets the sources of a directive, if it is set
+    pub fn sources(&self, name: &str) -> Option<&[Source]> {
+        self.directives.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, sources)| sources.as_slice())
+    }
+
+    /// Gets the directive names, in order
+    pub fn directives(&self) -> impl Iterator<Item = &str> {
+        self.directives.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Renders the policy as a header value.
+    /// Returns an error if a directive name or source would break the policy syntax.
+    pub fn build(&self) -> Result<String, Error> {
+        for (name, sources) in self.directives.iter() {
+            if name.is_empty() || !name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-') {
+                return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid CSP directive name {}", name)));
+            }
+            if let Some(source) = sources.iter().find(|source| !source.is_valid()) {
+                return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid CSP source {:?} in {}", source, name)));
+            }
+        }
+        Ok(self.to_string())
+    }
+
+    /// Parses a policy. Directive names are case-insensitive and only the first occurrence of each
+    /// directive is used, as browsers do.
+    pub fn parse(value: &str) -> Csp {
+        let mut result = Csp::new();
+        for directive in value.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let name = match tokens.next() {
+                Some(name) => name.to_ascii_lowercase(),
+                None => continue
+            };
+            if result.sources(&name).is_none() {
+                result.directives.push((name, tokens.map(Source::from).collect()));
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for Csp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, sources)) in self.directives.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", name)?;
+            for source in sources.iter() {
+                write!(f, " {}", source)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Response {
+    /// Parses the `Content-Security-Policy` header, if any
+    pub fn content_security_policy(&self) -> Option<Csp> {
+        self.headers().get(CONTENT_SECURITY_POLICY).map(Csp::parse)
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Import of curl command lines
+
+use std::io::{Error, ErrorKind};
+use std::time::{SystemTime, UNIX_EPOCH};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use crate::{HttpMethod, Request, uri, CONTENT_TYPE, ACCEPT, APPLICATION_JSON};
+
+/// `Content-Type` of curl `-d` data when it is not set explicitly
+const FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+
+/// Options without argument that do not change the request
+const IGNORED_FLAGS: &[&str] = &["-s", "--silent", "-S", "--show-error", "-L", "--location", "-k", "--insecure",
+    "-v", "--verbose", "-i", "--include", "-f", "--fail", "--compressed", "-N", "--no-buffer", "-g", "--globoff"];
+
+/// Options with an argument that do not change the request
+const IGNORED_OPTIONS: &[&str] = &["-o", "--output", "-m", "--max-time", "--connect-timeout", "--retry", "-w",
+    "--write-out", "-x", "--proxy"];
+
+/// Short options that take an argument, which can be attached (`-XPOST`)
+const SHORT_WITH_ARG: &[u8] = b"XHdubFAeomwx";
+
+impl Request {
+    /// Parses a curl command line into a `Request`.
+    ///
+    /// Supported options are the method (`-X`, `-I`, `-G`), the URL, headers (`-H`, `-A`, `-e`),
+    /// data (`-d`, `--data-raw`, `--data-binary`, `--data-urlencode`, `--json`), basic credentials (`-u`),
+    /// cookies (`-b`) and form fields (`-F`). Options reading files (`@file`) are not supported.
+    /// The URL query string is decoded into the request params.
+    ///
+    /// ```
+    /// use wrequest::{Request, HttpMethod};
+    ///
+    /// let request = Request::from_curl(r#"curl -X PUT 'https://service.com/users/1?lang=en' \
+    ///     -H 'Content-Type: application/json' \
+    ///     -d '{"name": "John"}'"#).unwrap();
+    ///
+    /// assert_eq!(request.method(), HttpMethod::PUT);
+    /// assert_eq!(request.url(), "https://service.com/users/1");
+    /// assert_eq!(request.params().get("lang"), Some("en"));
+    /// assert_eq!(request.json().unwrap()["name"], "John");
+    /// ```
+    pub fn from_curl(command: &str) -> Result<Request, Error> {
+        let tokens = tokenize(command)?;
+        let mut args = tokens.iter().map(String::as_str).peekable();
+        if args.peek() == Some(&"curl") {
+            args.next();
+        }
+
+        let mut method: Option<HttpMethod> = None;
+        let mut url: Option<&str> = None;
+        let mut headers: Vec<(String, String)> = Vec::new();
+        let mut cookies: Vec<(String, String)> = Vec::new();
+        let mut data: Vec<String> = Vec::new();
+        let mut form: Vec<(String, String)> = Vec::new();
+        let mut get = false;
+        let mut head = false;
+
+        let mut options: Vec<(String, Option<&str>)> = Vec::new();
+        while let Some(arg) = args.next() {
+            if !arg.starts_with('-') || arg == "-" {
+                if url.is_some() {
+                    return Err(invalid(format!("Unexpected argument {}", arg)));
+                }
+                url = Some(arg);
+                continue;
+            }
+            options.clear();
+            if !arg.starts_with("--") && arg.len() > 2 {
+                // Short options cluster (`-sSL`) or attached argument (`-XPOST`)
+                let bytes = arg.as_bytes();
+                if SHORT_WITH_ARG.contains(&bytes[1]) {
+                    options.push((arg[..2].to_string(), Some(&arg[2..])));
+                } else {
+                    for c in arg[1..].chars() {
+                        options.push((format!("-{}", c), None));
+                    }
+                }
+            } else {
+                options.push((arg.to_string(), None));
+            }
+
+            for (option, attached) in options.iter() {
+                let option = option.as_str();
+                if IGNORED_FLAGS.contains(&option) {
+                    continue;
+                }
+                match option {
+                    "-I" | "--head" => head = true,
+                    "-G" | "--get" => get = true,
+                    _ => {
+                        let value = match attached {
+                            Some(value) => *value,
+                            None => args.next().ok_or_else(|| invalid(format!("Missing argument of {}", option)))?
+                        };
+                        if IGNORED_OPTIONS.contains(&option) {
+                            continue;
+                        }
+                        match option {
+                            "--url" => url = Some(value),
+                            "-X" | "--request" => method = Some(value.parse()?),
+                            "-H" | "--header" => {
+                                let pos = value.find(':').ok_or_else(|| invalid(format!("Invalid header {}", value)))?;
+                                headers.push((value[..pos].trim().to_string(), value[pos + 1..].trim().to_string()));
+                            },
+                            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value.to_string())),
+                            "-e" | "--referer" => headers.push(("Referer".to_string(), value.to_string())),
+                            "-u" | "--user" => {
+                                let credentials = if value.contains(':') { value.to_string() } else { format!("{}:", value) };
+                                headers.push(("Authorization".to_string(), format!("Basic {}", BASE64.encode(credentials))));
+                            },
+                            "-b" | "--cookie" => {
+                                if !value.contains('=') {
+                                    return Err(invalid("Cookie files are not supported"));
+                                }
+                                for pair in value.split(';').map(str::trim).filter(|pair| !pair.is_empty()) {
+                                    let (name, cookie) = pair.split_at(pair.find('=').unwrap_or(pair.len()));
+                                    cookies.push((name.to_string(), cookie.trim_start_matches('=').to_string()));
+                                }
+                            },
+                            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                                if value.starts_with('@') {
+                                    return Err(invalid("Data files are not supported"));
+                                }
+                                data.push(value.to_string());
+                            },
+                            "--data-raw" => data.push(value.to_string()),
+                            "--data-urlencode" => data.push(encode_data(value)?),
+                            "--json" => {
+                                if value.starts_with('@') {
+                                    return Err(invalid("Data files are not supported"));
+                                }
+                                data.push(value.to_string());
+                                headers.push((CONTENT_TYPE.to_string(), APPLICATION_JSON.to_string()));
+                                headers.push((ACCEPT.to_string(), APPLICATION_JSON.to_string()));
+                            },
+                            "-F" | "--form" => {
+                                let pos = value.find('=').ok_or_else(|| invalid(format!("Invalid form field {}", value)))?;
+                                let field = &value[pos + 1..];
+                                if field.starts_with('@') || field.starts_with('<') {
+                                    return Err(invalid("Form files are not supported"));
+                                }
+                                form.push((value[..pos].to_string(), field.to_string()));
+                            },
+                            _ => return Err(invalid(format!("Unsupported curl option {}", option)))
+                        }
+                    }
+                }
+            }
+        }
+
+        let url = url.ok_or_else(|| invalid("Missing URL"))?;
+        if !data.is_empty() && !form.is_empty() {
+            return Err(invalid("Data and form fields cannot be mixed"));
+        }
+
+        let method = method.unwrap_or(if head {
+            HttpMethod::HEAD
+        } else if (data.is_empty() && form.is_empty()) || get {
+            HttpMethod::GET
+        } else {
+            HttpMethod::POST
+        });
+
+        let (target, query) = uri::split_query(url);
+        let mut request = Request::new(method, target);
+        if let Some(query) = query {
+            for (name, value) in uri::parse_query(query) {
+                request.insert_param(name, value);
+            }
+        }
+        for (name, value) in headers {
+            request.insert_header(name, value);
+        }
+        for (name, value) in cookies {
+            request.insert_cookie(name, value);
+        }
+
+        if get {
+            for (name, value) in uri::parse_query(&data.join("&")) {
+                request.insert_param(name, value);
+            }
+        } else if !data.is_empty() {
+            if !request.headers().contains_key(CONTENT_TYPE) {
+                request.insert_header(CONTENT_TYPE, FORM_URLENCODED);
+            }
+            request.set_body(data.join("&").into_bytes())?;
+        } else if !form.is_empty() {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+            let boundary = format!("------------------------wrequest{:016x}", nanos as u64);
+            let mut body = Vec::new();
+            for (name, value) in form.iter() {
+                body.extend_from_slice(format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                    boundary, name, value).as_bytes());
+            }
+            body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+            request.insert_header(CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary));
+            request.set_body(body)?;
+        }
+
+        Ok(request)
+    }
+}
+
+/// Encodes a `--data-urlencode` argument: `content`, `=content` or `name=content`
+fn encode_data(value: &str) -> Result<String, Error> {
+    match value.find('=') {
+        Some(0) => Ok(uri::percent_encode(&value[1..])),
+        Some(pos) => Ok(format!("{}={}", &value[..pos], uri::percent_encode(&value[pos + 1..]))),
+        None if value.contains('@') => Err(invalid("Data files are not supported")),
+        None => Ok(uri::percent_encode(value))
+    }
+}
+
+/// Splits a command line into words following the POSIX shell quoting rules
+fn tokenize(command: &str) -> Result<Vec<String>, Error> {
+    let mut result = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(q) => current.push(q),
+                        None => return Err(invalid("Unterminated single quote"))
+                    }
+                }
+            },
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('r') => current.push('\r'),
+                            Some('t') => current.push('\t'),
+                            Some(e) => current.push(e),
+                            None => return Err(invalid("Unterminated quote"))
+                        },
+                        Some(q) => current.push(q),
+                        None => return Err(invalid("Unterminated quote"))
+                    }
+                }
+            },
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(e @ ('"' | '\\' | '$' | '`')) => current.push(e),
+                            Some('\n') => {},
+                            Some(e) => {
+                                current.push('\\');
+                                current.push(e);
+                            },
+                            None => return Err(invalid("Unterminated double quote"))
+                        },
+                        Some(q) => current.push(q),
+                        None => return Err(invalid("Unterminated double quote"))
+                    }
+                }
+            },
+            '\\' => match chars.next() {
+                Some('\n') => {},
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                },
+                Some(e) => {
+                    in_word = true;
+                    current.push(e);
+                },
+                None => {}
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    result.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            },
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+    if in_word {
+        result.push(current);
+    }
+    Ok(result)
+}
+
+fn invalid<S: Into<String>>(message: S) -> Error {
+    Error::new(ErrorKind::InvalidInput, message.into())
+}
@@ -0,0 +1,153 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Default headers, params and cookies shared by many requests
+
+use std::borrow::Cow;
+use crate::{HeaderMap, HttpMethod, KeyValueMap, Request};
+use crate::middleware::Middleware;
+
+/// Headers, params and cookies applied to every request created through it, like `User-Agent`, `Accept` or
+/// authorization headers. Values set on a request take precedence over the defaults.
+///
+/// `Defaults` is also a [Middleware], so it can fill in requests created elsewhere.
+///
+/// ```
+/// use wrequest::Defaults;
+///
+/// let defaults = Defaults::new()
+///     .with_header("User-Agent", "crawler/1.0")
+///     .with_header("Accept", "application/json");
+///
+/// let mut request = defaults.get("https://service.com/feed");
+/// request.insert_header("Accept", "application/atom+xml");
+///
+/// assert_eq!(request.headers().get("User-Agent"), Some("crawler/1.0"));
+/// assert_eq!(request.headers().get("Accept"), Some("application/atom+xml"));
+/// ```
+#[derive(Clone, Default)]
+pub struct Defaults {
+    headers: HeaderMap,
+    params: KeyValueMap,
+    cookies: KeyValueMap
+}
+
+impl Defaults {
+    /// Constructor without defaults
+    pub fn new() -> Defaults {
+        Defaults::default()
+    }
+
+    /// Adds a default header
+    pub fn with_header<K, V>(mut self, key: K, value: V) -> Self
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        self.headers.insert(key, value);
+        self
+    }
+
+    /// Adds a default param
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> Self
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        self.params.insert(key, value);
+        self
+    }
+
+    /// Adds a default cookie
+    pub fn with_cookie<K, V>(mut self, key: K, value: V) -> Self
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        self.cookies.insert(key, value);
+        self
+    }
+
+    /// Gets the default headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Gets the default params
+    pub fn params(&self) -> &KeyValueMap {
+        &self.params
+    }
+
+    /// Gets the default cookies
+    pub fn cookies(&self) -> &KeyValueMap {
+        &self.cookies
+    }
+
+    /// Adds the defaults the request does not have yet
+    pub fn apply(&self, request: &mut Request) {
+        for (key, value) in self.headers.iter() {
+            if !request.headers().contains_key(key) {
+                request.insert_header(key.to_string(), value.to_string());
+            }
+        }
+        for (key, value) in self.params.iter() {
+            if !request.params().contains_key(key) {
+                request.insert_param(key.to_string(), value.to_string());
+            }
+        }
+        for (key, value) in self.cookies.iter() {
+            if !request.cookies().contains_key(key) {
+                request.insert_cookie(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    /// Creates a request with the defaults
+    pub fn request<S: Into<String>>(&self, method: HttpMethod, url: S) -> Request {
+        let mut request = Request::new(method, url);
+        self.apply(&mut request);
+        request
+    }
+
+    /// Creates a `GET` request with the defaults
+    pub fn get<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::GET, url)
+    }
+
+    /// Creates a `HEAD` request with the defaults
+    pub fn head<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::HEAD, url)
+    }
+
+    /// Creates a `POST` request with the defaults
+    pub fn post<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::POST, url)
+    }
+
+    /// Creates a `PUT` request with the defaults
+    pub fn put<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::PUT, url)
+    }
+
+    /// Creates a `PATCH` request with the defaults
+    pub fn patch<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::PATCH, url)
+    }
+
+    /// Creates a `DELETE` request with the defaults
+    pub fn delete<S: Into<String>>(&self, url: S) -> Request {
+        self.request(HttpMethod::DELETE, url)
+    }
+}
+
+impl Middleware for Defaults {
+    fn on_request(&self, request: &mut Request) {
+        self.apply(request);
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Text encodings of the bodies
+
+use std::borrow::Cow;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use crate::{HttpMessage, CONTENT_TYPE};
+
+/// Text encoding of a body, from its byte order mark (BOM) or the `charset` parameter of its `Content-Type`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TextEncoding {
+    /// UTF-8, the default encoding
+    Utf8,
+    /// UTF-16, little-endian
+    Utf16Le,
+    /// UTF-16, big-endian
+    Utf16Be,
+    /// ISO-8859-1, also used for US-ASCII
+    Latin1
+}
+
+impl TextEncoding {
+    /// Detects the encoding from the byte order mark of `data`, returning it with the length of the mark
+    pub fn from_bom(data: &[u8]) -> Option<(TextEncoding, usize)> {
+        if data.starts_with(b"\xef\xbb\xbf") {
+            Some((Self::Utf8, 3))
+        } else if data.starts_with(b"\xff\xfe") {
+            Some((Self::Utf16Le, 2))
+        } else if data.starts_with(b"\xfe\xff") {
+            Some((Self::Utf16Be, 2))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the encoding of a `charset` label, case-insensitive. `utf-16` without byte order mark is
+    /// big-endian. Returns `None` for unsupported charsets.
+    pub fn from_label(label: &str) -> Option<TextEncoding> {
+        match label.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Some(Self::Utf8),
+            "utf-16le" => Some(Self::Utf16Le),
+            "utf-16be" | "utf-16" => Some(Self::Utf16Be),
+            "iso-8859-1" | "latin1" | "us-ascii" => Some(Self::Latin1),
+            _ => None
+        }
+    }
+
+    /// Decodes `data`, without byte order mark. Returns an `ErrorKind::InvalidData` error if it is malformed.
+    pub fn decode<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, str>, Error> {
+        match self {
+            Self::Utf8 => std::str::from_utf8(data)
+                .map(Cow::Borrowed)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+            Self::Utf16Le | Self::Utf16Be => {
+                if !data.len().is_multiple_of(2) {
+                    return Err(Error::new(ErrorKind::InvalidData, "UTF-16 text with an odd number of bytes"));
+                }
+                let units = data.chunks_exact(2).map(|unit| match self {
+                    Self::Utf16Le => u16::from_le_bytes([unit[0], unit[1]]),
+                    _ => u16::from_be_bytes([unit[0], unit[1]])
+                });
+                char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .map(Cow::Owned)
+                    .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+            },
+            Self::Latin1 => Ok(data.iter().map(|byte| *byte as char).collect::<String>().into())
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}",
+               match self {
+                   Self::Utf8 => "UTF-8",
+                   Self::Utf16Le => "UTF-16LE",
+                   Self::Utf16Be => "UTF-16BE",
+                   Self::Latin1 => "ISO-8859-1"
+               }
+        )
+    }
+}
+
+impl HttpMessage {
+    /// Gets the `charset` parameter of the `Content-Type` header, if any
+    fn charset(&self) -> Option<&str> {
+        self.headers().get(CONTENT_TYPE)?
+            .split(';')
+            .skip(1)
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Detects the text encoding of the body: the byte order mark if any, which takes precedence, or the
+    /// `charset` parameter of the `Content-Type` header, UTF-8 by default and for unsupported charsets.
+    /// Returns `None` if there is no body.
+    ///
+    /// ```
+    /// use wrequest::{Response, TextEncoding, HTTP_200_OK};
+    ///
+    /// let mut response = Response::new(HTTP_200_OK);
+    /// response.insert_header("Content-Type", "application/json")
+    ///         .set_body(b"\xef\xbb\xbf{\"id\": 1}".to_vec()).unwrap();
+    /// assert_eq!(response.detected_encoding(), Some(TextEncoding::Utf8));
+    /// assert_eq!(response.text().unwrap(), "{\"id\": 1}");
+    /// assert_eq!(response.json().unwrap()["id"], 1);
+    ///
+    /// response.insert_header("Content-Type", "text/plain; charset=ISO-8859-1")
+    ///         .set_body(b"caf\xe9".to_vec()).unwrap();
+    /// assert_eq!(response.detected_encoding(), Some(TextEncoding::Latin1));
+    /// assert_eq!(response.text().unwrap(), "café");
+    /// ```
+    pub fn detected_encoding(&self) -> Option<TextEncoding> {
+        let body = self.body()?;
+        Some(TextEncoding::from_bom(body).map(|(encoding, _)| encoding)
+            .or_else(|| self.charset().and_then(TextEncoding::from_label))
+            .unwrap_or(TextEncoding::Utf8))
+    }
+
+    /// Decodes the body as text with the [detected encoding](HttpMessage::detected_encoding), without byte
+    /// order mark. Returns an empty string if there is no body and an `ErrorKind::InvalidData` error if the
+    /// body is malformed.
+    pub fn text(&self) -> Result<Cow<'_, str>, Error> {
+        let body = match self.body() {
+            Some(body) => body,
+            None => return Ok(Cow::Borrowed(""))
+        };
+        match TextEncoding::from_bom(body) {
+            Some((encoding, length)) => encoding.decode(&body[length..]),
+            None => self.detected_encoding().unwrap_or(TextEncoding::Utf8).decode(body)
+        }
+    }
+
+    /// Gets the body in UTF-8, without byte order mark, decoding it only if it is in another encoding
+    pub(crate) fn utf8_body(&self) -> Result<Cow<'_, [u8]>, Error> {
+        let body = self.body().unwrap_or(&[]);
+        match (TextEncoding::from_bom(body), self.detected_encoding()) {
+            (Some((TextEncoding::Utf8, length)), _) => Ok(Cow::Borrowed(&body[length..])),
+            (None, None | Some(TextEncoding::Utf8)) => Ok(Cow::Borrowed(body)),
+            _ => Ok(match self.text()? {
+                Cow::Borrowed(text) => Cow::Borrowed(text.as_bytes()),
+                Cow::Owned(text) => Cow::Owned(text.into_bytes())
+            })
+        }
+    }
+}
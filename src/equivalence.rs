@@ -0,0 +1,142 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Semantic comparison of messages
+
+use crate::{HeaderMap, HttpMessage, KeyValueMap, Request, Response, set_cookie_header, uri};
+
+/// Options of the semantic comparison of messages, see [Request::equivalent] and [Response::equivalent].
+///
+/// Header order and header name case are always ignored.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct EquivalenceOptions {
+    /// Headers ignored in the comparison, names are case-insensitive
+    pub ignore_headers: Vec<String>,
+    /// Compare JSON bodies semantically, ignoring whitespace and key order
+    pub json_bodies: bool,
+    /// Compare request URLs normalized, see [Request::normalize]
+    pub normalize_urls: bool
+}
+
+impl Default for EquivalenceOptions {
+    /// Ignores the volatile `Date` and `User-Agent` headers, bodies are compared byte-for-byte
+    fn default() -> Self {
+        EquivalenceOptions {
+            ignore_headers: vec!["Date".to_string(), "User-Agent".to_string()],
+            json_bodies: false,
+            normalize_urls: false
+        }
+    }
+}
+
+impl EquivalenceOptions {
+    /// Ignores a header
+    pub fn with_ignored_header(mut self, name: &str) -> Self {
+        self.ignore_headers.push(name.to_string());
+        self
+    }
+
+    /// Compares or not JSON bodies semantically
+    pub fn with_json_bodies(mut self, json_bodies: bool) -> Self {
+        self.json_bodies = json_bodies;
+        self
+    }
+
+    /// Compares or not request URLs normalized
+    pub fn with_normalized_urls(mut self, normalize_urls: bool) -> Self {
+        self.normalize_urls = normalize_urls;
+        self
+    }
+
+    fn messages_equivalent(&self, first: &HttpMessage, second: &HttpMessage) -> bool {
+        headers_equivalent(first.headers(), second.headers(), &self.ignore_headers)
+            && self.bodies_equivalent(first.body(), second.body())
+    }
+
+    fn bodies_equivalent(&self, first: Option<&[u8]>, second: Option<&[u8]>) -> bool {
+        if first == second {
+            return true;
+        }
+        if !self.json_bodies {
+            return false;
+        }
+        let parse = |body: Option<&[u8]>| body
+            .and_then(|body| std::str::from_utf8(body).ok())
+            .and_then(|body| json::parse(body).ok());
+        match (parse(first), parse(second)) {
+            (Some(first), Some(second)) => first == second,
+            _ => false
+        }
+    }
+}
+
+/// Checks two header maps have the same headers, but the `ignored` ones, regardless of order and name case
+pub(crate) fn headers_equivalent(first: &HeaderMap, second: &HeaderMap, ignored: &[String]) -> bool {
+    let ignored = |name: &str| ignored.iter().any(|h| h.eq_ignore_ascii_case(name));
+    first.iter().filter(|(name, _)| !ignored(name)).all(|(name, value)| second.get(name) == Some(value))
+        && second.iter().filter(|(name, _)| !ignored(name)).all(|(name, _)| first.contains_key(name))
+}
+
+/// Checks two key-value maps have the same entries, but the `ignored` keys
+pub(crate) fn key_values_equivalent(first: &KeyValueMap, second: &KeyValueMap, ignored: &[String]) -> bool {
+    let ignored = |key: &str| ignored.iter().any(|k| k == key);
+    first.iter().filter(|(key, _)| !ignored(key)).all(|(key, value)| second.get(key) == Some(value))
+        && second.iter().filter(|(key, _)| !ignored(key)).all(|(key, _)| first.contains_key(key))
+}
+
+impl Request {
+    /// Compares two requests semantically: method, URL, params, cookies, headers and body, ignoring
+    /// header order and name case, and as set by `options`
+    ///
+    /// ```
+    /// use wrequest::{EquivalenceOptions, Request};
+    ///
+    /// let mut recorded = Request::post("https://service.com/users");
+    /// recorded.insert_header("Content-Type", "application/json")
+    ///         .insert_header("Date", "Tue, 15 Nov 1994 08:12:31 GMT")
+    ///         .set_body(br#"{"name": "John", "age": 42}"#.to_vec()).unwrap();
+    ///
+    /// let mut request = Request::post("https://service.com/users");
+    /// request.insert_header("content-type", "application/json")
+    ///        .set_body(br#"{"age":42,"name":"John"}"#.to_vec()).unwrap();
+    ///
+    /// assert!(!request.equivalent(&recorded, &EquivalenceOptions::default()));
+    /// assert!(request.equivalent(&recorded, &EquivalenceOptions::default().with_json_bodies(true)));
+    /// ```
+    pub fn equivalent(&self, other: &Request, options: &EquivalenceOptions) -> bool {
+        let urls_equal = if options.normalize_urls {
+            uri::normalize(&self.full_url()) == uri::normalize(&other.full_url())
+        } else {
+            self.url() == other.url() && key_values_equivalent(self.params(), other.params(), &[])
+        };
+        self.method() == other.method()
+            && urls_equal
+            && key_values_equivalent(self.cookies(), other.cookies(), &[])
+            && options.messages_equivalent(self, other)
+    }
+}
+
+impl Response {
+    /// Compares two responses semantically: status code, cookies, authorization guides, headers and body,
+    /// ignoring header order and name case, and as set by `options`
+    pub fn equivalent(&self, other: &Response, options: &EquivalenceOptions) -> bool {
+        let cookies = |response: &Response| response.cookies().into_iter().map(set_cookie_header).collect::<Vec<_>>();
+        self.status_code() == other.status_code()
+            && cookies(self) == cookies(other)
+            && self.auth_headers() == other.auth_headers()
+            && self.proxy_auth_headers() == other.proxy_auth_headers()
+            && options.messages_equivalent(self, other)
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Typed metadata attached to messages
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Value stored in the extensions map, it can be cloned along with its message
+trait AnyClone: Any + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AnyClone>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyClone for T {
+    fn clone_box(&self) -> Box<dyn AnyClone> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Clone for Box<dyn AnyClone> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// Map of typed values attached to a request or response, at most one value per type.
+///
+/// Extensions let processing layers share data like the authenticated user or timing information
+/// without encoding it in headers. They are not part of the HTTP message, so they are never sent.
+///
+/// ```
+/// use wrequest::Request;
+///
+/// #[derive(Clone, PartialEq, Debug)]
+/// struct User(String);
+///
+/// let mut request = Request::get("https://service.com/users");
+/// request.extensions_mut().insert(User("john".to_string()));
+///
+/// assert_eq!(request.extensions().get::<User>(), Some(&User("john".to_string())));
+/// assert_eq!(request.extensions().get::<u32>(), None);
+/// ```
+#[derive(Clone, Default)]
+pub struct Extensions {
+    /// Values by type
+    map: HashMap<TypeId, Box<dyn AnyClone>>
+}
+
+impl Extensions {
+    /// Constructor
+    pub fn new() -> Extensions {
+        Extensions { map: HashMap::new() }
+    }
+
+    /// Inserts a value, returning the previous value of the same type if any
+    pub fn insert<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        self.map.insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|previous| previous.into_any().downcast().ok().map(|boxed| *boxed))
+    }
+
+    /// Gets a reference to the value of type `T`, if any
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map.get(&TypeId::of::<T>())
+            .and_then(|value| (**value).as_any().downcast_ref())
+    }
+
+    /// Gets a mutable reference to the value of type `T`, if any
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.map.get_mut(&TypeId::of::<T>())
+            .and_then(|value| (**value).as_any_mut().downcast_mut())
+    }
+
+    /// Removes and returns the value of type `T`, if any
+    pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        self.map.remove(&TypeId::of::<T>())
+            .and_then(|value| value.into_any().downcast().ok().map(|boxed| *boxed))
+    }
+
+    /// Checks if there is a value of type `T`
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Removes all the values
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Checks if there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions").field("len", &self.len()).finish()
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Stable request fingerprints
+
+use crate::{Request, uri};
+
+/// Components of a request included in its [fingerprint](Request::fingerprint)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FingerprintOptions {
+    /// Include the method
+    pub method: bool,
+    /// Include the normalized URL, with the request params
+    pub url: bool,
+    /// Headers included, names are case-insensitive and values trimmed
+    pub headers: Vec<String>,
+    /// Include the cookies
+    pub cookies: bool,
+    /// Include a digest of the body
+    pub body: bool
+}
+
+impl Default for FingerprintOptions {
+    /// Includes method, URL and body
+    fn default() -> Self {
+        FingerprintOptions {
+            method: true,
+            url: true,
+            headers: Vec::new(),
+            cookies: false,
+            body: true
+        }
+    }
+}
+
+impl FingerprintOptions {
+    /// Includes a header
+    pub fn with_header(mut self, name: &str) -> Self {
+        self.headers.push(name.to_string());
+        self
+    }
+
+    /// Includes or not the cookies
+    pub fn with_cookies(mut self, cookies: bool) -> Self {
+        self.cookies = cookies;
+        self
+    }
+
+    /// Includes or not the body
+    pub fn with_body(mut self, body: bool) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+/// FNV-1a 64-bit hash, stable across platforms and releases
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Fnv {
+        Fnv(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    /// Writes a component prefixed by its length, so that adjacent components cannot be confused
+    fn write_component(&mut self, data: &[u8]) {
+        self.write(&(data.len() as u64).to_le_bytes());
+        self.write(data);
+    }
+
+    /// Writes an optional component, absent ones differ from empty ones
+    fn write_optional(&mut self, data: Option<&[u8]>) {
+        match data {
+            Some(data) => {
+                self.write(&[1]);
+                self.write_component(data);
+            },
+            None => self.write(&[0])
+        }
+    }
+}
+
+impl Request {
+    /// Computes a stable hash of the request components selected by `options`, for request deduplication,
+    /// cache keys or idempotency bookkeeping. The URL is normalized (see [Request::normalize]) and the body
+    /// is reduced to a digest. Use `format!("{:016x}", fingerprint)` for a textual key.
+    ///
+    /// ```
+    /// use wrequest::{FingerprintOptions, Request};
+    ///
+    /// let options = FingerprintOptions::default().with_header("Accept");
+    ///
+    /// let mut first = Request::get("https://Example.com/users?b=2&a=1");
+    /// first.insert_header("Accept", "application/json");
+    /// let mut second = Request::get("https://example.com:443/users");
+    /// second.insert_param("a", "1").insert_param("b", "2");
+    /// second.insert_header("accept", "application/json ");
+    ///
+    /// assert_eq!(first.fingerprint(&options), second.fingerprint(&options));
+    /// ```
+    pub fn fingerprint(&self, options: &FingerprintOptions) -> u64 {
+        let mut hash = Fnv::new();
+        if options.method {
+            hash.write_component(self.method().to_string().as_bytes());
+        }
+        if options.url {
+            hash.write_component(uri::normalize(&self.full_url()).as_bytes());
+        }
+        for name in options.headers.iter() {
+            hash.write_component(name.to_ascii_lowercase().as_bytes());
+            hash.write_optional(self.headers().get(name).map(|value| value.trim().as_bytes()));
+        }
+        if options.cookies {
+            let mut cookies: Vec<(&str, &str)> = self.cookies().iter().collect();
+            cookies.sort();
+            hash.write(&(cookies.len() as u64).to_le_bytes());
+            for (name, value) in cookies {
+                hash.write_component(name.as_bytes());
+                hash.write_component(value.as_bytes());
+            }
+        }
+        if options.body {
+            let digest = self.body().map(|body| {
+                let mut digest = Fnv::new();
+                digest.write(body);
+                digest.0.to_le_bytes()
+            });
+            hash.write_optional(digest.as_ref().map(|digest| &digest[..]));
+        }
+        hash.0
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Declarative request and response fixtures (feature `serde`)
+//!
+//! Fixtures define messages in human-editable files, like YAML or TOML, so test cases can be written without
+//! Rust code. The crate does not depend on any file format: fixtures implement `Deserialize` and the loaders
+//! take a closure with the parse function of the format, like `|text| serde_yaml::from_str(text)` or
+//! `|text| toml::from_str(text)`.
+//!
+//! A request fixture has a `url` and optional `method` (`GET` by default), `headers`, `params`, `cookies`
+//! and either an inline `body` or a `body_file` path, relative to the fixture file:
+//!
+//! ```text
+//! method: POST
+//! url: https://service.com/users
+//! headers:
+//!   Content-Type: application/json
+//! params:
+//!   notify: "true"
+//! body_file: bodies/john.json
+//! ```
+//!
+//! A response fixture has a `status` and optional `headers`, `body` and `body_file`:
+//!
+//! ```text
+//! status = 201
+//! body = '{"id": 1}'
+//!
+//! [headers]
+//! Content-Type = "application/json"
+//! ```
+//!
+//! Any serde format works the same way, for instance JSON:
+//!
+//! ```
+//! use wrequest::HttpMethod;
+//! use wrequest::fixtures::RequestFixture;
+//!
+//! let fixture: RequestFixture = serde_json::from_str(r#"{
+//!     "method": "PUT",
+//!     "url": "https://service.com/users/1",
+//!     "headers": {"Content-Type": "text/plain"},
+//!     "body": "John"
+//! }"#).unwrap();
+//!
+//! let request = fixture.into_request(".").unwrap();
+//! assert_eq!(request.method(), HttpMethod::PUT);
+//! assert_eq!(request.body().unwrap(), b"John");
+//! ```
+
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use crate::{BodyTooLarge, HeaderMap, HttpMethod, HttpMessage, HttpStatusCode, KeyValueMap, Request, Response};
+
+/// Request definition of a fixture file
+#[derive(Clone, Deserialize)]
+pub struct RequestFixture {
+    /// Request method, `GET` by default
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Request URL
+    pub url: String,
+    /// Request headers
+    #[serde(default)]
+    pub headers: HeaderMap,
+    /// Query params
+    #[serde(default)]
+    pub params: KeyValueMap,
+    /// Request cookies
+    #[serde(default)]
+    pub cookies: KeyValueMap,
+    /// Inline body
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Path of a file with the body, relative to the fixture file
+    #[serde(default)]
+    pub body_file: Option<PathBuf>
+}
+
+impl RequestFixture {
+    /// Builds the request, with `body_file` relative to `base_dir`. Returns an `ErrorKind::InvalidData` error if
+    /// the method is unknown or both `body` and `body_file` are set, a [BodyTooLarge] error if the body exceeds the
+    /// maximum body size, and the error reading the body file if any.
+    pub fn into_request<P: AsRef<Path>>(self, base_dir: P) -> Result<Request, Error> {
+        let method = match self.method {
+            Some(method) => HttpMethod::from_str(&method).map_err(|e| Error::new(ErrorKind::InvalidData, e))?,
+            None => HttpMethod::GET
+        };
+        let mut request = Request::new(method, self.url);
+        for (key, value) in &self.params {
+            request.insert_param(key.to_string(), value.to_string());
+        }
+        for (key, value) in &self.cookies {
+            request.insert_cookie(key.to_string(), value.to_string());
+        }
+        *request.headers_mut() = self.headers;
+        set_body(&mut request, self.body, self.body_file, base_dir.as_ref())?;
+        Ok(request)
+    }
+}
+
+/// Response definition of a fixture file
+#[derive(Clone, Deserialize)]
+pub struct ResponseFixture {
+    /// Status code
+    pub status: HttpStatusCode,
+    /// Response headers
+    #[serde(default)]
+    pub headers: HeaderMap,
+    /// Inline body
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Path of a file with the body, relative to the fixture file
+    #[serde(default)]
+    pub body_file: Option<PathBuf>
+}
+
+impl ResponseFixture {
+    /// Builds the response, with `body_file` relative to `base_dir`. Returns an `ErrorKind::InvalidData` error if
+    /// the status code is not valid (see [StatusCode](crate::StatusCode)) or both `body` and `body_file` are set,
+    /// a [BodyTooLarge] error if the body exceeds the maximum body size, and the error reading the body file if any.
+    pub fn into_response<P: AsRef<Path>>(self, base_dir: P) -> Result<Response, Error> {
+        let mut response = Response::try_new(self.status).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        *response.headers_mut() = self.headers;
+        set_body(&mut response, self.body, self.body_file, base_dir.as_ref())?;
+        Ok(response)
+    }
+}
+
+fn set_body(message: &mut HttpMessage, body: Option<String>, body_file: Option<PathBuf>, base_dir: &Path) -> Result<(), Error> {
+    match (body, body_file) {
+        (Some(_), Some(_)) => return Err(Error::new(ErrorKind::InvalidData, "Fixture with both body and body_file")),
+        (Some(body), None) => { message.set_body(body.into_bytes())?; },
+        (None, Some(path)) => {
+            // The size is checked before reading, so that large files are not loaded
+            let limit = message.max_body_size();
+            let file = File::open(base_dir.join(path))?;
+            let size = usize::try_from(file.metadata()?.len()).unwrap_or(usize::MAX);
+            if size > limit {
+                return Err(BodyTooLarge::error(limit, Some(size)));
+            }
+            let mut body = Vec::with_capacity(size);
+            file.take((limit as u64).saturating_add(1)).read_to_end(&mut body)?;
+            message.set_body(body)?;
+        },
+        (None, None) => {}
+    }
+    Ok(())
+}
+
+/// Reads and parses a fixture file with the `parse` function of its format. Parse errors are returned as
+/// `ErrorKind::InvalidData` errors.
+fn load<T, F, E>(path: &Path, parse: F) -> Result<T, Error>
+where T: DeserializeOwned,
+      F: FnOnce(&str) -> Result<T, E>,
+      E: Display {
+    let document = fs::read_to_string(path)?;
+    parse(&document).map_err(|e| Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))
+}
+
+/// Directory `body_file` paths are relative to
+fn base_dir(path: &Path) -> &Path {
+    path.parent().unwrap_or_else(|| Path::new("."))
+}
+
+/// Loads a request fixture file, parsed with the `parse` function of its format
+///
+/// ```no_run
+/// let request = wrequest::fixtures::load_request("tests/create_user.json", |text| serde_json::from_str(text)).unwrap();
+/// ```
+pub fn load_request<P, F, E>(path: P, parse: F) -> Result<Request, Error>
+where P: AsRef<Path>,
+      F: FnOnce(&str) -> Result<RequestFixture, E>,
+      E: Display {
+    let path = path.as_ref();
+    load(path, parse)?.into_request(base_dir(path))
+}
+
+/// Loads a response fixture file, parsed with the `parse` function of its format
+pub fn load_response<P, F, E>(path: P, parse: F) -> Result<Response, Error>
+where P: AsRef<Path>,
+      F: FnOnce(&str) -> Result<ResponseFixture, E>,
+      E: Display {
+    let path = path.as_ref();
+    load(path, parse)?.into_response(base_dir(path))
+}
+
+/// Loads a file with a sequence of request fixtures, parsed with the `parse` function of its format
+pub fn load_requests<P, F, E>(path: P, parse: F) -> Result<Vec<Request>, Error>
+where P: AsRef<Path>,
+      F: FnOnce(&str) -> Result<Vec<RequestFixture>, E>,
+      E: Display {
+    let path = path.as_ref();
+    load(path, parse)?.into_iter().map(|fixture| fixture.into_request(base_dir(path))).collect()
+}
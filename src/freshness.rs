@@ -0,0 +1,124 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Age and freshness of cached responses (RFC 9111, section 4.2)
+//!
+//! ```
+//! use std::time::{Duration, SystemTime, UNIX_EPOCH};
+//! use wrequest::{Response, HTTP_200_OK};
+//!
+//! let sent = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+//! let received = sent + Duration::from_secs(2);
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.insert_header("Date", "Tue, 14 Nov 2023 22:13:20 GMT")
+//!         .insert_header("Age", "30")
+//!         .insert_header("Cache-Control", "public, max-age=300");
+//!
+//! // 30 seconds in upstream caches plus 2 seconds of delay, and 10 seconds in the local cache
+//! let now = received + Duration::from_secs(10);
+//! assert_eq!(response.current_age(sent, received, now), Duration::from_secs(42));
+//! assert_eq!(response.freshness_remaining(sent, received, now), Some(Duration::from_secs(258)));
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use chrono::{DateTime, NaiveDateTime};
+use crate::Response;
+
+/// `Age` header name
+pub const AGE: &str = "Age";
+/// `Date` header name
+pub const DATE: &str = "Date";
+/// `Cache-Control` header name
+pub const CACHE_CONTROL: &str = "Cache-Control";
+/// `Expires` header name
+pub const EXPIRES: &str = "Expires";
+
+/// Parses an HTTP date in the preferred IMF-fixdate format, like `Sun, 06 Nov 1994 08:49:37 GMT`, or in the
+/// obsolete RFC 850 and asctime formats (RFC 9110, section 5.6.7)
+pub fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let value = value.trim();
+    let timestamp = DateTime::parse_from_rfc2822(value).map(|date| date.timestamp())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%A, %d-%b-%y %H:%M:%S GMT").map(|date| date.and_utc().timestamp()))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%a %b %e %H:%M:%S %Y").map(|date| date.and_utc().timestamp()))
+        .ok()?;
+    u64::try_from(timestamp).ok().map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Parses delta seconds, a non-negative integer. Values too large are capped, as required for `Age`.
+fn parse_delta_seconds(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.is_empty() || !value.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some(Duration::from_secs(value.parse().unwrap_or(u64::MAX)))
+}
+
+/// Time elapsed from `earlier` to `later`, zero if `later` is before `earlier`
+fn elapsed(earlier: SystemTime, later: SystemTime) -> Duration {
+    later.duration_since(earlier).unwrap_or_default()
+}
+
+impl Response {
+    /// Gets the `Date` header, if any and well-formed
+    pub fn date(&self) -> Option<SystemTime> {
+        self.headers().get(DATE).and_then(parse_http_date)
+    }
+
+    /// Gets the `Age` header, the time the response spent in upstream caches, if any and well-formed
+    pub fn age(&self) -> Option<Duration> {
+        self.headers().get(AGE).and_then(parse_delta_seconds)
+    }
+
+    /// Computes the current age of a cached response (RFC 9111, section 4.2.3), from the time the request was
+    /// sent, the time the response was received and the current time. The age is the largest of the apparent
+    /// age, from the `Date` header, and the `Age` header corrected with the response delay, plus the time
+    /// the response has been stored since. Responses without `Date` header are taken as dated when received.
+    pub fn current_age(&self, request_time: SystemTime, response_time: SystemTime, now: SystemTime) -> Duration {
+        let apparent_age = self.date().map_or(Duration::ZERO, |date| elapsed(date, response_time));
+        let response_delay = elapsed(request_time, response_time);
+        let corrected_age_value = self.age().unwrap_or_default().saturating_add(response_delay);
+        let corrected_initial_age = apparent_age.max(corrected_age_value);
+        let resident_time = elapsed(response_time, now);
+        corrected_initial_age.saturating_add(resident_time)
+    }
+
+    /// Gets the freshness lifetime of the response for a private cache (RFC 9111, section 4.2.1): the
+    /// `max-age` directive of the `Cache-Control` header or else the time from `Date` to `Expires`. Invalid
+    /// `Expires` dates, like `0`, mean the response is already expired, as well as an `Expires` header without
+    /// `Date`. Returns `None` if the response has no explicit lifetime.
+    pub fn freshness_lifetime(&self) -> Option<Duration> {
+        let max_age = self.headers().get_list(CACHE_CONTROL).into_iter()
+            .filter_map(|directive| directive.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("max-age"))
+            .and_then(|(_, value)| parse_delta_seconds(value.trim_matches('"')));
+        if max_age.is_some() {
+            return max_age;
+        }
+        let expires = self.headers().get(EXPIRES)?;
+        Some(match (parse_http_date(expires), self.date()) {
+            (Some(expires), Some(date)) => elapsed(date, expires),
+            _ => Duration::ZERO
+        })
+    }
+
+    /// Gets the time the response stays fresh, see [Response::freshness_lifetime] and [Response::current_age].
+    /// Returns zero if the response is stale and `None` if it has no explicit lifetime.
+    pub fn freshness_remaining(&self, request_time: SystemTime, response_time: SystemTime, now: SystemTime) -> Option<Duration> {
+        self.freshness_lifetime()
+            .map(|lifetime| lifetime.saturating_sub(self.current_age(request_time, response_time, now)))
+    }
+}
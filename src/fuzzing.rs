@@ -0,0 +1,224 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Random valid messages for fuzzing and property testing (feature `fuzzing`)
+//!
+//! [HeaderMap], [Request] and [Response] implement `arbitrary::Arbitrary`, for fuzzers like `cargo fuzz`,
+//! and `proptest::arbitrary::Arbitrary`, for property tests. The messages generated are valid: header
+//! names are tokens and header values visible ASCII, URLs are absolute `http` or `https` URLs with unreserved
+//! params and framing is left to the serializer, so they can be written and parsed back.
+//!
+//! The proptest strategies generate the messages from random bytes with the `arbitrary` implementations,
+//! shrinking the bytes to simplify the failing cases.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use wrequest::Request;
+//! use wrequest::wire;
+//!
+//! proptest!(|(request in any::<Request>())| {
+//!     let parsed = wire::parse_request(&wire::request_to_bytes(&request).unwrap()).unwrap();
+//!     prop_assert_eq!(parsed.method(), request.method());
+//!     prop_assert_eq!(parsed.body(), request.body());
+//! });
+//! ```
+
+use std::fmt;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use proptest::collection::vec;
+use proptest::prelude::{any, BoxedStrategy, Strategy};
+use wcookie::SetCookie;
+use crate::{HeaderMap, HttpMethod, HttpVersion, Request, Response};
+use crate::wire::allows_body;
+
+/// Maximum length of the generated bodies
+pub const MAX_BODY_SIZE: usize = 256;
+
+/// Maximum number of random bytes a proptest strategy generates a message from
+const MAX_INPUT_SIZE: usize = 1024;
+
+/// Characters of header names and cookies
+const TOKEN: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-";
+/// Characters of URL paths and params, not percent-encoded
+const UNRESERVED: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789-._~";
+/// Characters of host labels
+const HOST: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// Headers generated besides the `X-` ones, none of them changes the framing or the parsing of the messages
+const HEADER_NAMES: &[&str] = &["Accept", "Accept-Language", "Cache-Control", "Content-Type", "ETag", "User-Agent",
+    "Vary"];
+
+const METHODS: &[HttpMethod] = &[HttpMethod::GET, HttpMethod::HEAD, HttpMethod::POST, HttpMethod::PUT,
+    HttpMethod::DELETE, HttpMethod::CONNECT, HttpMethod::OPTIONS, HttpMethod::TRACE, HttpMethod::PATCH];
+
+fn string(u: &mut Unstructured, alphabet: &[u8], min: usize, max: usize) -> Result<String> {
+    let length = u.int_in_range(min..=max)?;
+    let mut result = String::with_capacity(length);
+    for _ in 0..length {
+        result.push(*u.choose(alphabet)? as char);
+    }
+    Ok(result)
+}
+
+fn header_name(u: &mut Unstructured) -> Result<String> {
+    if u.ratio(1, 2)? {
+        return Ok(u.choose(HEADER_NAMES)?.to_string());
+    }
+    Ok(format!("X-{}", string(u, TOKEN, 1, 12)?))
+}
+
+/// Visible ASCII characters and inner spaces
+fn header_value(u: &mut Unstructured) -> Result<String> {
+    let length = u.int_in_range(0..=32)?;
+    let mut result = String::with_capacity(length);
+    for _ in 0..length {
+        result.push(u.int_in_range(0x20u8..=0x7e)? as char);
+    }
+    Ok(result.trim_matches(' ').to_string())
+}
+
+fn url(u: &mut Unstructured) -> Result<String> {
+    let mut result = format!("{}://", u.choose(&["http", "https"])?);
+    for _ in 0..u.int_in_range(1..=3)? {
+        result.push_str(&string(u, HOST, 1, 10)?);
+        result.push('.');
+    }
+    result.push_str(u.choose(&["com", "org", "net", "io"])?);
+    if u.ratio(1, 4)? {
+        result.push_str(&format!(":{}", u.int_in_range(1u16..=65535)?));
+    }
+    for _ in 0..u.int_in_range(0..=3)? {
+        result.push('/');
+        result.push_str(&string(u, UNRESERVED, 1, 8)?);
+    }
+    Ok(result)
+}
+
+/// Non-empty body, or none
+fn body(u: &mut Unstructured) -> Result<Option<Vec<u8>>> {
+    if u.ratio(1, 2)? {
+        return Ok(None);
+    }
+    let length = u.int_in_range(1..=MAX_BODY_SIZE)?;
+    Ok(Some(u.bytes(length)?.to_vec()))
+}
+
+fn version(u: &mut Unstructured) -> Result<HttpVersion> {
+    Ok(*u.choose(&[HttpVersion::Http11, HttpVersion::Http10])?)
+}
+
+impl<'a> Arbitrary<'a> for HeaderMap {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut result = HeaderMap::new();
+        for _ in 0..u.int_in_range(0..=8)? {
+            result.insert(header_name(u)?, header_value(u)?);
+        }
+        Ok(result)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Request {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut result = Request::new(*u.choose(METHODS)?, url(u)?);
+        result.set_version(version(u)?);
+        for (name, value) in HeaderMap::arbitrary(u)?.iter() {
+            result.insert_header(name.to_string(), value.to_string());
+        }
+        for _ in 0..u.int_in_range(0..=4)? {
+            result.insert_param(string(u, UNRESERVED, 1, 8)?, string(u, UNRESERVED, 0, 8)?);
+        }
+        for _ in 0..u.int_in_range(0..=3)? {
+            result.insert_cookie(string(u, TOKEN, 1, 8)?, string(u, TOKEN, 0, 12)?);
+        }
+        if let Some(body) = body(u)? {
+            result.set_body(body).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+        Ok(result)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Response {
+    /// Responses whose status code does not allow a body have none
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut result = Response::new(u.int_in_range(100..=599)?);
+        result.set_version(version(u)?);
+        for (name, value) in HeaderMap::arbitrary(u)?.iter() {
+            result.insert_header(name.to_string(), value.to_string());
+        }
+        for _ in 0..u.int_in_range(0..=2)? {
+            result.insert_cookie(SetCookie::new(&string(u, TOKEN, 1, 8)?, &string(u, TOKEN, 1, 12)?));
+        }
+        if let Some(body) = body(u)?.filter(|_| allows_body(result.status_code())) {
+            result.set_body(body).map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Strategy of the values generated from random bytes by their `arbitrary` implementation
+fn from_bytes<T>() -> BoxedStrategy<T>
+where T: for<'a> Arbitrary<'a> + fmt::Debug + 'static {
+    vec(any::<u8>(), 0..MAX_INPUT_SIZE)
+        .prop_filter_map("Not enough random bytes", |bytes| T::arbitrary_take_rest(Unstructured::new(&bytes)).ok())
+        .boxed()
+}
+
+/// Strategy of valid header maps
+pub fn header_maps() -> BoxedStrategy<HeaderMap> {
+    from_bytes()
+}
+
+/// Strategy of valid requests
+pub fn requests() -> BoxedStrategy<Request> {
+    from_bytes()
+}
+
+/// Strategy of valid responses
+pub fn responses() -> BoxedStrategy<Response> {
+    from_bytes()
+}
+
+/// Strategy of non-empty bodies, up to [MAX_BODY_SIZE] bytes
+pub fn bodies() -> BoxedStrategy<Vec<u8>> {
+    vec(any::<u8>(), 1..=MAX_BODY_SIZE).boxed()
+}
+
+impl proptest::arbitrary::Arbitrary for HeaderMap {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<HeaderMap>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        header_maps()
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for Request {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Request>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        requests()
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for Response {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Response>;
+
+    fn arbitrary_with(_args: ()) -> Self::Strategy {
+        responses()
+    }
+}
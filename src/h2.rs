@@ -0,0 +1,259 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Conversions to and from HTTP/2 header lists with pseudo-headers (RFC 9113, section 8.3)
+
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+use wcookie::SetCookie;
+use crate::{HttpMethod, HttpVersion, Request, Response, uri, parse_cookie_header, set_cookie_header};
+use crate::connection::HOP_BY_HOP_HEADERS;
+
+/// `:method` pseudo-header
+const METHOD: &str = ":method";
+/// `:scheme` pseudo-header
+const SCHEME: &str = ":scheme";
+/// `:authority` pseudo-header
+const AUTHORITY: &str = ":authority";
+/// `:path` pseudo-header
+const PATH: &str = ":path";
+/// `:status` pseudo-header
+const STATUS: &str = ":status";
+
+/// Checks a header is connection-specific, not allowed in HTTP/2 but `TE: trailers`
+fn is_connection_specific(name: &str, value: &str) -> bool {
+    if name.eq_ignore_ascii_case("TE") {
+        return !value.trim().eq_ignore_ascii_case("trailers");
+    }
+    HOP_BY_HOP_HEADERS.iter()
+        .filter(|header| !header.starts_with("Proxy-Auth"))
+        .any(|header| header.eq_ignore_ascii_case(name))
+}
+
+/// Collects the regular headers of an HTTP/2 header list, in lowercase, skipping connection-specific ones
+fn push_headers<'a, I>(result: &mut Vec<(String, String)>, headers: I)
+where I: Iterator<Item = (&'a str, &'a str)> {
+    for (name, value) in headers {
+        if !is_connection_specific(name, value) {
+            result.push((name.to_ascii_lowercase(), value.to_string()));
+        }
+    }
+}
+
+/// Splits an HTTP/2 header list into its pseudo-headers and regular headers, checking it is well-formed:
+/// lowercase names, pseudo-headers first and not repeated, no connection-specific headers
+#[allow(clippy::type_complexity)]
+fn split_headers<I, K, V>(headers: I, allowed: &[&str]) -> Result<(Vec<(String, String)>, Vec<(String, String)>), Error>
+where I: IntoIterator<Item = (K, V)>,
+      K: AsRef<str>,
+      V: AsRef<str> {
+    let mut pseudo: Vec<(String, String)> = Vec::new();
+    let mut regular: Vec<(String, String)> = Vec::new();
+    for (name, value) in headers {
+        let (name, value) = (name.as_ref(), value.as_ref());
+        if name.bytes().any(|c| c.is_ascii_uppercase()) {
+            return Err(malformed(format!("Header name {:?} is not lowercase", name)));
+        }
+        if name.starts_with(':') {
+            if !regular.is_empty() {
+                return Err(malformed(format!("Pseudo-header {} after regular headers", name)));
+            }
+            if !allowed.contains(&name) || pseudo.iter().any(|(n, _)| n == name) {
+                return Err(malformed(format!("Invalid or repeated pseudo-header {}", name)));
+            }
+            pseudo.push((name.to_string(), value.to_string()));
+        } else if is_connection_specific(name, value) {
+            return Err(malformed(format!("Connection-specific header {}", name)));
+        } else {
+            regular.push((name.to_string(), value.to_string()));
+        }
+    }
+    Ok((pseudo, regular))
+}
+
+fn pseudo_header<'a>(pseudo: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    pseudo.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+}
+
+impl Request {
+    /// Converts the request into an HTTP/2 header list: the `:method`, `:scheme`, `:authority` and `:path`
+    /// pseudo-headers (only `:method` and `:authority` for `CONNECT`) followed by the headers in lowercase,
+    /// without the connection-specific ones nor `Host`. Cookies are sent in a `cookie` header.
+    ///
+    /// The request URL must be absolute, or an authority for `CONNECT`.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::get("https://service.com/users?page=2");
+    /// request.insert_header("Accept", "application/json")
+    ///        .insert_header("Connection", "keep-alive");
+    ///
+    /// let headers = request.to_h2_headers().unwrap();
+    /// let names: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+    /// assert_eq!(names, vec![":method", ":scheme", ":authority", ":path", "accept"]);
+    /// assert_eq!(headers[3].1, "/users?page=2");
+    ///
+    /// let received = Request::from_h2_headers(headers).unwrap();
+    /// assert_eq!(received.url(), "https://service.com/users");
+    /// assert_eq!(received.params().get("page"), Some("2"));
+    /// ```
+    pub fn to_h2_headers(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut result = vec![(METHOD.to_string(), self.method().to_string())];
+        let url = self.full_url();
+        if self.method() == HttpMethod::CONNECT {
+            let authority = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+            result.push((AUTHORITY.to_string(), authority.trim_end_matches('/').to_string()));
+        } else {
+            let (scheme, rest) = url.split_once("://")
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("URL {:?} is not absolute", url)))?;
+            let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+            let path = rest[end..].split('#').next().unwrap_or("");
+            let path = if path.starts_with('/') { path.to_string() } else { format!("/{}", path) };
+            let authority = &rest[..end];
+            let authority = authority.rfind('@').map_or(authority, |at| &authority[at + 1..]);
+            result.push((SCHEME.to_string(), scheme.to_ascii_lowercase()));
+            result.push((AUTHORITY.to_string(), authority.to_string()));
+            result.push((PATH.to_string(), path));
+        }
+        push_headers(&mut result, self.headers().iter().filter(|(name, _)| !name.eq_ignore_ascii_case("Host")));
+        if let Some(cookies) = self.cookie_header() {
+            result.push(("cookie".to_string(), cookies));
+        }
+        Ok(result)
+    }
+
+    /// Builds a request from an HTTP/2 header list. The URL is built from the `:scheme`, `:authority` (or
+    /// `Host` header) and `:path` pseudo-headers, its query string is decoded into the request params and
+    /// the `cookie` headers into the request cookies.
+    ///
+    /// Malformed lists (uppercase names, unknown, repeated or missing pseudo-headers, pseudo-headers after
+    /// regular headers, connection-specific headers) are rejected with an `ErrorKind::InvalidData` error.
+    pub fn from_h2_headers<I, K, V>(headers: I) -> Result<Request, Error>
+    where I: IntoIterator<Item = (K, V)>,
+          K: AsRef<str>,
+          V: AsRef<str> {
+        let (pseudo, regular) = split_headers(headers, &[METHOD, SCHEME, AUTHORITY, PATH])?;
+        let method = pseudo_header(&pseudo, METHOD).ok_or_else(|| malformed("Missing :method pseudo-header"))?;
+        let method = HttpMethod::from_str(method).map_err(|e| malformed(e.to_string()))?;
+        let authority = pseudo_header(&pseudo, AUTHORITY)
+            .or_else(|| regular.iter().find(|(name, _)| name == "host").map(|(_, value)| value.as_str()));
+
+        let mut result = if method == HttpMethod::CONNECT {
+            match (authority, pseudo.len()) {
+                (Some(authority), 2) => Request::new(method, authority.to_string()),
+                _ => return Err(malformed("CONNECT requests must only have :method and :authority"))
+            }
+        } else {
+            let (scheme, path) = match (pseudo_header(&pseudo, SCHEME), pseudo_header(&pseudo, PATH)) {
+                (Some(scheme), Some(path)) if !path.is_empty() => (scheme, path),
+                _ => return Err(malformed("Missing :scheme or :path pseudo-header"))
+            };
+            let (path, query) = uri::split_query(path);
+            let url = match authority {
+                Some(authority) => format!("{}://{}{}", scheme, authority, path),
+                None => path.to_string()
+            };
+            let mut result = Request::new(method, url);
+            if let Some(query) = query {
+                for (key, value) in uri::parse_query(query) {
+                    result.insert_param(key, value);
+                }
+            }
+            result
+        };
+        result.set_version(HttpVersion::Http2);
+
+        let cookies: Vec<&str> = regular.iter().filter(|(name, _)| name == "cookie").map(|(_, value)| value.as_str()).collect();
+        for (key, cookie) in parse_cookie_header(&cookies.join("; ")) {
+            result.insert_cookie(key, cookie);
+        }
+        for (name, value) in regular {
+            if name != "cookie" {
+                let value = match result.headers().get(&name) {
+                    Some(current) => format!("{}, {}", current, value),
+                    None => value
+                };
+                result.try_insert_header(name, value).map_err(|e| malformed(e.to_string()))?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl Response {
+    /// Converts the response into an HTTP/2 header list: the `:status` pseudo-header followed by the
+    /// headers in lowercase, without the connection-specific ones. Cookies are sent as `set-cookie` headers
+    /// and authorization guides as `www-authenticate` and `proxy-authenticate` headers.
+    pub fn to_h2_headers(&self) -> Vec<(String, String)> {
+        let mut result = vec![(STATUS.to_string(), self.status_code().to_string())];
+        push_headers(&mut result, self.headers().iter());
+        for cookie in self.cookies() {
+            result.push(("set-cookie".to_string(), set_cookie_header(cookie)));
+        }
+        for auth in self.auth_headers() {
+            result.push(("www-authenticate".to_string(), auth.clone()));
+        }
+        for auth in self.proxy_auth_headers() {
+            result.push(("proxy-authenticate".to_string(), auth.clone()));
+        }
+        result
+    }
+
+    /// Builds a response from an HTTP/2 header list. `set-cookie` headers are parsed into the response
+    /// cookies (invalid ones are ignored), `www-authenticate` and `proxy-authenticate` headers into the
+    /// authorization guides and repeated headers are joined with commas.
+    ///
+    /// Malformed lists are rejected with an `ErrorKind::InvalidData` error.
+    pub fn from_h2_headers<I, K, V>(headers: I) -> Result<Response, Error>
+    where I: IntoIterator<Item = (K, V)>,
+          K: AsRef<str>,
+          V: AsRef<str> {
+        let (pseudo, regular) = split_headers(headers, &[STATUS])?;
+        let status = pseudo_header(&pseudo, STATUS)
+            .filter(|status| status.len() == 3)
+            .and_then(|status| status.parse().ok())
+            .ok_or_else(|| malformed("Missing or invalid :status pseudo-header"))?;
+
+        let mut result = Response::new(status);
+        result.set_version(HttpVersion::Http2);
+        for (name, value) in regular {
+            match name.as_str() {
+                "set-cookie" => if let Ok(cookie) = SetCookie::from_str(&value) {
+                    result.insert_cookie(cookie);
+                },
+                "www-authenticate" => {
+                    result.insert_auth_headers(value);
+                },
+                "proxy-authenticate" => {
+                    result.insert_proxy_auth_header(value);
+                },
+                _ => {
+                    let value = match result.headers().get(&name) {
+                        Some(current) => format!("{}, {}", current, value),
+                        None => value
+                    };
+                    result.try_insert_header(name, value).map_err(|e| malformed(e.to_string()))?;
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+fn malformed<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,358 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HTTP Archive (HAR) 1.2 support
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::har::{self, Timings};
+//! use std::time::{Duration, SystemTime};
+//!
+//! let mut request = Request::get("https://service.com/users");
+//! request.insert_param("page", "2");
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.set_body(b"[]".to_vec()).unwrap();
+//!
+//! let mut timings = Timings::new(SystemTime::now());
+//! timings.wait = Duration::from_millis(120);
+//!
+//! let document = har::export(&[(request, response, timings)]);
+//! let parsed = json::parse(&document).unwrap();
+//!
+//! assert_eq!(parsed["log"]["entries"][0]["request"]["url"], "https://service.com/users?page=2");
+//!
+//! // Recorded exchanges can be loaded back
+//! let exchanges = har::import(&document).unwrap();
+//! assert_eq!(exchanges[0].0.params().get("page"), Some("2"));
+//! assert_eq!(exchanges[0].1.body().unwrap(), b"[]");
+//! ```
+//!
+//! For more information see [HAR 1.2 Spec](http://www.softwareishard.com/blog/har-12-spec/).
+
+use std::io::{Error, ErrorKind};
+use std::time::{Duration, SystemTime};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, SecondsFormat, Utc};
+use json::{object, JsonValue};
+use wcookie::SetCookie;
+use crate::{HeaderMap, KeyValueMap, Request, Response, CONTENT_TYPE, reason_phrase, uri};
+
+/// HAR format version
+pub const HAR_VERSION: &str = "1.2";
+
+/// HTTP version reported for the exported messages
+const HTTP_VERSION: &str = "HTTP/1.1";
+
+/// Timings of a request/response exchange
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Timings {
+    /// Time the request was started
+    pub started: SystemTime,
+    /// Time spent in a queue waiting for a network connection, if known
+    pub blocked: Option<Duration>,
+    /// DNS resolution time, if known
+    pub dns: Option<Duration>,
+    /// Time required to create the TCP connection, if known
+    pub connect: Option<Duration>,
+    /// Time required for the TLS negotiation, if known
+    pub ssl: Option<Duration>,
+    /// Time required to send the request to the server
+    pub send: Duration,
+    /// Time waiting for the server response
+    pub wait: Duration,
+    /// Time required to read the entire response
+    pub receive: Duration
+}
+
+impl Timings {
+    /// Constructor with the `started` time, all the phases are empty or unknown.
+    pub fn new(started: SystemTime) -> Timings {
+        Timings {
+            started,
+            blocked: None,
+            dns: None,
+            connect: None,
+            ssl: None,
+            send: Duration::ZERO,
+            wait: Duration::ZERO,
+            receive: Duration::ZERO
+        }
+    }
+
+    /// Total elapsed time of the exchange. The `ssl` time is already included in `connect`, as HAR mandates.
+    pub fn total(&self) -> Duration {
+        self.blocked.unwrap_or_default() + self.dns.unwrap_or_default() + self.connect.unwrap_or_default()
+            + self.send + self.wait + self.receive
+    }
+}
+
+/// Exports a list of exchanges as an HTTP Archive 1.2 JSON document.
+///
+/// Bodies that are valid UTF-8 are exported as text, binary ones are encoded in base64 and marked with
+/// `"encoding": "base64"`.
+pub fn export(entries: &[(Request, Response, Timings)]) -> String {
+    let mut har_entries = JsonValue::new_array();
+    for (request, response, timings) in entries.iter() {
+        let _ = har_entries.push(export_entry(request, response, timings));
+    }
+
+    let document = object! {
+        log: {
+            version: HAR_VERSION,
+            creator: {
+                name: env!("CARGO_PKG_NAME"),
+                version: env!("CARGO_PKG_VERSION")
+            },
+            entries: har_entries
+        }
+    };
+
+    document.pretty(2)
+}
+
+fn export_entry(request: &Request, response: &Response, timings: &Timings) -> JsonValue {
+    object! {
+        startedDateTime: format_time(timings.started),
+        time: millis(timings.total()),
+        request: export_request(request),
+        response: export_response(response),
+        cache: {},
+        timings: {
+            blocked: optional_millis(timings.blocked),
+            dns: optional_millis(timings.dns),
+            connect: optional_millis(timings.connect),
+            send: millis(timings.send),
+            wait: millis(timings.wait),
+            receive: millis(timings.receive),
+            ssl: optional_millis(timings.ssl)
+        }
+    }
+}
+
+fn export_request(request: &Request) -> JsonValue {
+    let mut result = object! {
+        method: request.method().to_string(),
+        url: request.full_url(),
+        httpVersion: HTTP_VERSION,
+        cookies: export_key_values(request.cookies()),
+        headers: export_headers(request.headers()),
+        queryString: export_key_values(request.params()),
+        headersSize: -1,
+        bodySize: request.body().map_or(0, |body| body.len())
+    };
+
+    if let Some(body) = request.body() {
+        let mut post_data = object! {
+            mimeType: request.headers().get(CONTENT_TYPE).unwrap_or("")
+        };
+        export_body(&mut post_data, body);
+        result["postData"] = post_data;
+    }
+
+    result
+}
+
+fn export_response(response: &Response) -> JsonValue {
+    let mut cookies = JsonValue::new_array();
+    for cookie in response.cookies() {
+        let mut value = object! {
+            name: cookie.name.as_str(),
+            value: cookie.value.as_str(),
+            httpOnly: cookie.http_only,
+            secure: cookie.secure
+        };
+        if let Some(ref path) = cookie.path {
+            value["path"] = path.as_str().into();
+        }
+        if let Some(ref domain) = cookie.domain {
+            value["domain"] = domain.as_str().into();
+        }
+        if let Some(ref expires) = cookie.expires {
+            value["expires"] = expires.to_rfc3339_opts(SecondsFormat::Millis, true).into();
+        }
+        let _ = cookies.push(value);
+    }
+
+    let mut content = object! {
+        size: response.body().map_or(0, |body| body.len()),
+        mimeType: response.headers().get(CONTENT_TYPE).unwrap_or("")
+    };
+    if let Some(body) = response.body() {
+        export_body(&mut content, body);
+    }
+
+    object! {
+        status: response.status_code(),
+        statusText: reason_phrase(response.status_code()).unwrap_or(""),
+        httpVersion: HTTP_VERSION,
+        cookies: cookies,
+        headers: export_headers(response.headers()),
+        content: content,
+        redirectURL: response.headers().get("Location").unwrap_or(""),
+        headersSize: -1,
+        bodySize: response.body().map_or(0, |body| body.len())
+    }
+}
+
+fn export_headers(headers: &HeaderMap) -> JsonValue {
+    let mut result = JsonValue::new_array();
+    for (name, value) in headers.iter() {
+        let _ = result.push(object! { name: name, value: value });
+    }
+    result
+}
+
+fn export_key_values(map: &KeyValueMap) -> JsonValue {
+    let mut result = JsonValue::new_array();
+    for (name, value) in map.iter() {
+        let _ = result.push(object! { name: name, value: value });
+    }
+    result
+}
+
+fn export_body(target: &mut JsonValue, body: &[u8]) {
+    match std::str::from_utf8(body) {
+        Ok(text) => target["text"] = text.into(),
+        Err(_) => {
+            target["text"] = BASE64.encode(body).into();
+            target["encoding"] = "base64".into();
+        }
+    }
+}
+
+fn format_time(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn optional_millis(duration: Option<Duration>) -> f64 {
+    duration.map_or(-1.0, millis)
+}
+
+/// Imports the exchanges of an HTTP Archive JSON document.
+///
+/// The request URL is split into the target URL and its params: the HAR `queryString` list is used
+/// if present, otherwise the params are decoded from the URL query string.
+pub fn import(document: &str) -> Result<Vec<(Request, Response)>, Error> {
+    let parsed = json::parse(document).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let entries = &parsed["log"]["entries"];
+    if !entries.is_array() {
+        return Err(invalid("Missing log.entries array"));
+    }
+
+    let mut result = Vec::with_capacity(entries.len());
+    for entry in entries.members() {
+        result.push((import_request(&entry["request"])?, import_response(&entry["response"])?));
+    }
+    Ok(result)
+}
+
+fn import_request(value: &JsonValue) -> Result<Request, Error> {
+    let method = value["method"].as_str().ok_or_else(|| invalid("Missing request method"))?.parse()?;
+    let url = value["url"].as_str().ok_or_else(|| invalid("Missing request URL"))?;
+    let (target, query) = uri::split_query(url);
+
+    let mut request = Request::new(method, target);
+
+    if value["queryString"].is_array() && !value["queryString"].is_empty() {
+        for (name, param) in import_name_values(&value["queryString"])? {
+            request.insert_param(name, param);
+        }
+    } else if let Some(query) = query {
+        for (name, param) in uri::parse_query(query) {
+            request.insert_param(name, param);
+        }
+    }
+
+    for (name, header) in import_name_values(&value["headers"])? {
+        request.insert_header(name, header);
+    }
+
+    for (name, cookie) in import_name_values(&value["cookies"])? {
+        request.insert_cookie(name, cookie);
+    }
+
+    let post_data = &value["postData"];
+    if post_data.is_object() {
+        if let Some(body) = import_body(post_data)? {
+            request.set_body(body)?;
+        }
+        if let Some(mime) = post_data["mimeType"].as_str() {
+            if !mime.is_empty() && !request.headers().contains_key(CONTENT_TYPE) {
+                request.insert_header(CONTENT_TYPE, mime.to_string());
+            }
+        }
+    }
+
+    Ok(request)
+}
+
+fn import_response(value: &JsonValue) -> Result<Response, Error> {
+    let status = value["status"].as_u16().ok_or_else(|| invalid("Missing response status"))?;
+    let mut response = Response::new(status);
+
+    for (name, header) in import_name_values(&value["headers"])? {
+        response.insert_header(name, header);
+    }
+
+    for har_cookie in value["cookies"].members() {
+        let name = har_cookie["name"].as_str().ok_or_else(|| invalid("Missing cookie name"))?;
+        let mut cookie = SetCookie::new(name, har_cookie["value"].as_str().unwrap_or(""));
+        cookie.path = har_cookie["path"].as_str().map(String::from);
+        cookie.domain = har_cookie["domain"].as_str().map(String::from);
+        cookie.http_only = har_cookie["httpOnly"].as_bool().unwrap_or(false);
+        cookie.secure = har_cookie["secure"].as_bool().unwrap_or(false);
+        if let Some(expires) = har_cookie["expires"].as_str() {
+            let parsed = DateTime::parse_from_rfc3339(expires).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+            cookie.expires = Some(parsed.with_timezone(&Utc));
+        }
+        response.insert_cookie(cookie);
+    }
+
+    if let Some(body) = import_body(&value["content"])? {
+        response.set_body(body)?;
+    }
+
+    Ok(response)
+}
+
+fn import_name_values(value: &JsonValue) -> Result<Vec<(String, String)>, Error> {
+    let mut result = Vec::with_capacity(value.len());
+    for member in value.members() {
+        let name = member["name"].as_str().ok_or_else(|| invalid("Missing name"))?;
+        result.push((name.to_string(), member["value"].as_str().unwrap_or("").to_string()));
+    }
+    Ok(result)
+}
+
+fn import_body(value: &JsonValue) -> Result<Option<Vec<u8>>, Error> {
+    let text = match value["text"].as_str() {
+        Some(text) => text,
+        None => return Ok(None)
+    };
+    if value["encoding"].as_str() == Some("base64") {
+        BASE64.decode(text).map(Some).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    } else {
+        Ok(Some(text.as_bytes().to_vec()))
+    }
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,153 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Parsing of comma-separated header values (RFC 9110, section 5.6.1)
+
+use crate::{HeaderMap, Request, ACCEPT, APPLICATION_JSON};
+
+/// Splits a list at the separators that are not inside quoted strings, skipping empty members
+pub(crate) fn split_quoted(value: &str, separator: char) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                entries.push(value[start..i].trim());
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+    entries.push(value[start..].trim());
+    entries.retain(|entry| !entry.is_empty());
+    entries
+}
+
+/// Splits a `member;q=0.8` list member into the member without its quality value and the quality value,
+/// `1.0` if missing. Returns `None` if the quality value is malformed.
+fn weighted_member(member: &str) -> Option<(&str, f32)> {
+    let mut separators = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in member.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => separators.push(i),
+            _ => {}
+        }
+    }
+    for (n, start) in separators.iter().enumerate() {
+        let end = separators.get(n + 1).copied().unwrap_or(member.len());
+        if let Some((name, q)) = member[start + 1..end].split_once('=') {
+            if name.trim().eq_ignore_ascii_case("q") {
+                let quality = q.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q))?;
+                return Some((member[..*start].trim(), quality));
+            }
+        }
+    }
+    Some((member, 1.0))
+}
+
+/// Renders a weighted list, sorted from the highest quality to the lowest one. Quality values are clamped to
+/// `0..=1` and rounded to three decimals, and omitted if `1`.
+pub(crate) fn format_weighted_list(members: &[(&str, f32)]) -> String {
+    let mut members: Vec<(&str, u32)> = members.iter()
+        .map(|(member, q)| (member.trim(), if q.is_nan() { 0 } else { (q.clamp(0.0, 1.0) * 1000.0).round() as u32 }))
+        .collect();
+    members.sort_by_key(|(_, q)| std::cmp::Reverse(*q));
+    members.iter()
+        .map(|(member, q)| match q {
+            1000 => member.to_string(),
+            0 => format!("{};q=0", member),
+            q => format!("{};q=0.{}", member, format!("{:03}", q).trim_end_matches('0'))
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+impl HeaderMap {
+    /// Gets the members of a comma-separated header value, like `Vary` or `Cache-Control`. Commas inside
+    /// quoted strings do not split members. Returns an empty list if there is no header with `key`.
+    ///
+    /// ```
+    /// use wrequest::HeaderMap;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("Cache-Control", "private=\"Set-Cookie, Authorization\", max-age=60");
+    /// assert_eq!(headers.get_list("Cache-Control"), vec!["private=\"Set-Cookie, Authorization\"", "max-age=60"]);
+    /// ```
+    pub fn get_list(&self, key: &str) -> Vec<&str> {
+        self.get(key).map(|value| split_quoted(value, ',')).unwrap_or_default()
+    }
+
+    /// Gets the members of a weighted header value, like `Accept` or `Accept-Language`, with their quality
+    /// values (`1.0` if missing), sorted from the highest quality to the lowest one. Members with equal
+    /// quality keep their order and members with malformed quality values are skipped.
+    ///
+    /// ```
+    /// use wrequest::HeaderMap;
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("Accept", "text/html;q=0.8, application/json, text/plain;format=flowed;q=0.5");
+    /// assert_eq!(headers.get_weighted_list("Accept"),
+    ///            vec![("application/json", 1.0), ("text/html", 0.8), ("text/plain;format=flowed", 0.5)]);
+    /// ```
+    pub fn get_weighted_list(&self, key: &str) -> Vec<(&str, f32)> {
+        let mut result: Vec<(&str, f32)> = self.get_list(key).into_iter().filter_map(weighted_member).collect();
+        result.sort_by(|a, b| b.1.total_cmp(&a.1));
+        result
+    }
+}
+
+impl Request {
+    /// Sets the `Accept` header with media ranges and their quality values, sorted from the highest quality to
+    /// the lowest one. Quality values are clamped to `0..=1` and rounded to three decimals. An empty list removes
+    /// the header.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::get("https://service.com/users");
+    /// request.accept(&[("text/html", 0.5), ("application/json", 1.0), ("*/*", 0.0125)]);
+    /// assert_eq!(request.headers().get("Accept"), Some("application/json, text/html;q=0.5, */*;q=0.013"));
+    /// ```
+    pub fn accept(&mut self, media_ranges: &[(&str, f32)]) -> &mut Self {
+        if media_ranges.is_empty() {
+            self.headers_mut().remove(ACCEPT);
+        } else {
+            self.insert_header(ACCEPT, format_weighted_list(media_ranges));
+        }
+        self
+    }
+
+    /// Sets the `Accept` header to `application/json`
+    pub fn accept_json(&mut self) -> &mut Self {
+        self.insert_header(ACCEPT, APPLICATION_JSON);
+        self
+    }
+
+    /// Sets the `Accept` header to any media type, `*/*`
+    pub fn accept_any(&mut self) -> &mut Self {
+        self.insert_header(ACCEPT, "*/*");
+        self
+    }
+}
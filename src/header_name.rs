@@ -0,0 +1,151 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Interned standard header names
+
+use std::borrow::Cow;
+use std::fmt;
+
+/// Standard header names, in canonical and lowercase forms
+pub(crate) static STANDARD_NAMES: &[(&str, &str)] = &[
+    ("Accept", "accept"),
+    ("Accept-Charset", "accept-charset"),
+    ("Accept-Encoding", "accept-encoding"),
+    ("Accept-Language", "accept-language"),
+    ("Accept-Ranges", "accept-ranges"),
+    ("Access-Control-Allow-Credentials", "access-control-allow-credentials"),
+    ("Access-Control-Allow-Headers", "access-control-allow-headers"),
+    ("Access-Control-Allow-Methods", "access-control-allow-methods"),
+    ("Access-Control-Allow-Origin", "access-control-allow-origin"),
+    ("Access-Control-Expose-Headers", "access-control-expose-headers"),
+    ("Access-Control-Max-Age", "access-control-max-age"),
+    ("Access-Control-Request-Headers", "access-control-request-headers"),
+    ("Access-Control-Request-Method", "access-control-request-method"),
+    ("Age", "age"),
+    ("Allow", "allow"),
+    ("Alt-Svc", "alt-svc"),
+    ("Authorization", "authorization"),
+    ("Cache-Control", "cache-control"),
+    ("Connection", "connection"),
+    ("Content-Disposition", "content-disposition"),
+    ("Content-Encoding", "content-encoding"),
+    ("Content-Language", "content-language"),
+    ("Content-Length", "content-length"),
+    ("Content-Location", "content-location"),
+    ("Content-Range", "content-range"),
+    ("Content-Security-Policy", "content-security-policy"),
+    ("Content-Type", "content-type"),
+    ("Cookie", "cookie"),
+    ("Date", "date"),
+    ("ETag", "etag"),
+    ("Expect", "expect"),
+    ("Expires", "expires"),
+    ("Forwarded", "forwarded"),
+    ("From", "from"),
+    ("Host", "host"),
+    ("If-Match", "if-match"),
+    ("If-Modified-Since", "if-modified-since"),
+    ("If-None-Match", "if-none-match"),
+    ("If-Range", "if-range"),
+    ("If-Unmodified-Since", "if-unmodified-since"),
+    ("Keep-Alive", "keep-alive"),
+    ("Last-Modified", "last-modified"),
+    ("Link", "link"),
+    ("Location", "location"),
+    ("Max-Forwards", "max-forwards"),
+    ("Origin", "origin"),
+    ("Pragma", "pragma"),
+    ("Prefer", "prefer"),
+    ("Preference-Applied", "preference-applied"),
+    ("Proxy-Authenticate", "proxy-authenticate"),
+    ("Proxy-Authorization", "proxy-authorization"),
+    ("Range", "range"),
+    ("Referer", "referer"),
+    ("Referrer-Policy", "referrer-policy"),
+    ("Retry-After", "retry-after"),
+    ("Server", "server"),
+    ("Set-Cookie", "set-cookie"),
+    ("Strict-Transport-Security", "strict-transport-security"),
+    ("TE", "te"),
+    ("Trailer", "trailer"),
+    ("Transfer-Encoding", "transfer-encoding"),
+    ("Upgrade", "upgrade"),
+    ("User-Agent", "user-agent"),
+    ("Vary", "vary"),
+    ("Via", "via"),
+    ("Warning", "warning"),
+    ("WWW-Authenticate", "www-authenticate"),
+    ("X-Content-Type-Options", "x-content-type-options"),
+    ("X-Forwarded-For", "x-forwarded-for"),
+    ("X-Forwarded-Host", "x-forwarded-host"),
+    ("X-Forwarded-Proto", "x-forwarded-proto"),
+    ("X-Frame-Options", "x-frame-options"),
+    ("X-Request-Id", "x-request-id")
+];
+
+/// Header name. Standard names written in canonical or lowercase form are stored as static references,
+/// custom names (or unusual casings) are kept as given.
+#[derive(Clone)]
+pub(crate) enum HeaderName {
+    /// Index in the standard names table and lowercase form flag
+    Standard(u8, bool),
+    /// Custom name
+    Custom(Cow<'static, str>)
+}
+
+impl HeaderName {
+    /// Creates a name, interning it if it is a standard one
+    pub(crate) fn new<K: Into<Cow<'static, str>>>(name: K) -> HeaderName {
+        let name = name.into();
+        match Self::standard(&name) {
+            Some(standard) => standard,
+            None => HeaderName::Custom(name)
+        }
+    }
+
+    fn standard(name: &str) -> Option<HeaderName> {
+        let i = STANDARD_NAMES.binary_search_by(|(_, lowercase)| {
+            lowercase.bytes().cmp(name.bytes().map(|b| b.to_ascii_lowercase()))
+        }).ok()?;
+        let (canonical, lowercase) = STANDARD_NAMES[i];
+        if name == canonical {
+            Some(HeaderName::Standard(i as u8, false))
+        } else if name == lowercase {
+            Some(HeaderName::Standard(i as u8, true))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the name as inserted
+    pub(crate) fn as_str(&self) -> &str {
+        match self {
+            HeaderName::Standard(i, false) => STANDARD_NAMES[*i as usize].0,
+            HeaderName::Standard(i, true) => STANDARD_NAMES[*i as usize].1,
+            HeaderName::Custom(name) => name.as_ref()
+        }
+    }
+
+    /// Checks if the name is interned
+    pub(crate) fn is_standard(&self) -> bool {
+        matches!(self, HeaderName::Standard(_, _))
+    }
+}
+
+impl fmt::Debug for HeaderName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Redirect history of responses
+
+use crate::{Request, Response};
+
+/// Request and response of a hop of a redirect chain
+#[derive(Clone)]
+pub struct Exchange {
+    /// Request sent
+    pub request: Request,
+    /// Redirection response received
+    pub response: Response
+}
+
+impl Exchange {
+    /// Constructor
+    pub fn new(request: Request, response: Response) -> Exchange {
+        Exchange { request, response }
+    }
+
+    /// Gets the URL the response redirects to, resolved against the request URL
+    pub fn redirect_url(&self) -> Option<String> {
+        self.response.location(self.request.url())
+    }
+}
+
+impl Response {
+    /// Gets the redirect chain that led to this response, from the first request, as recorded by the
+    /// transport that followed the redirects
+    ///
+    /// ```
+    /// use wrequest::{Exchange, Request, Response, HTTP_200_OK, HTTP_301_MOVED_PERMANENTLY};
+    ///
+    /// let mut moved = Response::new(HTTP_301_MOVED_PERMANENTLY);
+    /// moved.insert_header("Location", "/v2/users");
+    ///
+    /// let mut response = Response::new(HTTP_200_OK);
+    /// response.push_history(Exchange::new(Request::get("https://service.com/users"), moved));
+    ///
+    /// assert_eq!(response.redirect_count(), 1);
+    /// assert_eq!(response.final_url().as_deref(), Some("https://service.com/v2/users"));
+    /// ```
+    pub fn history(&self) -> &[Exchange] {
+        &self.history
+    }
+
+    /// Appends a hop to the redirect chain
+    pub fn push_history(&mut self, exchange: Exchange) -> &mut Self {
+        self.history.push(exchange);
+        self
+    }
+
+    /// Sets the whole redirect chain
+    pub fn set_history(&mut self, history: Vec<Exchange>) -> &mut Self {
+        self.history = history;
+        self
+    }
+
+    /// Number of redirects followed to get this response
+    pub fn redirect_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Gets the URL this response was fetched from: the target of the last redirect, `None` if no redirect
+    /// was followed or the last one has no `Location` header
+    pub fn final_url(&self) -> Option<String> {
+        self.history.last().and_then(Exchange::redirect_url)
+    }
+
+    /// Gets the URL of the first request of the redirect chain, `None` if no redirect was followed
+    pub fn original_url(&self) -> Option<&str> {
+        self.history.first().map(|exchange| exchange.request.url())
+    }
+}
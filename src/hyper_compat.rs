@@ -0,0 +1,216 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions to and from hyper 0.14 messages (feature `hyper`)
+
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+use bytes::Bytes;
+use hyper::Body;
+use hyper::body::HttpBody;
+use hyper::header::{HeaderName, HeaderValue};
+use wcookie::SetCookie;
+use crate::{BodyTooLarge, HeaderMap, HttpVersion, MessageBody, Request, Response, uri, parse_cookie_header, set_cookie_header};
+
+/// `Cookie` header name
+const COOKIE: &str = "Cookie";
+/// `Set-Cookie` header name
+const SET_COOKIE: &str = "Set-Cookie";
+/// `WWW-Authenticate` header name
+const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+/// `Proxy-Authenticate` header name
+const PROXY_AUTHENTICATE: &str = "Proxy-Authenticate";
+
+impl Request {
+    /// Converts the request into a hyper request with its buffered body.
+    ///
+    /// The URI includes the request params and the cookies are sent in a `Cookie` header.
+    pub fn into_hyper(mut self) -> Result<hyper::Request<Body>, Error> {
+        let body = match std::mem::replace(&mut self.base.body, MessageBody::None) {
+            MessageBody::Single(data) => Body::from(data),
+            MessageBody::Shared(data) => Body::from(Bytes::from_owner(data)),
+            _ => Body::empty()
+        };
+        self.into_hyper_with_body(body)
+    }
+
+    /// Converts the request into a hyper request with the given `body`, for example a streaming one.
+    /// The request body, if any, is ignored.
+    pub fn into_hyper_with_body(self, body: Body) -> Result<hyper::Request<Body>, Error> {
+        let method = hyper::Method::from_bytes(self.method.to_string().as_bytes()).map_err(invalid_input)?;
+        let uri = hyper::Uri::from_str(&self.full_url()).map_err(invalid_input)?;
+
+        let mut result = hyper::Request::builder().method(method).uri(uri).body(body).map_err(invalid_input)?;
+        copy_headers(&self.headers, result.headers_mut())?;
+        if let Some(cookies) = self.cookie_header() {
+            let value = match result.headers().get(COOKIE).and_then(|v| v.to_str().ok()) {
+                Some(existing) => format!("{}; {}", existing, cookies),
+                None => cookies
+            };
+            result.headers_mut().insert(hyper::header::COOKIE, HeaderValue::from_str(&value).map_err(invalid_input)?);
+        }
+        Ok(result)
+    }
+
+    /// Collects a hyper request into a `Request`. The URI query string is decoded into the request params
+    /// and the `Cookie` headers into the request cookies.
+    pub async fn from_hyper(request: hyper::Request<Body>) -> Result<Request, Error> {
+        let (parts, body) = request.into_parts();
+        let method = parts.method.as_str().parse()?;
+        let uri = parts.uri.to_string();
+        let (target, query) = uri::split_query(&uri);
+
+        let mut result = Request::new(method, target);
+        result.set_version(from_hyper_version(parts.version));
+        if let Some(query) = query {
+            for (key, value) in uri::parse_query(query) {
+                result.insert_param(key, value);
+            }
+        }
+        for value in parts.headers.get_all(hyper::header::COOKIE) {
+            for (key, cookie) in parse_cookie_header(value.to_str().map_err(invalid_data)?) {
+                result.insert_cookie(key, cookie);
+            }
+        }
+        for (name, value) in collect_headers(&parts.headers)? {
+            if !name.eq_ignore_ascii_case(COOKIE) {
+                result.insert_header(name, value);
+            }
+        }
+
+        let data = read_body(body, result.max_body_size()).await?;
+        if !data.is_empty() {
+            result.set_body(data)?;
+        }
+        Ok(result)
+    }
+}
+
+impl Response {
+    /// Converts the response into a hyper response. Cookies are sent as `Set-Cookie` headers and
+    /// authorization guides as `WWW-Authenticate` and `Proxy-Authenticate` headers.
+    pub fn into_hyper(self) -> Result<hyper::Response<Body>, Error> {
+        let body = match self.base.body {
+            MessageBody::Single(data) => Body::from(data),
+            MessageBody::Shared(data) => Body::from(Bytes::from_owner(data)),
+            _ => Body::empty()
+        };
+        let mut result = hyper::Response::builder().status(self.status_code).body(body).map_err(invalid_input)?;
+        let headers = result.headers_mut();
+        copy_headers(&self.base.headers, headers)?;
+        for cookie in self.cookies.iter() {
+            headers.append(hyper::header::SET_COOKIE, HeaderValue::from_str(&set_cookie_header(cookie)).map_err(invalid_input)?);
+        }
+        for auth in self.auth.iter() {
+            headers.append(hyper::header::WWW_AUTHENTICATE, HeaderValue::from_str(auth).map_err(invalid_input)?);
+        }
+        for auth in self.proxy_auth.iter() {
+            headers.append(hyper::header::PROXY_AUTHENTICATE, HeaderValue::from_str(auth).map_err(invalid_input)?);
+        }
+        Ok(result)
+    }
+
+    /// Collects a hyper response into a `Response`, reading its whole body.
+    ///
+    /// `Set-Cookie` headers are parsed into the response cookies (invalid ones are ignored),
+    /// `WWW-Authenticate` and `Proxy-Authenticate` headers into the authorization guides and repeated
+    /// headers are joined with commas.
+    pub async fn from_hyper(response: hyper::Response<Body>) -> Result<Response, Error> {
+        let (parts, body) = response.into_parts();
+        let mut result = Response::new(parts.status.as_u16());
+        result.set_version(from_hyper_version(parts.version));
+
+        for value in parts.headers.get_all(hyper::header::SET_COOKIE) {
+            if let Ok(cookie) = value.to_str().map_err(invalid_data).and_then(|v| SetCookie::from_str(v).map_err(invalid_data)) {
+                result.insert_cookie(cookie);
+            }
+        }
+        for (name, value) in collect_headers(&parts.headers)? {
+            if !name.eq_ignore_ascii_case(SET_COOKIE) && !name.eq_ignore_ascii_case(WWW_AUTHENTICATE)
+                && !name.eq_ignore_ascii_case(PROXY_AUTHENTICATE) {
+                result.insert_header(name, value);
+            }
+        }
+        for value in parts.headers.get_all(hyper::header::WWW_AUTHENTICATE) {
+            result.insert_auth_headers(value.to_str().map_err(invalid_data)?);
+        }
+        for value in parts.headers.get_all(hyper::header::PROXY_AUTHENTICATE) {
+            result.insert_proxy_auth_header(value.to_str().map_err(invalid_data)?);
+        }
+
+        let data = read_body(body, result.max_body_size()).await?;
+        if !data.is_empty() {
+            result.set_body(data)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Reads a hyper body, failing with a [BodyTooLarge] error as soon as it exceeds `limit` bytes
+async fn read_body(mut body: Body, limit: usize) -> Result<Vec<u8>, Error> {
+    let declared = body.size_hint().exact().and_then(|size| usize::try_from(size).ok());
+    if let Some(size) = declared.filter(|size| *size > limit) {
+        return Err(BodyTooLarge::error(limit, Some(size)));
+    }
+    let mut data = Vec::with_capacity(declared.unwrap_or(0));
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(Error::other)?;
+        if data.len() + chunk.len() > limit {
+            return Err(BodyTooLarge::error(limit, None));
+        }
+        data.extend_from_slice(&chunk);
+    }
+    Ok(data)
+}
+
+/// Converts a hyper protocol version, HTTP/0.9 is taken as HTTP/1.0
+fn from_hyper_version(version: hyper::Version) -> HttpVersion {
+    match version {
+        hyper::Version::HTTP_09 | hyper::Version::HTTP_10 => HttpVersion::Http10,
+        hyper::Version::HTTP_2 => HttpVersion::Http2,
+        hyper::Version::HTTP_3 => HttpVersion::Http3,
+        _ => HttpVersion::Http11
+    }
+}
+
+/// Copies the message headers into a hyper header map
+fn copy_headers(headers: &HeaderMap, target: &mut hyper::HeaderMap) -> Result<(), Error> {
+    for (name, value) in headers.iter() {
+        let name = HeaderName::from_bytes(name.as_bytes()).map_err(invalid_input)?;
+        target.append(name, HeaderValue::from_str(value).map_err(invalid_input)?);
+    }
+    Ok(())
+}
+
+/// Collects the hyper headers, joining the values of repeated headers with commas
+fn collect_headers(headers: &hyper::HeaderMap) -> Result<Vec<(String, String)>, Error> {
+    let mut result = Vec::with_capacity(headers.keys_len());
+    for name in headers.keys() {
+        let values: Result<Vec<&str>, Error> = headers.get_all(name).iter()
+            .map(|v| v.to_str().map_err(invalid_data))
+            .collect();
+        result.push((name.as_str().to_string(), values?.join(", ")));
+    }
+    Ok(result)
+}
+
+fn invalid_input<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidInput, e)
+}
+
+fn invalid_data<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidData, e)
+}
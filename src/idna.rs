@@ -0,0 +1,310 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Internationalized domain names (RFC 5890) in punycode (RFC 3492)
+//!
+//! Unicode hosts are lowercased and converted to their ASCII form label by label. Unicode normalization
+//! (NFC) and the full IDNA mapping tables (UTS #46) are not applied.
+
+use std::io::{Error, ErrorKind};
+use crate::{Request, uri};
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Prefix of the punycode labels
+const ACE_PREFIX: &str = "xn--";
+/// Maximum length of a DNS label, in bytes
+const MAX_LABEL_LENGTH: usize = 63;
+
+fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+    let mut delta = if first { delta / DAMP } else { delta / 2 };
+    delta += delta / points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+fn threshold(k: u32, bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(digit: u32) -> char {
+    (if digit < 26 { b'a' + digit as u8 } else { b'0' + (digit - 26) as u8 }) as char
+}
+
+fn decode_digit(c: u8) -> Option<u32> {
+    match c {
+        b'a'..=b'z' => Some((c - b'a') as u32),
+        b'A'..=b'Z' => Some((c - b'A') as u32),
+        b'0'..=b'9' => Some((c - b'0') as u32 + 26),
+        _ => None
+    }
+}
+
+/// Encodes a label in punycode, without the `xn--` prefix. Returns `None` on overflow.
+pub(crate) fn punycode_encode(input: &str) -> Option<String> {
+    let input: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: String = input.iter().filter(|c| **c < INITIAL_N).map(|c| char::from(*c as u8)).collect();
+    let basic = output.len() as u32;
+    let mut handled = basic;
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    while (handled as usize) < input.len() {
+        let m = *input.iter().filter(|c| **c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(handled + 1)?)?;
+        n = m;
+        for c in input.iter() {
+            if *c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if *c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+    Some(output)
+}
+
+/// Decodes a punycode label, without the `xn--` prefix. Returns `None` if it is malformed.
+pub(crate) fn punycode_decode(input: &str) -> Option<String> {
+    let (basic, extended) = match input.rfind('-') {
+        Some(dash) => (&input[..dash], &input[dash + 1..]),
+        None => ("", input)
+    };
+    if !basic.is_ascii() {
+        return None;
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.bytes();
+    let mut remaining = extended.len();
+    while remaining > 0 {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(digits.next()?)?;
+            remaining -= 1;
+            i = i.checked_add(digit.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length)?;
+        i %= length;
+        output.insert(i as usize, char::from_u32(n)?);
+        i += 1;
+    }
+    Some(output.into_iter().collect())
+}
+
+/// Splits a host into labels, with the ideographic full stops as separators too
+fn labels(host: &str) -> impl Iterator<Item = &str> {
+    host.split(['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'])
+}
+
+/// Converts a host to its ASCII form, encoding the Unicode labels in lowercase punycode. Returns an
+/// `ErrorKind::InvalidInput` error if a label is too long.
+pub(crate) fn to_ascii(host: &str) -> Result<String, Error> {
+    let mut result = Vec::new();
+    for label in labels(host) {
+        let label = if label.is_ascii() {
+            label.to_ascii_lowercase()
+        } else {
+            punycode_encode(&label.to_lowercase())
+                .map(|encoded| format!("{}{}", ACE_PREFIX, encoded))
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Host label is too long"))?
+        };
+        if label.len() > MAX_LABEL_LENGTH {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Host label {} is too long", label)));
+        }
+        result.push(label);
+    }
+    Ok(result.join("."))
+}
+
+/// Converts a host to its Unicode form, decoding the punycode labels. Malformed labels are kept as they are,
+/// including the ones that decode to ASCII only (RFC 5891, section 5.4), as `xn--paypal-` would be displayed
+/// like the different `paypal` label.
+pub(crate) fn to_unicode(host: &str) -> String {
+    host.split('.')
+        .map(|label| match label.get(..ACE_PREFIX.len()) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(ACE_PREFIX) =>
+                punycode_decode(&label[ACE_PREFIX.len()..])
+                    .filter(|decoded| !decoded.is_ascii())
+                    .unwrap_or_else(|| label.to_string()),
+            _ => label.to_string()
+        })
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Converts the host of an absolute URL to its ASCII form. URLs with ASCII hosts or hosts that cannot be
+/// converted are returned as they are.
+pub(crate) fn ascii_url(url: String) -> String {
+    match uri::host_range(&url) {
+        Some((start, end)) if !url[start..end].is_ascii() => match to_ascii(&url[start..end]) {
+            Ok(host) => format!("{}{}{}", &url[..start], host, &url[end..]),
+            Err(_) => url
+        },
+        _ => url
+    }
+}
+
+/// Writing systems told apart to detect homographs
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Han,
+    Hiragana,
+    Katakana,
+    Hangul,
+    /// Other script, by Unicode block of 128 code points
+    Other(u32)
+}
+
+/// Gets the script of a character, `None` for digits, hyphens and other characters common to all scripts
+fn script(c: char) -> Option<Script> {
+    let code = c as u32;
+    match code {
+        0x30..=0x39 | 0x2D | 0x5F | 0x300..=0x36F | 0x30FC => None,
+        0x41..=0x5A | 0x61..=0x7A | 0xC0..=0x24F | 0x1E00..=0x1EFF => Some(Script::Latin),
+        0x370..=0x3FF | 0x1F00..=0x1FFF => Some(Script::Greek),
+        0x400..=0x52F => Some(Script::Cyrillic),
+        0x530..=0x58F => Some(Script::Armenian),
+        0x590..=0x5FF => Some(Script::Hebrew),
+        0x600..=0x6FF | 0x750..=0x77F => Some(Script::Arabic),
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF => Some(Script::Han),
+        0x3040..=0x309F => Some(Script::Hiragana),
+        0x30A0..=0x30FF => Some(Script::Katakana),
+        0x1100..=0x11FF | 0xAC00..=0xD7AF => Some(Script::Hangul),
+        _ if code < 0x80 => None,
+        _ => Some(Script::Other(code >> 7))
+    }
+}
+
+/// Checks a label mixes scripts, like Latin and Cyrillic. Latin is allowed with Han and either Japanese kana
+/// or Korean Hangul, as in the highly restrictive profile of UTS #39.
+fn is_mixed_script_label(label: &str) -> bool {
+    let mut scripts: Vec<Script> = Vec::new();
+    for script in label.chars().filter_map(script) {
+        if !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    let allowed = |allowed: &[Script]| scripts.iter().all(|script| allowed.contains(script));
+    scripts.len() > 1
+        && !allowed(&[Script::Latin, Script::Han, Script::Hiragana, Script::Katakana])
+        && !allowed(&[Script::Latin, Script::Han, Script::Hangul])
+}
+
+/// Checks a label of a host, in Unicode or punycode, mixes scripts
+pub(crate) fn is_mixed_script(host: &str) -> bool {
+    to_unicode(host).split('.').any(is_mixed_script_label)
+}
+
+impl Request {
+    /// Gets the host of the URL, without user info nor port, or `None` for relative URLs. Unicode hosts are
+    /// converted to punycode when the request is created, so the host is always in ASCII.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let request = Request::get("https://Bücher.example:8443/search");
+    /// assert_eq!(request.url(), "https://xn--bcher-kva.example:8443/search");
+    /// assert_eq!(request.host(), Some("xn--bcher-kva.example"));
+    /// assert_eq!(request.unicode_host().as_deref(), Some("bücher.example"));
+    /// assert_eq!(request.unicode_url(), "https://bücher.example:8443/search");
+    /// ```
+    pub fn host(&self) -> Option<&str> {
+        uri::host_range(self.url()).map(|(start, end)| &self.url()[start..end])
+    }
+
+    /// Gets the display form of the host, with the punycode labels decoded
+    pub fn unicode_host(&self) -> Option<String> {
+        self.host().map(to_unicode)
+    }
+
+    /// Gets the display form of the URL, with the punycode labels of the host decoded
+    pub fn unicode_url(&self) -> String {
+        match uri::host_range(self.url()) {
+            Some((start, end)) => format!("{}{}{}", &self.url()[..start], to_unicode(&self.url()[start..end]), &self.url()[end..]),
+            None => self.url().to_string()
+        }
+    }
+
+    /// Checks a label of the host mixes scripts, like the Cyrillic `а` in `pаypal.com`, a common homograph
+    /// attack. Latin with Han and Japanese kana or Korean Hangul is allowed.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// assert!(Request::get("https://p\u{430}ypal.com").has_mixed_script_host());
+    /// assert!(!Request::get("https://яндекс.рф").has_mixed_script_host());
+    /// assert!(!Request::get("https://example.рф").has_mixed_script_host());
+    /// ```
+    pub fn has_mixed_script_host(&self) -> bool {
+        self.host().is_some_and(is_mixed_script)
+    }
+}
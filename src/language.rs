@@ -0,0 +1,116 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Language tags of the `Content-Language` and `Accept-Language` headers (RFC 9110, sections 8.5 and 12.5.4)
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::language::LanguageTag;
+//!
+//! let mut request = Request::get("https://service.com/docs/1");
+//! request.insert_header("Accept-Language", "fr-CH, fr;q=0.9, en;q=0.8");
+//! let preferred = request.accept_languages();
+//! assert_eq!(preferred[0].0.primary_language(), "fr");
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.set_content_language(&["fr-CH"]).unwrap();
+//! assert_eq!(response.content_language(), vec![LanguageTag::parse("fr-ch").unwrap()]);
+//! ```
+
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use crate::{HttpMessage, Request};
+
+/// `Content-Language` header name
+pub const CONTENT_LANGUAGE: &str = "Content-Language";
+/// `Accept-Language` header name
+pub const ACCEPT_LANGUAGE: &str = "Accept-Language";
+
+/// Language tag, like `en` or `es-419`, with the basic BCP 47 syntax: a primary subtag of 1 to 8 letters
+/// followed by subtags of 1 to 8 letters or digits separated by `-`. Tags are compared case-insensitively.
+#[derive(Clone, Debug)]
+pub struct LanguageTag(String);
+
+impl LanguageTag {
+    /// Parses a language tag, `None` if it has not the basic BCP 47 syntax
+    pub fn parse(value: &str) -> Option<LanguageTag> {
+        let value = value.trim();
+        let mut subtags = value.split('-');
+        let primary = subtags.next()?;
+        let valid = |subtag: &str| (1..=8).contains(&subtag.len());
+        if !valid(primary) || !primary.bytes().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        if !subtags.all(|subtag| valid(subtag) && subtag.bytes().all(|c| c.is_ascii_alphanumeric())) {
+            return None;
+        }
+        Some(LanguageTag(value.to_string()))
+    }
+
+    /// Gets the tag as it was written
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Gets the primary language subtag, like `en` for `en-US`
+    pub fn primary_language(&self) -> &str {
+        self.0.split('-').next().unwrap_or("")
+    }
+}
+
+impl PartialEq for LanguageTag {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for LanguageTag {}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl HttpMessage {
+    /// Sets the `Content-Language` header with the languages of the intended audience. Fails with an
+    /// `ErrorKind::InvalidInput` error if a tag has not the basic BCP 47 syntax.
+    pub fn set_content_language<S: AsRef<str>>(&mut self, languages: &[S]) -> Result<&mut Self, Error> {
+        let mut tags = Vec::with_capacity(languages.len());
+        for language in languages {
+            let tag = LanguageTag::parse(language.as_ref())
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("Invalid language tag {:?}", language.as_ref())))?;
+            tags.push(tag.0);
+        }
+        self.insert_header(CONTENT_LANGUAGE, tags.join(", "));
+        Ok(self)
+    }
+
+    /// Gets the language tags of the `Content-Language` header, without the invalid ones
+    pub fn content_language(&self) -> Vec<LanguageTag> {
+        self.headers().get_list(CONTENT_LANGUAGE).into_iter().filter_map(LanguageTag::parse).collect()
+    }
+}
+
+impl Request {
+    /// Gets the language tags of the `Accept-Language` header with their quality values, from the most
+    /// preferred to the least one, see [crate::HeaderMap::get_weighted_list]. The `*` range and invalid tags
+    /// are skipped.
+    pub fn accept_languages(&self) -> Vec<(LanguageTag, f32)> {
+        self.headers().get_weighted_list(ACCEPT_LANGUAGE).into_iter()
+            .filter_map(|(tag, quality)| LanguageTag::parse(tag).map(|tag| (tag, quality)))
+            .collect()
+    }
+}
@@ -41,7 +41,7 @@
 //! };
 //! 
 //! // JSON Object is encoded at the body
-//! request.set_json(&data);
+//! request.set_json(&data).unwrap();
 //! 
 //! assert_eq!(request.headers().get("Content-Type").unwrap(), "application/json" );
 //! 
@@ -74,7 +74,7 @@
 //! response.insert_cookie(cookie);
 //! 
 //! // JSON Object is encoded at the body
-//! response.set_json(&data);
+//! response.set_json(&data).unwrap();
 //! 
 //! assert_eq!(response.headers().get("Content-Type").unwrap(), "application/json" );
 //! 
@@ -86,23 +86,134 @@
 
 #![allow(dead_code)]
 
-use unicase::UniCase;
-use case_insensitive_hashmap::CaseInsensitiveHashMap;
-use std::str::from_utf8;
+use std::str::{from_utf8, FromStr};
 use std::io::{ErrorKind, Error};
 use json::JsonValue;
 use std::fmt;
-use std::collections::HashMap;
 use std::iter::Iterator;
 use wcookie::SetCookie;
 use std::ops::{Deref, DerefMut};
+use std::borrow::Cow;
+use std::sync::{Arc, OnceLock};
+use smallvec::SmallVec;
+use header_name::HeaderName;
+
+mod uri;
+mod curl;
+mod header_name;
+mod h2;
+mod header_list;
+mod idna;
+#[cfg(feature = "simd")]
+mod simd;
+
+pub mod sse;
+pub mod har;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod matchers;
+pub mod mock;
+pub mod cassette;
+pub mod middleware;
+pub mod transport;
+pub mod ratelimit;
+pub mod prefer;
+pub mod via;
+pub mod security;
+pub mod csp;
+pub mod multipart;
+pub mod wire;
+pub mod upgrade;
+pub mod alt_svc;
+pub mod connection;
+pub mod transfer;
+pub mod batch;
+pub mod conditional;
+pub mod language;
+pub mod policy;
+#[cfg(feature = "signed-url")]
+pub mod signed_url;
+pub mod range;
+pub mod freshness;
+pub mod view;
+#[cfg(feature = "jwt")]
+pub mod jwt;
+#[cfg(feature = "serde")]
+pub mod fixtures;
+#[cfg(feature = "tower")]
+pub mod tower;
+#[cfg(feature = "fuzzing")]
+pub mod fuzzing;
+#[cfg(feature = "hyper")]
+mod hyper_compat;
+#[cfg(feature = "reqwest")]
+mod reqwest_compat;
+#[cfg(feature = "ureq")]
+mod ureq_compat;
+#[cfg(feature = "serde")]
+mod serde_compat;
+#[cfg(feature = "serde")]
+pub use serde_compat::SCHEMA_VERSION;
+
+mod problem;
+pub use problem::Problem;
+mod extensions;
+pub use extensions::Extensions;
+mod equivalence;
+pub use equivalence::EquivalenceOptions;
+mod fingerprint;
+pub use fingerprint::FingerprintOptions;
+mod limits;
+mod status;
+pub use status::{StatusClass, StatusCode, StatusError, register_status_code};
+mod credentials;
+mod history;
+pub use history::Exchange;
+mod template;
+pub use template::RequestTemplate;
+mod defaults;
+pub use defaults::Defaults;
+mod size;
+mod ssrf;
+mod sniff;
+mod encoding;
+pub use encoding::TextEncoding;
+pub use ssrf::UrlClassification;
+pub use credentials::{AUTHORIZATION, Credentials};
+pub use limits::{BodyTooLarge, DEFAULT_MAX_BODY_SIZE, default_max_body_size, set_default_max_body_size};
 
 /// `Content-Type` header name
 pub const CONTENT_TYPE: &str = "Content-Type";
 /// `Content-Type` header value for JSON encoded in UTF-8
 pub const APPLICATION_JSON: &str = "application/json";
+/// `Content-Type` header value for binary data of unknown type
+pub const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+/// `Content-Type` header value for HTML forms
+pub const APPLICATION_FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
 /// `Accept` header name
 pub const ACCEPT: &str = "Accept";
+/// `Content-Type` header value for newline-delimited JSON (JSON Lines)
+pub const APPLICATION_NDJSON: &str = "application/x-ndjson";
+/// `Content-Type` header value for Server-Sent Events
+pub const TEXT_EVENT_STREAM: &str = "text/event-stream";
+/// `Content-Type` header value for Problem Details encoded in JSON
+pub const APPLICATION_PROBLEM_JSON: &str = "application/problem+json";
+/// `Idempotency-Key` header name
+pub const IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+/// `Expect` header name
+pub const EXPECT: &str = "Expect";
+/// `Expect` header value of the requests that wait for a `100 Continue` response before sending their body
+pub const EXPECT_CONTINUE: &str = "100-continue";
+/// `Link` header name
+pub const LINK: &str = "Link";
+/// `Allow` header name
+pub const ALLOW: &str = "Allow";
+/// `Location` header name
+pub const LOCATION: &str = "Location";
+/// `Content-Location` header name
+pub const CONTENT_LOCATION: &str = "Content-Location";
+/// `X-Request-Id` header name, used to correlate the messages of an exchange
+pub const X_REQUEST_ID: &str = "X-Request-Id";
 
 /// HTTP Request Method
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -126,11 +237,87 @@ impl fmt::Display for HttpMethod {
     }
 }
 
+impl HttpMethod {
+    /// Checks if the method is safe (read-only), as defined by RFC 9110: `GET`, `HEAD`, `OPTIONS` and `TRACE`
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Self::GET | Self::HEAD | Self::OPTIONS | Self::TRACE)
+    }
+
+    /// Checks if the method is idempotent, as defined by RFC 9110: safe methods plus `PUT` and `DELETE`
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Self::PUT | Self::DELETE)
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = Error;
+
+    /// Parses a method name. Method names are case-sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "GET" => Ok(Self::GET),
+            "HEAD" => Ok(Self::HEAD),
+            "POST" => Ok(Self::POST),
+            "PUT" => Ok(Self::PUT),
+            "DELETE" => Ok(Self::DELETE),
+            "CONNECT" => Ok(Self::CONNECT),
+            "OPTIONS" => Ok(Self::OPTIONS),
+            "TRACE" => Ok(Self::TRACE),
+            "PATCH" => Ok(Self::PATCH),
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown HTTP method {}", s)))
+        }
+    }
+}
+
+/// HTTP protocol version
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub enum HttpVersion {
+    /// HTTP/1.0
+    Http10,
+    /// HTTP/1.1, the default version of the messages
+    #[default]
+    Http11,
+    /// HTTP/2
+    Http2,
+    /// HTTP/3
+    Http3
+}
+
+impl fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}",
+                match self {
+                    Self::Http10 => "HTTP/1.0",
+                    Self::Http11 => "HTTP/1.1",
+                    Self::Http2 => "HTTP/2",
+                    Self::Http3 => "HTTP/3"
+                }
+        )
+    }
+}
+
+impl FromStr for HttpVersion {
+    type Err = Error;
+
+    /// Parses a protocol version as written in HTTP/1 start lines, like `HTTP/1.1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "HTTP/1.0" => Ok(Self::Http10),
+            "HTTP/1.1" => Ok(Self::Http11),
+            "HTTP/2" | "HTTP/2.0" => Ok(Self::Http2),
+            "HTTP/3" | "HTTP/3.0" => Ok(Self::Http3),
+            _ => Err(Error::new(ErrorKind::InvalidInput, format!("Unknown HTTP version {}", s)))
+        }
+    }
+}
+
 // Message Body
 #[derive(Clone, PartialEq, Debug)]
 enum MessageBody {
     None,
     Single(Vec<u8>),
+    /// Single body shared by several messages, cloning it does not copy the data
+    Shared(Arc<[u8]>),
     MultiPart
 }
 
@@ -139,31 +326,176 @@ impl MessageBody {
         matches!(*self, Self::None)
     }
     fn is_single(&self) -> bool {
-        matches!(*self, Self::Single(_))
+        matches!(*self, Self::Single(_) | Self::Shared(_))
+    }
+    fn as_slice(&self) -> Option<&[u8]> {
+        match self {
+            Self::Single(data) => Some(data),
+            Self::Shared(data) => Some(data),
+            _ => None
+        }
+    }
+    /// Gets the single body for appending data, copying a shared one
+    fn as_vec_mut(&mut self) -> Option<&mut Vec<u8>> {
+        if let Self::Shared(data) = self {
+            *self = Self::Single(data.to_vec());
+        }
+        match self {
+            Self::Single(data) => Some(data),
+            _ => None
+        }
     }
     fn is_multipart(&self) -> bool {
         matches!(*self, Self::MultiPart)
     }
 }
 
-/// Map of HTTP message headers. Header keys are case-insensitive.
+/// Checks a header name is a valid token (RFC 9110): not empty, made of letters, digits and ``!#$%&'*+-.^_`|~``
+pub fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(is_token_char)
+}
+
+/// Checks a header value has no control characters but horizontal tabs, so it cannot break the message framing
+/// (no CR or LF to inject headers)
+pub fn is_valid_header_value(value: &str) -> bool {
+    value.bytes().all(|b| b == b'\t' || !b.is_ascii_control())
+}
+
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+/// Map of HTTP message headers. Header keys are case-insensitive, but they keep the casing they were inserted with
+/// and iteration follows the insertion order, so serialized messages are reproducible.
+///
+/// Standard header names (like `Content-Type` or `accept`) are interned and up to [HEADERS_INLINE] headers are stored
+/// inline. Names and values are `Cow<'static, str>`, so static strings like [APPLICATION_JSON] are never copied and
+/// typical messages only allocate for dynamic names and values.
+///
+/// [HeaderMap::insert] is lenient, it sanitizes invalid names and values, while [HeaderMap::try_insert] is strict and
+/// rejects them:
+///
+/// ```
+/// use wrequest::HeaderMap;
+///
+/// let mut headers = HeaderMap::new();
+/// assert!(headers.try_insert("X-Bad", "value\r\nInjected: yes").is_err());
+///
+/// headers.insert("X-Bad", "value\r\nInjected: yes");
+/// assert_eq!(headers.get("X-Bad"), Some("value  Injected: yes"));
+/// ```
+#[derive(Clone)]
 pub struct HeaderMap {
-    map : CaseInsensitiveHashMap<String>
+    /// Headers in insertion order
+    entries: SmallVec<[(HeaderName, Cow<'static, str>); HEADERS_INLINE]>
 }
 
+/// Number of headers a [HeaderMap] stores without allocating
+pub const HEADERS_INLINE: usize = 16;
+
 impl HeaderMap {
     /// Constructor
     pub fn new() -> HeaderMap {
         HeaderMap {
-            map: CaseInsensitiveHashMap::new()
+            entries: SmallVec::new()
+        }
+    }
+
+    /// Constructor with room for `capacity` headers without reallocating
+    pub fn with_capacity(capacity: usize) -> HeaderMap {
+        HeaderMap {
+            entries: SmallVec::with_capacity(capacity)
         }
     }
 
-    /// Insert a header with `key` and `value`. Returns `true` if there was a previous header with the same `key`.
+    /// Reserves room for at least `additional` more headers
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    /// Insert a header with `key` and `value`. Returns `true` if there was a previous header with the same `key`,
+    /// whose value is replaced keeping its position and casing.
+    ///
+    /// Invalid characters are removed from the `key` and control characters of the `value` are replaced by spaces.
+    /// Headers whose `key` is empty once sanitized are ignored.
     pub fn insert<K,V>(&mut self, key: K, value: V) -> bool
-    where K: Into<String>,
-          V: Into<String> {
-        self.map.insert(key.into(),value.into()).is_some()
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        let key = key.into();
+        let key = if is_valid_header_name(&key) {
+            HeaderName::new(key)
+        } else {
+            let mut key = key.into_owned();
+            key.retain(|c| c.is_ascii() && is_token_char(c as u8));
+            if key.is_empty() {
+                return false;
+            }
+            HeaderName::new(key)
+        };
+        let mut value = value.into();
+        if !is_valid_header_value(&value) {
+            value = Cow::Owned(value.chars().map(|c| if c != '\t' && c.is_ascii_control() {' '} else {c}).collect());
+        }
+        self.put(key, value)
+    }
+
+    /// Insert a header with `key` and `value`, checking both are valid. Returns `true` if there was a previous
+    /// header with the same `key`, or an `ErrorKind::InvalidInput` error if the name or the value are invalid.
+    pub fn try_insert<K,V>(&mut self, key: K, value: V) -> Result<bool, Error>
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        let key = key.into();
+        if !is_valid_header_name(&key) {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid header name {:?}", key)));
+        }
+        let value = value.into();
+        if !is_valid_header_value(&value) {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid value of header {}", key)));
+        }
+        Ok(self.put(HeaderName::new(key), value))
+    }
+
+    /// Replaces the value of an existing header or appends a new one
+    fn put(&mut self, key: HeaderName, value: Cow<'static, str>) -> bool {
+        match self.position(key.as_str()) {
+            Some(i) => {
+                self.entries[i].1 = value;
+                true
+            },
+            None => {
+                self.entries.push((key, value));
+                false
+            }
+        }
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| k.as_str().eq_ignore_ascii_case(key))
+    }
+
+    /// Removes the leading and trailing whitespace of the header values
+    fn trim_values(&mut self) {
+        for (_, value) in self.entries.iter_mut() {
+            let trimmed = value.trim_matches(|c| c == ' ' || c == '\t');
+            if trimmed.len() != value.len() {
+                *value = Cow::Owned(trimmed.to_string());
+            }
+        }
+    }
+
+    /// Removes a header, returning its value if any
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.position(key).map(|i| self.entries.remove(i).1.into_owned())
+    }
+
+    /// Number of headers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if there are no headers
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
     }
    
     /// Returns `true` if there is a header with `key`. Note keys are case-insensitive.
@@ -171,33 +503,54 @@ impl HeaderMap {
     where
         K: Into<String>
     {
-        self.map.contains_key(key.into())
+        self.position(&key.into()).is_some()
     }
 
     /// Gets a reference to the header value if any.
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.map.get(key).map(|s| s.as_str())
+        self.position(key).map(|i| self.entries[i].1.as_ref())
     }
 
     /// Gets an iterator to a tuple of `(key, value)`
-    pub fn iter(&self) -> HeaderIter {
+    pub fn iter(&self) -> HeaderIter<'_> {
         HeaderIter {
-            iter: self.map.iter()
+            iter: self.entries.iter()
+        }
+    }
+}
+
+impl Default for HeaderMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for HeaderMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for HeaderMap
+where K: Into<Cow<'static, str>>,
+      V: Into<Cow<'static, str>> {
+    /// Inserts all the headers, see [HeaderMap::insert]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
         }
     }
 }
 
 impl From<Vec<(String, String)>> for HeaderMap {
     ///Converts a `Vec<(String, String)>` to a `HeaderMap`. It takes ownership of contained `String` values.
+    /// Headers keep the vector order, the last value of a repeated header wins.
     fn from(value: Vec<(String, String)>) -> Self { 
-        let mut owned = value;
         let mut result = HeaderMap::new();
-        loop {
-            if let Some((k,v)) = owned.pop() {
-                result.insert(k,v);
-            } else {
-                break;
-            }
+        for (k,v) in value {
+            result.insert(k,v);
         }
 
         result
@@ -209,7 +562,7 @@ impl From<&Vec<(&str, &str)>> for HeaderMap {
     fn from(value: &Vec<(&str, &str)>) -> Self { 
         let mut result = HeaderMap::new();
         for (k, v) in value.iter() {
-            result.insert(*k,*v);
+            result.insert(k.to_string(), v.to_string());
         }
         result
     }
@@ -220,74 +573,246 @@ impl From<&Vec<(&str, &str)>> for HeaderMap {
 /// 
 /// Many thanks to [Returning Rust Iterators](https://depth-first.com/articles/2020/06/22/returning-rust-iterators/)
 pub struct HeaderIter<'a> {
-    iter: std::collections::hash_map::Iter<'a, UniCase<String>, String>
+    iter: std::slice::Iter<'a, (HeaderName, Cow<'static, str>)>
 }
 
 impl<'a> Iterator for HeaderIter<'a> {
     type Item = (&'a str, &'a str);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|(key, value)| (key.as_str(), value.as_str()) )
+        self.iter.next().map(|(key, value)| (key.as_str(), value.as_ref()) )
     }
 }
 
 
-/// Base struct for Request params and cookies. Keys are case-sensitive.
+/// Base struct for Request params and cookies. Keys are case-sensitive and iteration follows the insertion order.
+///
+/// ```
+/// use wrequest::KeyValueMap;
+///
+/// let mut params: KeyValueMap = [("page", "2"), ("utm_source", "mail"), ("sort", "name")].into_iter().collect();
+/// params.remove("utm_source");
+///
+/// let keys: Vec<&str> = params.iter().map(|(key, _)| key).collect();
+/// assert_eq!(keys, vec!["page", "sort"]);
+/// ```
+#[derive(Clone)]
 pub struct KeyValueMap {
-    map : HashMap<String, String>
+    /// Entries in insertion order
+    entries: Vec<(Cow<'static, str>, Cow<'static, str>)>
 }
 
 impl KeyValueMap {
     /// Constructor
     pub fn new() -> KeyValueMap {
         KeyValueMap {
-            map: HashMap::new()
+            entries: Vec::new()
+        }
+    }
+
+    /// Constructor with room for `capacity` entries without reallocating
+    pub fn with_capacity(capacity: usize) -> KeyValueMap {
+        KeyValueMap {
+            entries: Vec::with_capacity(capacity)
         }
     }
-    /// Insert a `key`/`value`
+
+    /// Reserves room for at least `additional` more entries
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if there are no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert a `key`/`value`. Returns `true` if there was a previous entry with the same `key`, whose value
+    /// is replaced keeping its position.
     pub fn insert<K,V>(&mut self, key: K, value: V) -> bool
-    where K: Into<String>,
-          V: Into<String> {
-        self.map.insert(key.into(),value.into()).is_some()
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        let key = key.into();
+        match self.position(&key) {
+            Some(i) => {
+                self.entries[i].1 = value.into();
+                true
+            },
+            None => {
+                self.entries.push((key, value.into()));
+                false
+            }
+        }
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| k == key)
     }
 
     /// Gets the `value` assotiated to a `key`, if any.
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.map.get(key).map(|s| s.as_str())
+        self.position(key).map(|i| self.entries[i].1.as_ref())
+    }
+
+    /// Gets the `value` assotiated to a `key`, or `default` if there is none
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Parses the `value` assotiated to a `key`, if any
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::get("https://service.com/users");
+    /// request.insert_param("page", "2")
+    ///        .insert_param("size", "big");
+    ///
+    /// let params = request.params();
+    /// assert_eq!(params.get_parsed::<u32>("page").transpose().unwrap(), Some(2));
+    /// assert!(params.get_parsed::<u32>("size").unwrap().is_err());
+    /// assert_eq!(params.get_parsed_or("limit", 20u32).unwrap(), 20);
+    /// ```
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.get(key).map(str::parse)
+    }
+
+    /// Parses the `value` assotiated to a `key`, or returns `default` if there is none
+    pub fn get_parsed_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, T::Err> {
+        self.get_parsed(key).unwrap_or(Ok(default))
     }
 
     /// Checks the map contains a value with `key`
     pub fn contains_key(&self, key: &str) -> bool {
-        self.map.contains_key(key)
+        self.position(key).is_some()
+    }
+
+    /// Removes an entry, returning its value if any
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.position(key).map(|i| self.entries.remove(i).1.into_owned())
+    }
+
+    /// Keeps only the entries for which `f` returns `true`
+    pub fn retain<F>(&mut self, mut f: F)
+    where F: FnMut(&str, &str) -> bool {
+        self.entries.retain(|(key, value)| f(key, value));
+    }
+
+    /// Removes all the entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
     }
 
     /// Generates an interator to `(key, value)`
-    pub fn iter(&self) -> KeyValueIter {
+    pub fn iter(&self) -> KeyValueIter<'_> {
         KeyValueIter {
-            iter: self.map.iter()
+            iter: self.entries.iter()
+        }
+    }
+}
+
+impl Default for KeyValueMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for KeyValueMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Extend<(K, V)> for KeyValueMap
+where K: Into<Cow<'static, str>>,
+      V: Into<Cow<'static, str>> {
+    /// Inserts all the entries, see [KeyValueMap::insert]
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for KeyValueMap
+where K: Into<Cow<'static, str>>,
+      V: Into<Cow<'static, str>> {
+    /// Collects the entries in order, the last value of a repeated key wins
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut result = KeyValueMap::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<'a> IntoIterator for &'a KeyValueMap {
+    type Item = (&'a str, &'a str);
+    type IntoIter = KeyValueIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for KeyValueMap {
+    type Item = (String, String);
+    type IntoIter = KeyValueIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        KeyValueIntoIter {
+            iter: self.entries.into_iter()
         }
     }
 }
 
 /// Iterator Over key/value parameters or cookies
 pub struct KeyValueIter<'a> {
-    iter: std::collections::hash_map::Iter<'a, String, String>
+    iter: std::slice::Iter<'a, (Cow<'static, str>, Cow<'static, str>)>
 }
 
 impl<'a> Iterator for KeyValueIter<'a> {
     type Item = (&'a str, &'a str);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|(key, value)| (key.as_str(), value.as_str()) )
+        self.iter.next().map(|(key, value)| (key.as_ref(), value.as_ref()) )
+    }
+}
+
+/// Owning iterator over key/value parameters or cookies
+pub struct KeyValueIntoIter {
+    iter: std::vec::IntoIter<(Cow<'static, str>, Cow<'static, str>)>
+}
+
+impl Iterator for KeyValueIntoIter {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(key, value)| (key.into_owned(), value.into_owned()))
     }
 }
 
 /// Base message struct for Request and Response
+#[derive(Clone)]
 pub struct HttpMessage {
     /// Request headers
     headers: HeaderMap,
     /// Request body (not implemented multi-part yet)
-    body: MessageBody
+    body: MessageBody,
+    /// Typed metadata, not sent
+    extensions: Extensions,
+    /// Parsed JSON body, reset when the body changes
+    json_cache: OnceLock<JsonValue>,
+    /// Maximum body size, the crate default if not set
+    max_body_size: Option<usize>,
+    /// Protocol version
+    version: HttpVersion
 }
 
 impl HttpMessage {
@@ -295,18 +820,41 @@ impl HttpMessage {
     pub fn new() -> HttpMessage {
         HttpMessage {
             headers : HeaderMap::new(),
-            body: MessageBody::None
+            body: MessageBody::None,
+            extensions: Extensions::new(),
+            json_cache: OnceLock::new(),
+            max_body_size: None,
+            version: HttpVersion::default()
         }
     }
 
+    /// Constructor with the `headers`, see [HeaderMap::insert]
+    pub fn with_headers<I, K, V>(headers: I) -> HttpMessage
+    where I: IntoIterator<Item = (K, V)>,
+          K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        let mut result = HttpMessage::new();
+        result.headers.extend(headers);
+        result
+    }
+
     /// Inserts a header with `key` and `value`
     pub fn insert_header<K,V>(&mut self, key: K, value: V) -> &mut Self
-    where K: Into<String>,
-          V: Into<String> {
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
         self.headers.insert(key, value);
         self
     } 
     
+    /// Inserts a header with `key` and `value`, or returns an error if they are invalid.
+    /// See [HeaderMap::try_insert].
+    pub fn try_insert_header<K,V>(&mut self, key: K, value: V) -> Result<&mut Self, Error>
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>> {
+        self.headers.try_insert(key, value)?;
+        Ok(self)
+    }
+
     /// Gets the headers map
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
@@ -317,6 +865,27 @@ impl HttpMessage {
         &mut self.headers
     }
 
+    /// Gets the typed metadata attached to the message
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Gets a mutable reference to the typed metadata attached to the message
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Gets the protocol version, HTTP/1.1 unless it was set or the message was received with another one
+    pub fn version(&self) -> HttpVersion {
+        self.version
+    }
+
+    /// Sets the protocol version
+    pub fn set_version(&mut self, version: HttpVersion) -> &mut Self {
+        self.version = version;
+        self
+    }
+
     /// Checks if the request has a single body
     pub fn has_single_body(&self) -> bool {
         self.body.is_single()
@@ -327,48 +896,251 @@ impl HttpMessage {
         self.body.is_multipart()
     }
     
-    /// Sets a single body
-    pub fn set_body(&mut self, data: Vec<u8>) -> &mut Self {
-        self.body = MessageBody::Single(data);
+    /// Sets the maximum body size of the message, in bytes, overriding the crate default
+    /// (see [set_default_max_body_size])
+    pub fn set_max_body_size(&mut self, bytes: usize) -> &mut Self {
+        self.max_body_size = Some(bytes);
         self
     }
 
-    /// Gets body data if any, returns `None` if there is no single body
-    pub fn body (&self) -> Option<&Vec<u8>> {
-        if let MessageBody::Single(ref body) = self.body {
-            Some(body)
-        } else {
-            None
+    /// Gets the maximum body size of the message, in bytes
+    pub fn max_body_size(&self) -> usize {
+        self.max_body_size.unwrap_or_else(default_max_body_size)
+    }
+
+    /// Checks a body of `size` bytes does not exceed [HttpMessage::max_body_size]
+    fn check_body_size(&self, size: usize) -> Result<(), Error> {
+        let limit = self.max_body_size();
+        if size > limit {
+            return Err(BodyTooLarge::error(limit, Some(size)));
+        }
+        Ok(())
+    }
+
+    /// Sets a single body. Returns a [BodyTooLarge] error if the body exceeds [HttpMessage::max_body_size],
+    /// keeping the current body.
+    pub fn set_body(&mut self, data: Vec<u8>) -> Result<&mut Self, Error> {
+        self.check_body_size(data.len())?;
+        self.body = MessageBody::Single(data);
+        self.json_cache.take();
+        Ok(self)
+    }
+
+    /// Sets a single body shared with other messages, its data is not copied. Returns a [BodyTooLarge]
+    /// error if the body exceeds [HttpMessage::max_body_size], keeping the current body.
+    pub fn set_shared_body(&mut self, data: Arc<[u8]>) -> Result<&mut Self, Error> {
+        self.check_body_size(data.len())?;
+        self.body = MessageBody::Shared(data);
+        self.json_cache.take();
+        Ok(self)
+    }
+
+    /// Appends data to the single body, or sets it if there is none. Returns a [BodyTooLarge] error if the
+    /// body would exceed [HttpMessage::max_body_size], keeping the current body.
+    pub(crate) fn append_body(&mut self, data: Vec<u8>) -> Result<&mut Self, Error> {
+        let current = self.body().map_or(0, <[u8]>::len);
+        self.check_body_size(current.saturating_add(data.len()))?;
+        match self.body_mut() {
+            Some(body) => {
+                body.extend_from_slice(&data);
+                Ok(self)
+            },
+            None => self.set_body(data)
+        }
+    }
+
+    /// Gets the single body for appending data, copying a shared one
+    fn body_mut(&mut self) -> Option<&mut Vec<u8>> {
+        self.json_cache.take();
+        self.body.as_vec_mut()
+    }
+
+    /// Moves a single body to shared storage, so cloning the message does not copy it, and returns it.
+    /// Returns `None` if there is no single body.
+    pub fn share_body(&mut self) -> Option<Arc<[u8]>> {
+        if let MessageBody::Single(ref mut data) = self.body {
+            self.body = MessageBody::Shared(Arc::from(std::mem::take(data)));
         }
+        match self.body {
+            MessageBody::Shared(ref data) => Some(data.clone()),
+            _ => None
+        }
+    }
+
+    /// Gets body data if any, returns `None` if there is no single body
+    pub fn body (&self) -> Option<&[u8]> {
+        self.body.as_slice()
     }
 
-     /// Sets a json object as request body. The `data` object is marshaled into a buffer using UTF8 coding.
-     /// Returns `true` if request body is overriden
-     pub fn set_json(&mut self, data: &JsonValue) -> &mut Self {
-        let pretty = data.pretty(4);
+    /// Sets a json object as request body. The `data` object is marshaled in compact form, without
+    /// whitespace, into a buffer using UTF8 coding. Fails as [HttpMessage::set_body] does.
+    ///
+    /// ```
+    /// use json::object;
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::post("https://service.com/users");
+    /// request.set_json(&object! { name: "John", age: 30 }).unwrap();
+    /// assert_eq!(request.body().unwrap(), b"{\"name\":\"John\",\"age\":30}");
+    ///
+    /// request.set_json_pretty(&object! { name: "John" }, 2).unwrap();
+    /// assert_eq!(request.body().unwrap(), b"{\n  \"name\": \"John\"\n}");
+    /// ```
+    pub fn set_json(&mut self, data: &JsonValue) -> Result<&mut Self, Error> {
+        self.set_body(data.dump().into_bytes())?;
         self.headers.insert(CONTENT_TYPE, APPLICATION_JSON);
-        self.set_body(pretty.into_bytes())
+        Ok(self)
     }
 
-    /// Checks if the Response has body and tries to parse as a `json::JsonValue'
+    /// Sets a json object as body like [HttpMessage::set_json], but pretty-printed with `spaces` of
+    /// indentation, for example for debugging
+    pub fn set_json_pretty(&mut self, data: &JsonValue, spaces: u16) -> Result<&mut Self, Error> {
+        self.set_body(data.pretty(spaces).into_bytes())?;
+        self.headers.insert(CONTENT_TYPE, APPLICATION_JSON);
+        Ok(self)
+    }
+
+    /// Checks if the Response has body and tries to parse as a `json::JsonValue'. Byte order marks are
+    /// skipped and bodies in other encodings than UTF-8 are decoded first, see [HttpMessage::text].
+    ///
+    /// With the `simd` feature the body is parsed with simd-json.
     pub fn json(&self) -> Result<JsonValue, Error> {
         if ! self.body.is_single() {
             return Err(Error::new(ErrorKind::InvalidData, "Empty body"));
         }
 
-        let str_body = from_utf8(self.body().unwrap());
+        parse_json(&self.utf8_body()?)
+    }
 
-        if str_body.is_err() {
-            return Err(Error::new(ErrorKind::InvalidData, str_body.err().unwrap()));
+    /// Parses the body as JSON like [HttpMessage::json], but only once: the parsed value is kept until the
+    /// body changes. Parse errors are not kept.
+    pub fn json_cached(&self) -> Result<&JsonValue, Error> {
+        if let Some(value) = self.json_cache.get() {
+            return Ok(value);
         }
+        let value = self.json()?;
+        Ok(self.json_cache.get_or_init(|| value))
+    }
 
-        let result = json::parse(str_body.unwrap());
+    /// Sets an `application/x-www-form-urlencoded` body with the `params`, as HTML forms are posted.
+    /// Fails as [HttpMessage::set_body] does.
+    pub fn set_form(&mut self, params: &KeyValueMap) -> Result<&mut Self, Error> {
+        let pairs: Vec<String> = params.iter()
+            .map(|(key, value)| format!("{}={}", uri::form_encode(key), uri::form_encode(value)))
+            .collect();
+        self.set_body(pairs.join("&").into_bytes())?;
+        self.headers.insert(CONTENT_TYPE, APPLICATION_FORM_URLENCODED);
+        Ok(self)
+    }
 
-        return if result.is_ok() {
-            Ok(result.unwrap())
-        } else {
-            Err(Error::new(ErrorKind::InvalidData, result.err().unwrap()))
+    /// Decodes an `application/x-www-form-urlencoded` body, with `+` as space. Returns an empty map if there is
+    /// no body and an `ErrorKind::InvalidData` error if the `Content-Type` is another one or the body is not
+    /// UTF-8. If a key is repeated, its last value is kept.
+    ///
+    /// ```
+    /// use wrequest::{Request, APPLICATION_FORM_URLENCODED, CONTENT_TYPE};
+    ///
+    /// let mut request = Request::post("https://service.com/login");
+    /// request.insert_header(CONTENT_TYPE, APPLICATION_FORM_URLENCODED)
+    ///        .set_body(b"user=john&note=two+words%21".to_vec()).unwrap();
+    ///
+    /// let form = request.form_params().unwrap();
+    /// assert_eq!(form.get("user"), Some("john"));
+    /// assert_eq!(form.get("note"), Some("two words!"));
+    /// ```
+    pub fn form_params(&self) -> Result<KeyValueMap, Error> {
+        let content_type = self.headers.get(CONTENT_TYPE).unwrap_or("");
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        if !mime.eq_ignore_ascii_case(APPLICATION_FORM_URLENCODED) {
+            return Err(Error::new(ErrorKind::InvalidData, format!("Content-Type {:?} is not a form", content_type)));
+        }
+        let body = from_utf8(self.body().unwrap_or(&[])).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut result = KeyValueMap::new();
+        for pair in body.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            result.insert(uri::form_decode(key), uri::form_decode(value));
+        }
+        Ok(result)
+    }
+
+    /// Renders up to `max_bytes` of the body for logging: as text if it is UTF-8 without control characters
+    /// but whitespace, as hexadecimal bytes otherwise. If the body is longer, a note with the shown and total
+    /// sizes is appended. Returns an empty string if there is no single body.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::post("https://service.com/upload");
+    /// request.set_body(b"hello world".to_vec()).unwrap();
+    /// assert_eq!(request.body_preview(5), "hello\n... (5 of 11 bytes)");
+    ///
+    /// request.set_body(vec![0x00, 0xff, 0x10]).unwrap();
+    /// assert_eq!(request.body_preview(1024), "00 ff 10");
+    /// ```
+    pub fn body_preview(&self, max_bytes: usize) -> String {
+        self.body().map(|body| preview(body, max_bytes)).unwrap_or_default()
+    }
+
+    /// Appends a JSON value as a new line of a newline-delimited JSON body (`application/x-ndjson`).
+    /// The value is encoded in a single line using UTF8 coding. Returns a [BodyTooLarge] error, keeping
+    /// the current body, if the body would exceed the maximum body size.
+    pub fn append_ndjson(&mut self, data: &JsonValue) -> Result<&mut Self, Error> {
+        let mut line = data.dump().into_bytes();
+        line.push(b'\n');
+        self.append_body(line)?;
+        self.headers.insert(CONTENT_TYPE, APPLICATION_NDJSON);
+        Ok(self)
+    }
+
+    /// Gets an iterator that parses each non-blank line of the body as a `json::JsonValue`.
+    /// The iterator is empty if there is no single body.
+    pub fn ndjson(&self) -> NdJsonIter<'_> {
+        NdJsonIter {
+            remaining: self.body().unwrap_or(&[])
+        }
+    }
+}
+
+impl Default for HttpMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parses a JSON document encoded in UTF-8
+#[cfg(not(feature = "simd"))]
+fn parse_json(data: &[u8]) -> Result<JsonValue, Error> {
+    let str_body = from_utf8(data).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    json::parse(str_body).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Parses a JSON document encoded in UTF-8 with simd-json
+#[cfg(feature = "simd")]
+fn parse_json(data: &[u8]) -> Result<JsonValue, Error> {
+    simd::parse(data)
+}
+
+/// Iterator over the values of a newline-delimited JSON body
+pub struct NdJsonIter<'a> {
+    remaining: &'a [u8]
+}
+
+impl<'a> Iterator for NdJsonIter<'a> {
+    type Item = Result<JsonValue, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.remaining.is_empty() {
+            let (line, rest) = match self.remaining.iter().position(|b| *b == b'\n') {
+                Some(pos) => (&self.remaining[..pos], &self.remaining[pos + 1..]),
+                None => (self.remaining, &self.remaining[self.remaining.len()..])
+            };
+            self.remaining = rest;
+            let line = line.trim_ascii();
+            if !line.is_empty() {
+                return Some(parse_json(line));
+            }
         }
+        None
     }
 }
 
@@ -387,6 +1159,7 @@ impl HttpMessage {
 /// 
 /// For more information see [HTTP Request](https://developer.mozilla.org/en-US/docs/Web/HTTP/Messages#http_requests).
 ///
+#[derive(Clone)]
 pub struct Request {
     /// Base message
     base: HttpMessage,
@@ -409,7 +1182,7 @@ impl Request {
         Request {
             base: HttpMessage::new(),
             method,
-            url: url.into(),
+            url: idna::ascii_url(url.into()),
             cookies: KeyValueMap::new(),
             params: KeyValueMap::new()
         }
@@ -485,8 +1258,8 @@ impl Request {
 
     /// Insert a request param with `key` and `value`. Param keys are case-sensitive.
     pub fn insert_param<K, V>(&mut self, key: K, value: V) -> &mut Self 
-    where K: Into<String>,
-          V: Into<String>
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>>
     {
         self.params.insert(key, value);
         self
@@ -504,8 +1277,8 @@ impl Request {
 
     /// Insert a cookie param with `key` and `value`. Param keys are case-sensitive.
     pub fn insert_cookie<K, V>(&mut self, key: K, value: V) -> &mut Self 
-    where K: Into<String>,
-          V: Into<String>
+    where K: Into<Cow<'static, str>>,
+          V: Into<Cow<'static, str>>
     {
         self.cookies.insert(key, value);
         self
@@ -516,15 +1289,100 @@ impl Request {
         &self.cookies
     }
 
-    /// Gets a mutable params map reference
+    /// Gets a mutable cookie map reference
     pub fn cookies_mut(&mut self) -> &mut KeyValueMap {
-        &mut self.params
+        &mut self.cookies
     }
 
-    /// Gets the target URL
+    /// Gets the target URL. Unicode hosts are converted to punycode when the request is created,
+    /// see [Request::unicode_url] for the display form.
     pub fn url(&self) -> &str {
         self.url.as_str()
-    }  
+    }
+
+    /// Gets the `Cookie` header value that carries the request cookies, if any
+    pub fn cookie_header(&self) -> Option<String> {
+        let mut cookies: Vec<(&str, &str)> = self.cookies.iter().collect();
+        if cookies.is_empty() {
+            return None;
+        }
+        cookies.sort();
+        let pairs: Vec<String> = cookies.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        Some(pairs.join("; "))
+    }
+
+    /// Checks the request has an `Expect: 100-continue` header, so its body should only be sent once the
+    /// server answers with a `100 Continue` response (see [wire::write_request_with_continue])
+    pub fn expects_continue(&self) -> bool {
+        self.headers().get(EXPECT).is_some_and(|value| value.trim().eq_ignore_ascii_case(EXPECT_CONTINUE))
+    }
+
+    /// Sets the `Expect: 100-continue` header, so the body is only sent once the server accepts the request
+    pub fn set_expect_continue(&mut self) -> &mut Self {
+        self.base.headers.insert(EXPECT, EXPECT_CONTINUE);
+        self
+    }
+
+    /// Checks if the request may be sent again after a failure: its method is idempotent or it carries
+    /// an `Idempotency-Key` header
+    pub fn is_retryable(&self) -> bool {
+        self.method.is_idempotent() || self.headers().contains_key(IDEMPOTENCY_KEY)
+    }
+
+    /// Gets the request ID carried in the `header_name` header (usually [X_REQUEST_ID]). If the request has no
+    /// ID, a random UUID is generated and inserted.
+    pub fn ensure_request_id(&mut self, header_name: &str) -> String {
+        if let Some(id) = self.headers().get(header_name) {
+            if !id.trim().is_empty() {
+                return id.to_string();
+            }
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        self.insert_header(header_name.to_string(), id.clone());
+        id
+    }
+
+    /// Normalizes the request so that logically equal requests compare equal, for example as cache keys:
+    /// the URL is normalized (RFC 3986) with lowercase scheme and host, no default port, no dot segments,
+    /// percent-encoding of unreserved characters decoded, other escapes in uppercase and sorted query params,
+    /// without fragment. Leading and trailing whitespace of the header values is removed.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    ///
+    /// let mut request = Request::get("HTTPS://Example.COM:443/a/./b/../c/%7euser?b=2&a=%2f1#top");
+    /// request.insert_header("Accept", " text/html ");
+    /// request.normalize();
+    ///
+    /// assert_eq!(request.url(), "https://example.com/a/c/~user?a=%2F1&b=2");
+    /// assert_eq!(request.headers().get("accept"), Some("text/html"));
+    /// ```
+    pub fn normalize(&mut self) -> &mut Self {
+        self.url = uri::normalize(&self.url);
+        self.base.headers.trim_values();
+        self
+    }
+
+    /// Gets the target URL including the request params, percent-encoded and sorted by key, as query string
+    pub fn full_url(&self) -> String {
+        let mut params: Vec<(&str, &str)> = self.params.iter().collect();
+        if params.is_empty() {
+            return self.url.clone();
+        }
+        params.sort();
+
+        let mut result = self.url.clone();
+        result.push(if self.url.contains('?') {'&'} else {'?'});
+        for (i, (key, value)) in params.iter().enumerate() {
+            if i > 0 {
+                result.push('&');
+            }
+            result.push_str(&uri::percent_encode(key));
+            result.push('=');
+            result.push_str(&uri::percent_encode(value));
+        }
+        result
+    }
 }
 
 impl Deref for Request {
@@ -541,8 +1399,35 @@ impl DerefMut for Request {
     }
 }
 
+impl fmt::Debug for Request {
+    /// Formats the method, URL, version, headers, params, cookies and body, without the extensions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("url", &self.url)
+            .field("version", &self.version())
+            .field("headers", &self.headers)
+            .field("params", &self.params)
+            .field("cookies", &self.cookies)
+            .field("body", &self.body())
+            .finish_non_exhaustive()
+    }
+}
+
 impl fmt::Display for Request {
+    /// Formats the method, URL and headers. The alternate form (`{:#}`) prints a wire-style dump with the
+    /// params in the URL, the cookies and a preview of the body.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            writeln!(f, "{} {} {}", self.method, self.full_url(), self.version())?;
+            for (key, value) in self.headers.iter() {
+                writeln!(f, "{}: {}", key, value)?;
+            }
+            if let Some(cookies) = self.cookie_header() {
+                writeln!(f, "Cookie: {}", cookies)?;
+            }
+            return write_body_preview(f, self);
+        }
         writeln!(f, "{} {}", self.method, &self.url)?;
         let headers = self.headers.iter();
         for (key,value) in headers {
@@ -552,6 +1437,44 @@ impl fmt::Display for Request {
     }
 }
 
+/// Maximum number of body bytes printed by the alternate `Display` form of messages
+const DISPLAY_BODY_PREVIEW: usize = 1024;
+
+/// Writes the empty line after the headers and a bounded preview of the body, if any
+fn write_body_preview(f: &mut fmt::Formatter<'_>, message: &HttpMessage) -> fmt::Result {
+    if let Some(body) = message.body() {
+        writeln!(f)?;
+        writeln!(f, "{}", preview(body, DISPLAY_BODY_PREVIEW))?;
+    }
+    Ok(())
+}
+
+/// Renders up to `max_bytes` of `data`: as text if it is UTF-8 without control characters but whitespace,
+/// as hexadecimal bytes otherwise. Truncated data is followed by a note with its total size.
+fn preview(data: &[u8], max_bytes: usize) -> String {
+    let shown = &data[..data.len().min(max_bytes)];
+    // A multi-byte character may be cut at the end
+    let text = match from_utf8(shown) {
+        Ok(text) => Some(text),
+        Err(e) if e.error_len().is_none() && shown.len() < data.len() => from_utf8(&shown[..e.valid_up_to()]).ok(),
+        Err(_) => None
+    };
+    let text = text.filter(|text| !text.chars().any(|c| c.is_control() && !matches!(c, '\t' | '\r' | '\n')));
+    let mut result = match text {
+        Some(text) => text.to_string(),
+        None => {
+            let lines: Vec<String> = shown.chunks(16)
+                .map(|line| line.iter().map(|b| format!("{:02x}", b)).collect::<Vec<String>>().join(" "))
+                .collect();
+            lines.join("\n")
+        }
+    };
+    if shown.len() < data.len() {
+        result.push_str(&format!("\n... ({} of {} bytes)", shown.len(), data.len()));
+    }
+    result
+}
+
 // HTTP Response status code
 pub type HttpStatusCode = u16;
 
@@ -559,6 +1482,8 @@ pub type HttpStatusCode = u16;
 pub const HTTP_100_CONTINUE: u16 = 100;
 /// HTTP 101 SWITCHING_PROTOCOLS status code
 pub const HTTP_101_SWITCHING_PROTOCOLS: u16 = 101;
+/// HTTP 103 EARLY HINTS status code
+pub const HTTP_103_EARLY_HINTS: u16 = 103;
 /// HTTP 200 OK status code
 pub const HTTP_200_OK: u16 = 200;
 /// HTTP 201 CREATED status code
@@ -571,6 +1496,8 @@ pub const HTTP_203_NON_AUTHORIZATIVE_INFORMATION: u16 = 203;
 pub const HTTP_204_NO_CONTENT: u16 = 204;
 /// HTTP 205 RESET CONTENT status code
 pub const HTTP_205_RESET_CONTENT: u16 = 205;
+/// HTTP 206 PARTIAL CONTENT status code
+pub const HTTP_206_PARTIAL_CONTENT: u16 = 206;
 /// HTTP 300 MULTIPLE CHOICES status code
 pub const HTTP_300_MULTIPLE_CHOICES: u16 = 300;
 /// HTTP 301 MOVED PERMANENTLY status code
@@ -603,12 +1530,16 @@ pub const HTTP_409_CONFLICT: u16 = 409;
 pub const HTTP_410_GONE: u16 = 410;
 /// HTTP 411 LENGTH REQUIRED status code
 pub const HTTP_411_LENGTH_REQURED: u16 = 411;
+/// HTTP 412 PRECONDITION FAILED status code
+pub const HTTP_412_PRECONDITION_FAILED: u16 = 412;
 /// HTTP 413 PAYLOAD TOO LARGE status code
 pub const HTTP_413_PAYLOAD_TOO_LARGE: u16 = 413;
 /// HTTP 414 URI TOO LARGE status code
 pub const HTTP_414_URI_TOO_LONG: u16 = 414;
 /// HTTP 415 UNSUPORTED MEDIA TYPE status code
 pub const HTTP_415_UNSUPORTED_MEDIA_TYPE: u16 = 415;
+/// HTTP 416 RANGE NOT SATISFIABLE status code
+pub const HTTP_416_RANGE_NOT_SATISFIABLE: u16 = 416;
 /// HTTP 417 EXPECTATION FAILED status code
 pub const HTTP_417_EXPECTATION_FAILED: u16 = 417;
 /// HTTP 426 UPGRADE REQUIRED status code
@@ -626,11 +1557,116 @@ pub const HTTP_504_GATEWAY_TIMEOUT: u16 = 504;
 /// HTTP 505 HTTP VERSION NOT SUPPORTED status code
 pub const HTTP_505_HTTP_VERSION_NOT_SUPPORTED: u16 = 505;
 
+/// Gets the standard reason phrase of a status code, or the registered one of a custom code
+/// (see [register_status_code]), if it is known
+pub fn reason_phrase(status: HttpStatusCode) -> Option<&'static str> {
+    Some(match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        103 => "Early Hints",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        203 => "Non-Authoritative Information",
+        204 => "No Content",
+        205 => "Reset Content",
+        206 => "Partial Content",
+        300 => "Multiple Choices",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        305 => "Use Proxy",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        402 => "Payment Required",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        407 => "Proxy Authentication Required",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        411 => "Length Required",
+        412 => "Precondition Failed",
+        413 => "Content Too Large",
+        414 => "URI Too Long",
+        415 => "Unsupported Media Type",
+        416 => "Range Not Satisfiable",
+        417 => "Expectation Failed",
+        421 => "Misdirected Request",
+        422 => "Unprocessable Content",
+        426 => "Upgrade Required",
+        428 => "Precondition Required",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        505 => "HTTP Version Not Supported",
+        _ => return status::custom_reason_phrase(status)
+    })
+}
+
 
 
 /// List of `Set-Cookie` headers in a HTTP Response
 type SetCookies = Vec<SetCookie>;
 
+/// Parses a `Cookie` header value into `(name, value)` pairs
+pub(crate) fn parse_cookie_header(value: &str) -> Vec<(String, String)> {
+    value.split(';')
+         .map(str::trim)
+         .filter(|pair| !pair.is_empty())
+         .map(|pair| match pair.find('=') {
+             Some(pos) => (pair[..pos].trim().to_string(), pair[pos + 1..].trim().to_string()),
+             None => (pair.to_string(), String::new())
+         })
+         .collect()
+}
+
+/// Formats a `Set-Cookie` header value
+pub(crate) fn set_cookie_header(cookie: &SetCookie) -> String {
+    let mut result = format!("{}={}", cookie.name, cookie.value);
+    if let Some(ref domain) = cookie.domain {
+        result.push_str(&format!("; Domain={}", domain));
+    }
+    if let Some(ref path) = cookie.path {
+        result.push_str(&format!("; Path={}", path));
+    }
+    if let Some(ref expires) = cookie.expires {
+        result.push_str(&format!("; Expires={}", expires.format("%a, %d %b %Y %H:%M:%S GMT")));
+    }
+    if let Some(max_age) = cookie.max_age {
+        result.push_str(&format!("; Max-Age={}", max_age.as_secs()));
+    }
+    match cookie.same_site {
+        wcookie::SameSiteValue::Strict => result.push_str("; SameSite=Strict"),
+        wcookie::SameSiteValue::None => result.push_str("; SameSite=None"),
+        wcookie::SameSiteValue::Lax => {}
+    }
+    if cookie.secure {
+        result.push_str("; Secure");
+    }
+    if cookie.http_only {
+        result.push_str("; HttpOnly");
+    }
+    let mut extensions: Vec<(&String, &Option<String>)> = cookie.extensions.iter().collect();
+    extensions.sort();
+    for (key, value) in extensions {
+        match value {
+            Some(value) => result.push_str(&format!("; {}={}", key, value)),
+            None => result.push_str(&format!("; {}", key))
+        }
+    }
+    result
+}
+
 /// HTTP Response
 /// 
 /// An HTTP Response is formed by:
@@ -640,6 +1676,7 @@ type SetCookies = Vec<SetCookie>;
 /// * (optional) Response body
 /// 
 /// For mor information see [HTTP Response](https://developer.mozilla.org/en-US/docs/Web/HTTP/Messages#http_responses)
+#[derive(Clone)]
 pub struct Response {
     /// Base Message
     base: HttpMessage,
@@ -650,22 +1687,136 @@ pub struct Response {
     /// Authorize headers in HTTP `401 Not Authorized` responses
     auth: Vec<String>,
     /// Proxy authorize headers in HTTP `401 Not Authorized` responses
-    proxy_auth: Vec<String>
+    proxy_auth: Vec<String>,
+    /// Redirect chain that led to the response
+    history: Vec<Exchange>
 }
 
 
 impl Response {
     /// Response default constructor, only sets the status code.
     /// After constructing the value, as struct members are public, they can be
-    /// accessed directly
+    /// accessed directly. The status code is not validated, see [Response::try_new]
     pub fn new(status: HttpStatusCode) -> Response {
         Response {
             base: HttpMessage::new(),
             cookies: SetCookies::new(),
             status_code: status,
             auth: Vec::new(),
-            proxy_auth: Vec::new()        
+            proxy_auth: Vec::new(),
+            history: Vec::new()
+        }
+    }
+
+    /// Creates a response with a JSON body, encoded as [HttpMessage::set_json] does, and its `Content-Type`
+    ///
+    /// ```
+    /// use json::object;
+    /// use wrequest::{Response, HTTP_201_CREATED};
+    ///
+    /// let response = Response::json(HTTP_201_CREATED, &object! { id: 42 }).unwrap();
+    ///
+    /// assert_eq!(response.headers().get("Content-Type"), Some("application/json"));
+    /// assert_eq!(response.json().unwrap()["id"], 42);
+    /// ```
+    pub fn json(status: HttpStatusCode, data: &JsonValue) -> Result<Response, Error> {
+        let mut response = Response::new(status);
+        response.set_json(data)?;
+        Ok(response)
+    }
+
+    /// Creates a `103 Early Hints` interim response with `Link` headers, for example
+    /// `</style.css>; rel=preload; as=style`, to be sent before the final response
+    /// (see [wire::ResponseSequence])
+    ///
+    /// ```
+    /// use wrequest::{Response, HTTP_103_EARLY_HINTS};
+    ///
+    /// let hints = Response::early_hints(&["</style.css>; rel=preload; as=style", "<https://cdn.com>; rel=preconnect"]);
+    ///
+    /// assert_eq!(hints.status_code(), HTTP_103_EARLY_HINTS);
+    /// assert_eq!(hints.headers().get("Link"),
+    ///            Some("</style.css>; rel=preload; as=style, <https://cdn.com>; rel=preconnect"));
+    /// ```
+    pub fn early_hints<S: AsRef<str>>(links: &[S]) -> Response {
+        let mut response = Response::new(HTTP_103_EARLY_HINTS);
+        let links: Vec<&str> = links.iter().map(|link| link.as_ref()).collect();
+        if !links.is_empty() {
+            response.insert_header(LINK, links.join(", "));
+        }
+        response
+    }
+
+    /// Creates a `201 Created` response with the `Location` header of the created resource
+    ///
+    /// ```
+    /// use wrequest::{Request, Response, HTTP_201_CREATED};
+    ///
+    /// let request = Request::post("https://service.com/api/users");
+    /// let response = Response::created_at("users/42");
+    ///
+    /// assert_eq!(response.status_code(), HTTP_201_CREATED);
+    /// assert_eq!(response.location(request.url()).as_deref(), Some("https://service.com/api/users/42"));
+    /// ```
+    pub fn created_at<S: Into<String>>(url: S) -> Response {
+        let mut response = Response::new(HTTP_201_CREATED);
+        response.insert_header(LOCATION, url.into());
+        response
+    }
+
+    /// Gets the `Location` header resolved against the `base` URL, usually the request one
+    pub fn location(&self, base: &str) -> Option<String> {
+        self.headers().get(LOCATION).map(|location| uri::resolve(base, location.trim()))
+    }
+
+    /// Gets the `Content-Location` header, the URL of the representation in the body, resolved against the
+    /// `base` URL, usually the request one
+    pub fn content_location(&self, base: &str) -> Option<String> {
+        self.headers().get(CONTENT_LOCATION).map(|location| uri::resolve(base, location.trim()))
+    }
+
+    /// Sets the `Content-Location` header
+    pub fn set_content_location<S: Into<String>>(&mut self, url: S) -> &mut Self {
+        self.insert_header(CONTENT_LOCATION, url.into());
+        self
+    }
+
+    /// Creates a `405 Method Not Allowed` response with the `Allow` header listing the `allowed` methods
+    ///
+    /// ```
+    /// use wrequest::{HttpMethod, Response, HTTP_405_METHOD_NOT_ALLOWED};
+    ///
+    /// let response = Response::method_not_allowed(&[HttpMethod::GET, HttpMethod::HEAD]);
+    ///
+    /// assert_eq!(response.status_code(), HTTP_405_METHOD_NOT_ALLOWED);
+    /// assert_eq!(response.headers().get("Allow"), Some("GET, HEAD"));
+    /// assert_eq!(response.allowed_methods(), vec![HttpMethod::GET, HttpMethod::HEAD]);
+    /// ```
+    pub fn method_not_allowed(allowed: &[HttpMethod]) -> Response {
+        let mut response = Response::new(HTTP_405_METHOD_NOT_ALLOWED);
+        response.set_allowed_methods(allowed);
+        response
+    }
+
+    /// Sets the `Allow` header, for example in responses to `OPTIONS` requests. An empty list means the
+    /// resource allows no methods.
+    pub fn set_allowed_methods(&mut self, allowed: &[HttpMethod]) -> &mut Self {
+        let methods: Vec<String> = allowed.iter().map(|method| method.to_string()).collect();
+        self.insert_header(ALLOW, methods.join(", "));
+        self
+    }
+
+    /// Gets the methods of the `Allow` header, without the unknown ones nor duplicates
+    pub fn allowed_methods(&self) -> Vec<HttpMethod> {
+        let mut result = Vec::new();
+        for method in self.headers().get_list(ALLOW) {
+            if let Ok(method) = HttpMethod::from_str(method) {
+                if !result.contains(&method) {
+                    result.push(method);
+                }
+            }
         }
+        result
     }
 
     /// Get the Response status code
@@ -714,6 +1865,21 @@ impl Response {
     pub fn proxy_auth_headers_mut(&mut self) -> &mut Vec<String> {
         &mut self.proxy_auth
     }
+
+    /// Clones the response sharing its body: a single body is first moved to shared storage (see
+    /// [HttpMessage::share_body]), so neither this clone nor the following ones copy it.
+    pub fn clone_shallow(&mut self) -> Response {
+        self.share_body();
+        self.clone()
+    }
+
+    /// Copies the request ID of `request`, carried in the `header_name` header, to the response
+    pub fn mirror_request_id(&mut self, request: &Request, header_name: &str) -> &mut Self {
+        if let Some(id) = request.headers().get(header_name) {
+            self.insert_header(header_name.to_string(), id.to_string());
+        }
+        self
+    }
 }
 
 impl Deref for Response {
@@ -730,8 +1896,143 @@ impl DerefMut for Response {
     }
 }
 
+impl fmt::Debug for Response {
+    /// Formats the status code, version, headers, cookies and body, without the authorization guides,
+    /// the redirect history and the extensions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cookies: Vec<String> = self.cookies.iter().map(set_cookie_header).collect();
+        f.debug_struct("Response")
+            .field("status_code", &self.status_code)
+            .field("version", &self.version())
+            .field("headers", &self.headers)
+            .field("cookies", &cookies)
+            .field("body", &self.body())
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Response {
+    /// Formats the status line and headers. The alternate form (`{:#}`) prints a wire-style dump with the
+    /// cookies, the authorization guides and a preview of the body.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = reason_phrase(self.status_code).unwrap_or("");
+        if f.alternate() {
+            writeln!(f, "{} {} {}", self.version(), self.status_code, reason)?;
+            for (key, value) in self.headers.iter() {
+                writeln!(f, "{}: {}", key, value)?;
+            }
+            for cookie in self.cookies.iter() {
+                writeln!(f, "Set-Cookie: {}", set_cookie_header(cookie))?;
+            }
+            for auth in self.auth.iter() {
+                writeln!(f, "WWW-Authenticate: {}", auth)?;
+            }
+            for auth in self.proxy_auth.iter() {
+                writeln!(f, "Proxy-Authenticate: {}", auth)?;
+            }
+            return write_body_preview(f, self);
+        }
+        writeln!(f, "{} {}", self.status_code, reason)?;
+        for (key, value) in self.headers.iter() {
+            writeln!(f, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test_request;
 
 #[cfg(test)]
-mod test_response;
\ No newline at end of file
+mod test_response;
+#[cfg(test)]
+mod test_headers;
+#[cfg(test)]
+mod test_extensions;
+
+#[cfg(test)]
+mod test_sse;
+
+#[cfg(test)]
+mod test_har;
+
+#[cfg(test)]
+mod test_curl;
+
+#[cfg(all(test, feature = "openapi"))]
+mod test_openapi;
+
+#[cfg(test)]
+mod test_matchers;
+
+#[cfg(test)]
+mod test_mock;
+
+#[cfg(test)]
+mod test_cassette;
+#[cfg(test)]
+mod test_middleware;
+#[cfg(test)]
+mod test_transport;
+#[cfg(test)]
+mod test_ratelimit;
+#[cfg(test)]
+mod test_prefer;
+#[cfg(test)]
+mod test_via;
+#[cfg(test)]
+mod test_security;
+#[cfg(test)]
+mod test_csp;
+#[cfg(test)]
+mod test_multipart;
+#[cfg(test)]
+mod test_wire;
+#[cfg(test)]
+mod test_upgrade;
+#[cfg(test)]
+mod test_alt_svc;
+#[cfg(test)]
+mod test_connection;
+#[cfg(test)]
+mod test_transfer;
+#[cfg(test)]
+mod test_h2;
+#[cfg(test)]
+mod test_batch;
+#[cfg(test)]
+mod test_conditional;
+#[cfg(test)]
+mod test_language;
+#[cfg(test)]
+mod test_policy;
+#[cfg(test)]
+mod test_idna;
+#[cfg(all(test, feature = "signed-url"))]
+mod test_signed_url;
+#[cfg(test)]
+mod test_range;
+#[cfg(test)]
+mod test_freshness;
+#[cfg(test)]
+mod test_view;
+
+#[cfg(all(test, feature = "hyper"))]
+mod test_hyper;
+
+#[cfg(all(test, feature = "reqwest"))]
+mod test_reqwest;
+#[cfg(all(test, feature = "ureq"))]
+mod test_ureq;
+#[cfg(all(test, feature = "simd"))]
+mod test_simd;
+#[cfg(all(test, feature = "serde"))]
+mod test_serde;
+#[cfg(all(test, feature = "serde"))]
+mod test_fixtures;
+#[cfg(all(test, feature = "jwt"))]
+mod test_jwt;
+#[cfg(all(test, feature = "tower"))]
+mod test_tower;
+#[cfg(all(test, feature = "fuzzing"))]
+mod test_fuzzing;
@@ -0,0 +1,76 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Maximum body size
+
+use std::error;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Initial crate-level maximum body size, 64 MiB
+pub const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+static MAX_BODY_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_BODY_SIZE);
+
+/// Gets the crate-level maximum body size, used by the messages without their own limit
+pub fn default_max_body_size() -> usize {
+    MAX_BODY_SIZE.load(Ordering::Relaxed)
+}
+
+/// Sets the crate-level maximum body size, used by the messages without their own limit
+pub fn set_default_max_body_size(bytes: usize) {
+    MAX_BODY_SIZE.store(bytes, Ordering::Relaxed);
+}
+
+/// Error of a body larger than the allowed maximum. It is returned as the inner error of an
+/// `ErrorKind::InvalidData` I/O error:
+///
+/// ```
+/// use wrequest::{BodyTooLarge, Request};
+///
+/// let mut request = Request::post("https://service.com/files");
+/// request.set_max_body_size(4);
+///
+/// let error = request.set_body(b"12345".to_vec()).err().unwrap();
+/// let too_large = error.get_ref().and_then(|e| e.downcast_ref::<BodyTooLarge>()).unwrap();
+/// assert_eq!(too_large.limit, 4);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BodyTooLarge {
+    /// Maximum body size, in bytes
+    pub limit: usize,
+    /// Body size, in bytes, if known. Bodies read from a stream are dropped once they exceed the limit,
+    /// so their whole size is not known.
+    pub size: Option<usize>
+}
+
+impl BodyTooLarge {
+    /// Creates the I/O error that wraps a `BodyTooLarge` error
+    pub(crate) fn error(limit: usize, size: Option<usize>) -> Error {
+        Error::new(ErrorKind::InvalidData, BodyTooLarge { limit, size })
+    }
+}
+
+impl fmt::Display for BodyTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.size {
+            Some(size) => write!(f, "Body of {} bytes exceeds the maximum of {} bytes", size, self.limit),
+            None => write!(f, "Body exceeds the maximum of {} bytes", self.limit)
+        }
+    }
+}
+
+impl error::Error for BodyTooLarge {}
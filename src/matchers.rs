@@ -0,0 +1,407 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Composable request matchers for tests and mocks
+//!
+//! ```
+//! use wrequest::Request;
+//! use wrequest::HttpMethod::POST;
+//! use wrequest::matchers::*;
+//! use json::object;
+//!
+//! let mut request = Request::post("https://service.com/users");
+//! request.set_json(&object! { name: "John" }).unwrap();
+//!
+//! let matcher = method(POST)
+//!     .and(path("/users"))
+//!     .and(header("content-type", contains("json")))
+//!     .and(json_body(|j| j["name"] == "John"));
+//!
+//! assert!(matcher.matches(&request));
+//!
+//! let mismatch = method(POST).and(header("Accept", "application/json")).check(&request).unwrap_err();
+//! assert_eq!(mismatch.to_string(), "header Accept equal to \"application/json\": header is missing");
+//! ```
+
+use std::fmt;
+use json::JsonValue;
+use crate::{HttpMethod, Request, uri};
+
+/// Reasons why a request does not match
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Mismatch {
+    /// One reason per failed matcher
+    pub reasons: Vec<String>
+}
+
+impl Mismatch {
+    fn new<S: Into<String>>(reason: S) -> Mismatch {
+        Mismatch {
+            reasons: vec![reason.into()]
+        }
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.reasons.join("\n"))
+    }
+}
+
+/// Request predicate that explains why it fails
+pub trait Matcher {
+    /// Checks a request, returning the reasons of the mismatch if any
+    fn check(&self, request: &Request) -> Result<(), Mismatch>;
+
+    /// Describes what the matcher expects
+    fn describe(&self) -> String;
+
+    /// Checks a request matches
+    fn matches(&self, request: &Request) -> bool {
+        self.check(request).is_ok()
+    }
+
+    /// Panics with a readable report if the request does not match
+    fn assert(&self, request: &Request) {
+        if let Err(mismatch) = self.check(request) {
+            panic!("Request {} {} does not match:\n{}", request.method(), request.url(), mismatch);
+        }
+    }
+
+    /// Matches if both matchers match
+    fn and<M: Matcher>(self, other: M) -> And<Self, M>
+    where Self: Sized {
+        And(self, other)
+    }
+
+    /// Matches if any of the matchers matches
+    fn or<M: Matcher>(self, other: M) -> Or<Self, M>
+    where Self: Sized {
+        Or(self, other)
+    }
+}
+
+impl<M: Matcher + ?Sized> Matcher for Box<M> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        (**self).check(request)
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+/// String predicate used by header, param, cookie and body matchers
+pub trait ValueMatcher {
+    /// Checks the value matches
+    fn matches_value(&self, value: &str) -> bool;
+
+    /// Describes what the matcher expects
+    fn describe(&self) -> String;
+}
+
+impl ValueMatcher for &str {
+    fn matches_value(&self, value: &str) -> bool {
+        *self == value
+    }
+
+    fn describe(&self) -> String {
+        format!("equal to {:?}", self)
+    }
+}
+
+impl ValueMatcher for String {
+    fn matches_value(&self, value: &str) -> bool {
+        self == value
+    }
+
+    fn describe(&self) -> String {
+        format!("equal to {:?}", self)
+    }
+}
+
+/// Value matcher built with a predicate and a description
+pub struct ValuePredicate<F> {
+    predicate: F,
+    description: String
+}
+
+impl<F: Fn(&str) -> bool> ValueMatcher for ValuePredicate<F> {
+    fn matches_value(&self, value: &str) -> bool {
+        (self.predicate)(value)
+    }
+
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+}
+
+/// Value that contains `pattern`
+pub fn contains<S: Into<String>>(pattern: S) -> ValuePredicate<impl Fn(&str) -> bool> {
+    let pattern = pattern.into();
+    let description = format!("containing {:?}", pattern);
+    ValuePredicate { predicate: move |value: &str| value.contains(pattern.as_str()), description }
+}
+
+/// Value that starts with `prefix`
+pub fn starts_with<S: Into<String>>(prefix: S) -> ValuePredicate<impl Fn(&str) -> bool> {
+    let prefix = prefix.into();
+    let description = format!("starting with {:?}", prefix);
+    ValuePredicate { predicate: move |value: &str| value.starts_with(prefix.as_str()), description }
+}
+
+/// Value equal to `expected` ignoring ASCII case
+pub fn equals_ignore_case<S: Into<String>>(expected: S) -> ValuePredicate<impl Fn(&str) -> bool> {
+    let expected = expected.into();
+    let description = format!("equal to {:?} ignoring case", expected);
+    ValuePredicate { predicate: move |value: &str| value.eq_ignore_ascii_case(&expected), description }
+}
+
+/// Any value, the matcher only checks its presence
+pub fn any_value() -> ValuePredicate<impl Fn(&str) -> bool> {
+    ValuePredicate { predicate: |_: &str| true, description: "present".to_string() }
+}
+
+/// Matches the request method
+pub fn method(expected: HttpMethod) -> MethodMatcher {
+    MethodMatcher(expected)
+}
+
+/// Matches the URL path, ignoring scheme, authority and query string
+pub fn path<S: Into<String>>(expected: S) -> PathMatcher {
+    PathMatcher(expected.into())
+}
+
+/// Matches a header value. Header names are case-insensitive.
+pub fn header<K: Into<String>, V: ValueMatcher>(name: K, value: V) -> HeaderMatcher<V> {
+    HeaderMatcher(name.into(), value)
+}
+
+/// Matches a request param value
+pub fn param<K: Into<String>, V: ValueMatcher>(name: K, value: V) -> ParamMatcher<V> {
+    ParamMatcher(name.into(), value)
+}
+
+/// Matches a request cookie value
+pub fn cookie<K: Into<String>, V: ValueMatcher>(name: K, value: V) -> CookieMatcher<V> {
+    CookieMatcher(name.into(), value)
+}
+
+/// Matches the body decoded as UTF-8 text
+pub fn body<V: ValueMatcher>(value: V) -> BodyMatcher<V> {
+    BodyMatcher(value)
+}
+
+/// Matches the body parsed as JSON with a predicate
+pub fn json_body<F: Fn(&JsonValue) -> bool>(predicate: F) -> JsonBodyMatcher<F> {
+    JsonBodyMatcher(predicate)
+}
+
+/// Matches if `matcher` does not match
+pub fn not<M: Matcher>(matcher: M) -> Not<M> {
+    Not(matcher)
+}
+
+/// Matches any request
+pub fn any() -> Any {
+    Any
+}
+
+/// Matcher of the request method, see [method]
+pub struct MethodMatcher(HttpMethod);
+
+impl Matcher for MethodMatcher {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        if request.method() == self.0 {
+            Ok(())
+        } else {
+            Err(Mismatch::new(format!("{}: method is {}", self.describe(), request.method())))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("method {}", self.0)
+    }
+}
+
+/// Matcher of the URL path, see [path]
+pub struct PathMatcher(String);
+
+impl Matcher for PathMatcher {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        let actual = uri::path(request.url());
+        if actual == self.0 {
+            Ok(())
+        } else {
+            Err(Mismatch::new(format!("{}: path is {}", self.describe(), actual)))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("path {}", self.0)
+    }
+}
+
+/// Checks an optional value with a value matcher
+fn check_value<V: ValueMatcher>(description: String, kind: &str, value: Option<&str>, matcher: &V) -> Result<(), Mismatch> {
+    match value {
+        Some(value) if matcher.matches_value(value) => Ok(()),
+        Some(value) => Err(Mismatch::new(format!("{}: {} is {:?}", description, kind, value))),
+        None => Err(Mismatch::new(format!("{}: {} is missing", description, kind)))
+    }
+}
+
+/// Matcher of a header, see [header]
+pub struct HeaderMatcher<V>(String, V);
+
+impl<V: ValueMatcher> Matcher for HeaderMatcher<V> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        check_value(self.describe(), "header", request.headers().get(&self.0), &self.1)
+    }
+
+    fn describe(&self) -> String {
+        format!("header {} {}", self.0, self.1.describe())
+    }
+}
+
+/// Matcher of a request param, see [param]
+pub struct ParamMatcher<V>(String, V);
+
+impl<V: ValueMatcher> Matcher for ParamMatcher<V> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        check_value(self.describe(), "param", request.params().get(&self.0), &self.1)
+    }
+
+    fn describe(&self) -> String {
+        format!("param {} {}", self.0, self.1.describe())
+    }
+}
+
+/// Matcher of a request cookie, see [cookie]
+pub struct CookieMatcher<V>(String, V);
+
+impl<V: ValueMatcher> Matcher for CookieMatcher<V> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        check_value(self.describe(), "cookie", request.cookies().get(&self.0), &self.1)
+    }
+
+    fn describe(&self) -> String {
+        format!("cookie {} {}", self.0, self.1.describe())
+    }
+}
+
+/// Matcher of the body text, see [body]
+pub struct BodyMatcher<V>(V);
+
+impl<V: ValueMatcher> Matcher for BodyMatcher<V> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        match request.body() {
+            Some(body) => match std::str::from_utf8(body) {
+                Ok(text) => check_value(self.describe(), "body", Some(text), &self.0),
+                Err(_) => Err(Mismatch::new(format!("{}: body is not UTF-8 text", self.describe())))
+            },
+            None => Err(Mismatch::new(format!("{}: body is missing", self.describe())))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("body {}", self.0.describe())
+    }
+}
+
+/// Matcher of the JSON body, see [json_body]
+pub struct JsonBodyMatcher<F>(F);
+
+impl<F: Fn(&JsonValue) -> bool> Matcher for JsonBodyMatcher<F> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        match request.json() {
+            Ok(value) if (self.0)(&value) => Ok(()),
+            Ok(value) => Err(Mismatch::new(format!("{}: body is {}", self.describe(), value.dump()))),
+            Err(e) => Err(Mismatch::new(format!("{}: {}", self.describe(), e)))
+        }
+    }
+
+    fn describe(&self) -> String {
+        "JSON body matching predicate".to_string()
+    }
+}
+
+/// Conjunction of matchers, see [Matcher::and]
+pub struct And<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for And<A, B> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        match (self.0.check(request), self.1.check(request)) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(e), Ok(())) | (Ok(()), Err(e)) => Err(e),
+            (Err(mut first), Err(second)) => {
+                first.reasons.extend(second.reasons);
+                Err(first)
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} and {}", self.0.describe(), self.1.describe())
+    }
+}
+
+/// Disjunction of matchers, see [Matcher::or]
+pub struct Or<A, B>(A, B);
+
+impl<A: Matcher, B: Matcher> Matcher for Or<A, B> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        match self.0.check(request) {
+            Ok(()) => Ok(()),
+            Err(first) => match self.1.check(request) {
+                Ok(()) => Ok(()),
+                Err(second) => Err(Mismatch::new(format!("none of ({}) or ({}) matches: {}; {}",
+                    self.0.describe(), self.1.describe(), first.reasons.join("; "), second.reasons.join("; "))))
+            }
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("({}) or ({})", self.0.describe(), self.1.describe())
+    }
+}
+
+/// Negation of a matcher, see [not]
+pub struct Not<M>(M);
+
+impl<M: Matcher> Matcher for Not<M> {
+    fn check(&self, request: &Request) -> Result<(), Mismatch> {
+        match self.0.check(request) {
+            Ok(()) => Err(Mismatch::new(format!("{}: it matches", self.describe()))),
+            Err(_) => Ok(())
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("not ({})", self.0.describe())
+    }
+}
+
+/// Matcher of any request, see [any]
+pub struct Any;
+
+impl Matcher for Any {
+    fn check(&self, _request: &Request) -> Result<(), Mismatch> {
+        Ok(())
+    }
+
+    fn describe(&self) -> String {
+        "any request".to_string()
+    }
+}
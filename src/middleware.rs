@@ -0,0 +1,155 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Request and response interceptors
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::middleware::{Middleware, Pipeline, DefaultHeader};
+//!
+//! struct Tracing;
+//!
+//! impl Middleware for Tracing {
+//!     fn on_response(&self, request: &Request, response: &mut Response) {
+//!         if let Some(id) = request.headers().get("X-Request-Id") {
+//!             response.insert_header("X-Request-Id", id.to_string());
+//!         }
+//!     }
+//! }
+//!
+//! let mut pipeline = Pipeline::new();
+//! pipeline.add(DefaultHeader::new("Authorization", "Bearer 1234"))
+//!         .add(DefaultHeader::new("X-Request-Id", "42"))
+//!         .add(Tracing);
+//!
+//! let response = pipeline.execute(Request::get("https://service.com/users"), |request| {
+//!     assert_eq!(request.headers().get("Authorization"), Some("Bearer 1234"));
+//!     Response::new(HTTP_200_OK)
+//! });
+//!
+//! assert_eq!(response.headers().get("X-Request-Id"), Some("42"));
+//! ```
+
+use std::io::Error;
+use crate::{Request, Response};
+use crate::transport::Transport;
+#[cfg(feature = "async")]
+use crate::transport::AsyncTransport;
+
+/// Interceptor of the requests before they are sent and of their responses once received
+pub trait Middleware {
+    /// Processes a request before it is sent, by default it does nothing
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Processes the response of a `request`, by default it does nothing
+    fn on_response(&self, _request: &Request, _response: &mut Response) {}
+}
+
+/// Ordered chain of middleware.
+///
+/// Requests go through the middleware in insertion order and responses in reverse order,
+/// so the first middleware sees the request first and the response last.
+#[derive(Default)]
+pub struct Pipeline {
+    layers: Vec<Box<dyn Middleware + Send + Sync>>
+}
+
+impl Pipeline {
+    /// Constructor of an empty pipeline
+    pub fn new() -> Pipeline {
+        Pipeline { layers: Vec::new() }
+    }
+
+    /// Appends a middleware at the end of the chain
+    pub fn add<M: Middleware + Send + Sync + 'static>(&mut self, middleware: M) -> &mut Self {
+        self.layers.push(Box::new(middleware));
+        self
+    }
+
+    /// Number of middleware in the chain
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Checks the chain is empty
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Runs the `on_request` hooks in insertion order
+    pub fn on_request(&self, request: &mut Request) {
+        for layer in self.layers.iter() {
+            layer.on_request(request);
+        }
+    }
+
+    /// Runs the `on_response` hooks in reverse insertion order
+    pub fn on_response(&self, request: &Request, response: &mut Response) {
+        for layer in self.layers.iter().rev() {
+            layer.on_response(request, response);
+        }
+    }
+
+    /// Processes `request`, obtains its response with `send` and processes the response
+    pub fn execute<F>(&self, mut request: Request, send: F) -> Response
+    where F: FnOnce(&Request) -> Response {
+        self.on_request(&mut request);
+        let mut response = send(&request);
+        self.on_response(&request, &mut response);
+        response
+    }
+
+    /// Processes `request`, sends it with `transport` and processes the response.
+    /// Transport errors are returned without running the `on_response` hooks.
+    pub fn send<T: Transport>(&self, mut request: Request, transport: &T) -> Result<Response, Error> {
+        self.on_request(&mut request);
+        let mut response = transport.send(request.clone())?;
+        self.on_response(&request, &mut response);
+        Ok(response)
+    }
+
+    /// Processes `request`, sends it with an asynchronous `transport` and processes the response
+    /// (feature `async`). Transport errors are returned without running the `on_response` hooks.
+    #[cfg(feature = "async")]
+    pub async fn send_async<T: AsyncTransport>(&self, mut request: Request, transport: &T) -> Result<Response, Error> {
+        self.on_request(&mut request);
+        let mut response = transport.send(request.clone()).await?;
+        self.on_response(&request, &mut response);
+        Ok(response)
+    }
+}
+
+/// Middleware that inserts a header in the requests that do not have it
+pub struct DefaultHeader {
+    name: String,
+    value: String
+}
+
+impl DefaultHeader {
+    /// Constructor with the header `name` and `value`
+    pub fn new<K, V>(name: K, value: V) -> DefaultHeader
+    where K: Into<String>,
+          V: Into<String> {
+        DefaultHeader { name: name.into(), value: value.into() }
+    }
+}
+
+impl Middleware for DefaultHeader {
+    fn on_request(&self, request: &mut Request) {
+        if !request.headers().contains_key(self.name.as_str()) {
+            request.insert_header(self.name.clone(), self.value.clone());
+        }
+    }
+}
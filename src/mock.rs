@@ -0,0 +1,199 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! In-process mock of HTTP services
+//!
+//! ```
+//! use wrequest::{Request, Response, HttpMethod, HTTP_200_OK, HTTP_404_NOT_FOUND};
+//! use wrequest::mock::MockRouter;
+//! use wrequest::matchers::header;
+//!
+//! let mut ok = Response::new(HTTP_200_OK);
+//! ok.set_body(b"admin".to_vec()).unwrap();
+//!
+//! let mut router = MockRouter::new();
+//! router.route_when(HttpMethod::GET, "/users/{id}", header("X-Role", "admin"), ok)
+//!       .route_fn(HttpMethod::GET, "/users/{id}", |_request, params| {
+//!           let mut response = Response::new(HTTP_200_OK);
+//!           response.set_body(params["id"].clone().into_bytes()).unwrap();
+//!           response
+//!       });
+//!
+//! let mut request = Request::get("https://service.com/users/42");
+//! assert_eq!(router.handle(&request).body().unwrap(), b"42");
+//!
+//! request.insert_header("X-Role", "admin");
+//! assert_eq!(router.handle(&request).body().unwrap(), b"admin");
+//!
+//! assert_eq!(router.handle(&Request::get("https://service.com/groups")).status_code(), HTTP_404_NOT_FOUND);
+//! ```
+
+use std::collections::HashMap;
+use crate::{HttpMethod, Request, Response, uri, HTTP_404_NOT_FOUND, HTTP_405_METHOD_NOT_ALLOWED};
+use crate::matchers::Matcher;
+
+/// Values of the `{param}` segments of a route pattern
+pub type PathParams = HashMap<String, String>;
+
+/// Function that builds the response of a matched request
+type HandlerFn = Box<dyn Fn(&Request, &PathParams) -> Response + Send + Sync>;
+
+/// Response producer of a route
+enum Handler {
+    /// Canned response, cloned on every match
+    Fixed(Box<Response>),
+    /// Response built for every matched request
+    Dynamic(HandlerFn)
+}
+
+/// Segment of a route pattern
+enum Segment {
+    /// Segment that must be equal
+    Literal(String),
+    /// `{name}` segment that captures any non-empty value
+    Param(String)
+}
+
+struct Route {
+    method: HttpMethod,
+    pattern: Vec<Segment>,
+    matcher: Option<Box<dyn Matcher + Send + Sync>>,
+    handler: Handler
+}
+
+impl Route {
+    /// Checks the path matches the route pattern, returning the captured params
+    fn match_path(&self, path: &str) -> Option<PathParams> {
+        let segments: Vec<&str> = path.split('/').collect();
+        if segments.len() != self.pattern.len() {
+            return None;
+        }
+        let mut params = PathParams::new();
+        for (expected, actual) in self.pattern.iter().zip(segments.iter()) {
+            match expected {
+                Segment::Literal(literal) => if literal != actual {
+                    return None;
+                },
+                Segment::Param(name) => {
+                    if actual.is_empty() {
+                        return None;
+                    }
+                    params.insert(name.clone(), uri::percent_decode(actual));
+                }
+            }
+        }
+        Some(params)
+    }
+}
+
+/// Router of requests to canned or computed responses.
+///
+/// Routes are tried in registration order and the first one whose method, path pattern and
+/// optional matcher match handles the request. If the path matches some route but not its method
+/// the response is `405 Method Not Allowed`, otherwise it is the fallback response (`404 Not Found`
+/// by default).
+pub struct MockRouter {
+    routes: Vec<Route>,
+    fallback: Response
+}
+
+impl MockRouter {
+    /// Constructor
+    pub fn new() -> MockRouter {
+        MockRouter {
+            routes: Vec::new(),
+            fallback: Response::new(HTTP_404_NOT_FOUND)
+        }
+    }
+
+    /// Registers a canned `response` for a `method` and path `pattern`, like `/users/{id}`
+    pub fn route<P: AsRef<str>>(&mut self, method: HttpMethod, pattern: P, response: Response) -> &mut Self {
+        self.add(method, pattern.as_ref(), None, Handler::Fixed(Box::new(response)))
+    }
+
+    /// Registers a canned `response` for a `method` and path `pattern` whose requests also match `matcher`
+    pub fn route_when<P, M>(&mut self, method: HttpMethod, pattern: P, matcher: M, response: Response) -> &mut Self
+    where P: AsRef<str>,
+          M: Matcher + Send + Sync + 'static {
+        self.add(method, pattern.as_ref(), Some(Box::new(matcher)), Handler::Fixed(Box::new(response)))
+    }
+
+    /// Registers a `handler` that builds the response from the request and the captured path params
+    pub fn route_fn<P, F>(&mut self, method: HttpMethod, pattern: P, handler: F) -> &mut Self
+    where P: AsRef<str>,
+          F: Fn(&Request, &PathParams) -> Response + Send + Sync + 'static {
+        self.add(method, pattern.as_ref(), None, Handler::Dynamic(Box::new(handler)))
+    }
+
+    /// Sets the response for requests that do not match any route
+    pub fn fallback(&mut self, response: Response) -> &mut Self {
+        self.fallback = response;
+        self
+    }
+
+    /// Builds the response of a request
+    pub fn handle(&self, request: &Request) -> Response {
+        let path = uri::path(request.url());
+        let mut allowed: Vec<HttpMethod> = Vec::new();
+
+        for route in self.routes.iter() {
+            let params = match route.match_path(path) {
+                Some(params) => params,
+                None => continue
+            };
+            if route.method != request.method() {
+                if !allowed.contains(&route.method) {
+                    allowed.push(route.method);
+                }
+                continue;
+            }
+            if let Some(ref matcher) = route.matcher {
+                if !matcher.matches(request) {
+                    continue;
+                }
+            }
+            return match route.handler {
+                Handler::Fixed(ref response) => (**response).clone(),
+                Handler::Dynamic(ref handler) => handler(request, &params)
+            };
+        }
+
+        if allowed.is_empty() {
+            self.fallback.clone()
+        } else {
+            let mut response = Response::new(HTTP_405_METHOD_NOT_ALLOWED);
+            let allow: Vec<String> = allowed.iter().map(|m| m.to_string()).collect();
+            response.insert_header("Allow", allow.join(", "));
+            response
+        }
+    }
+
+    fn add(&mut self, method: HttpMethod, pattern: &str, matcher: Option<Box<dyn Matcher + Send + Sync>>,
+           handler: Handler) -> &mut Self {
+        let pattern = pattern.split('/')
+            .map(|segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => Segment::Param(name.to_string()),
+                None => Segment::Literal(segment.to_string())
+            })
+            .collect();
+        self.routes.push(Route { method, pattern, matcher, handler });
+        self
+    }
+}
+
+impl Default for MockRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,535 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Streaming multipart bodies
+//!
+//! [MultipartWriter] emits the parts of a multipart body incrementally to any `Write` sink, so large
+//! files are not buffered in memory. With the `tokio` feature [AsyncMultipartWriter] does the same for
+//! tokio `AsyncWrite` sinks. [MultipartReader] parses them incrementally from byte chunks, yielding
+//! part events so uploads can be streamed to disk.
+//!
+//! ```
+//! use std::io::Write;
+//! use wrequest::multipart::{Event, MultipartReader, MultipartWriter, Part, encoded_length};
+//!
+//! let file = Part::file("artifact", "build.tar", "application/x-tar");
+//! let data = b"tar contents";
+//!
+//! let mut writer = MultipartWriter::new(Vec::new());
+//! let content_type = writer.content_type();
+//! let length = encoded_length(writer.boundary(), &[(&file, data.len() as u64)]);
+//!
+//! writer.start_part(&file).unwrap();
+//! writer.write_all(data).unwrap();
+//! let body = writer.finish().unwrap();
+//! assert_eq!(body.len() as u64, length);
+//!
+//! let mut reader = MultipartReader::from_content_type(&content_type).unwrap();
+//! let mut received = Vec::new();
+//! for chunk in body.chunks(7) {
+//!     reader.feed(chunk);
+//!     while let Some(event) = reader.next_event().unwrap() {
+//!         match event {
+//!             Event::PartStart(part) => assert_eq!(part.filename().as_deref(), Some("build.tar")),
+//!             Event::Data(data) => received.extend_from_slice(&data),
+//!             Event::PartEnd | Event::End => {}
+//!         }
+//!     }
+//! }
+//! reader.finish().unwrap();
+//! assert_eq!(received, data);
+//! ```
+
+use std::io::{Error, ErrorKind, Read, Write};
+use crate::{HeaderMap, CONTENT_TYPE};
+
+/// `Content-Disposition` header name
+const CONTENT_DISPOSITION: &str = "Content-Disposition";
+
+/// Maximum boundary length (RFC 2046)
+const MAX_BOUNDARY_LEN: usize = 70;
+
+/// Part of a multipart body, described by its headers. Its data is written separately.
+#[derive(Clone)]
+pub struct Part {
+    headers: HeaderMap
+}
+
+impl Part {
+    /// Creates a part with the given headers
+    pub fn new(headers: HeaderMap) -> Part {
+        Part { headers }
+    }
+
+    /// Creates a `form-data` part for the field `name`
+    pub fn form_data(name: &str) -> Part {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_DISPOSITION, format!("form-data; name=\"{}\"", escape_quoted(name)));
+        Part { headers }
+    }
+
+    /// Creates a `form-data` part for the file `filename` of the field `name`
+    pub fn file(name: &str, filename: &str, content_type: &str) -> Part {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_DISPOSITION, format!("form-data; name=\"{}\"; filename=\"{}\"",
+            escape_quoted(name), escape_quoted(filename)));
+        headers.insert(CONTENT_TYPE, content_type.to_string());
+        Part { headers }
+    }
+
+    /// Adds a header to the part
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Gets the part headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Gets the field name of a `form-data` part, from its `Content-Disposition` header
+    pub fn name(&self) -> Option<String> {
+        self.disposition_param("name")
+    }
+
+    /// Gets the file name of a `form-data` part, from its `Content-Disposition` header
+    pub fn filename(&self) -> Option<String> {
+        self.disposition_param("filename")
+    }
+
+    fn disposition_param(&self, key: &str) -> Option<String> {
+        let disposition = self.headers.get(CONTENT_DISPOSITION)?;
+        disposition.split(';').skip(1)
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case(key))
+            .map(|(_, value)| {
+                let value = value.trim();
+                value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value).to_string()
+            })
+    }
+}
+
+/// Escapes a `Content-Disposition` quoted value the way browsers do
+fn escape_quoted(value: &str) -> String {
+    value.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Generates a random boundary
+pub fn generate_boundary() -> String {
+    format!("wrequest-{}", uuid::Uuid::new_v4().simple())
+}
+
+/// Checks a boundary is valid (RFC 2046): 1 to 70 letters, digits or ``'()+_,-./:=?`` and spaces,
+/// not ending with a space
+pub fn is_valid_boundary(boundary: &str) -> bool {
+    !boundary.is_empty() && boundary.len() <= MAX_BOUNDARY_LEN && !boundary.ends_with(' ')
+        && boundary.bytes().all(|c| c.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&c))
+}
+
+/// Framing of the parts: delimiters and headers
+#[derive(Clone, Debug)]
+struct Framing {
+    boundary: String,
+    /// Number of parts started
+    parts: usize
+}
+
+impl Framing {
+    fn new(boundary: String) -> Result<Framing, Error> {
+        if !is_valid_boundary(&boundary) {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid multipart boundary {:?}", boundary)));
+        }
+        Ok(Framing { boundary, parts: 0 })
+    }
+
+    /// Delimiter and headers that start the next part, ending the previous one
+    fn start(&mut self, part: &Part) -> Vec<u8> {
+        let result = part_header(&self.boundary, part, self.parts == 0);
+        self.parts += 1;
+        result
+    }
+
+    /// Closing delimiter, ending the last part
+    fn finish(&self) -> Vec<u8> {
+        closing(&self.boundary, self.parts == 0)
+    }
+
+    fn check_started(&self) -> Result<(), Error> {
+        if self.parts == 0 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Multipart data written before starting a part"));
+        }
+        Ok(())
+    }
+}
+
+fn part_header(boundary: &str, part: &Part, first: bool) -> Vec<u8> {
+    let mut result = String::new();
+    if !first {
+        result.push_str("\r\n");
+    }
+    result.push_str("--");
+    result.push_str(boundary);
+    result.push_str("\r\n");
+    for (name, value) in part.headers.iter() {
+        result.push_str(name);
+        result.push_str(": ");
+        result.push_str(value);
+        result.push_str("\r\n");
+    }
+    result.push_str("\r\n");
+    result.into_bytes()
+}
+
+fn closing(boundary: &str, empty: bool) -> Vec<u8> {
+    let prefix = if empty { "" } else { "\r\n" };
+    format!("{}--{}--\r\n", prefix, boundary).into_bytes()
+}
+
+/// Computes the total length of a multipart body from its parts and their data lengths, for example
+/// to send a `Content-Length` header before streaming the body
+pub fn encoded_length(boundary: &str, parts: &[(&Part, u64)]) -> u64 {
+    let framing: usize = parts.iter().enumerate()
+        .map(|(i, (part, _))| part_header(boundary, part, i == 0).len())
+        .sum();
+    let data: u64 = parts.iter().map(|(_, length)| *length).sum();
+    framing as u64 + data + closing(boundary, parts.is_empty()).len() as u64
+}
+
+/// Multipart body writer to a `Write` sink.
+///
+/// Each part is started with [MultipartWriter::start_part] and its data written through the `Write`
+/// implementation, in chunks of any size. [MultipartWriter::finish] writes the closing delimiter.
+pub struct MultipartWriter<W: Write> {
+    sink: W,
+    framing: Framing
+}
+
+impl<W: Write> MultipartWriter<W> {
+    /// Creates a writer with a random boundary
+    pub fn new(sink: W) -> MultipartWriter<W> {
+        MultipartWriter { sink, framing: Framing { boundary: generate_boundary(), parts: 0 } }
+    }
+
+    /// Creates a writer with the given boundary, that must not appear in the parts data
+    pub fn with_boundary(sink: W, boundary: &str) -> Result<MultipartWriter<W>, Error> {
+        Ok(MultipartWriter { sink, framing: Framing::new(boundary.to_string())? })
+    }
+
+    /// Gets the boundary
+    pub fn boundary(&self) -> &str {
+        &self.framing.boundary
+    }
+
+    /// Gets the `Content-Type` header value of the body, `multipart/form-data` with the boundary
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.framing.boundary)
+    }
+
+    /// Writes the delimiter and headers of a part, ending the previous one
+    pub fn start_part(&mut self, part: &Part) -> Result<(), Error> {
+        let header = self.framing.start(part);
+        self.sink.write_all(&header)
+    }
+
+    /// Writes a whole part whose data is read from `reader`, returning the data length
+    pub fn write_part<R: Read>(&mut self, part: &Part, reader: &mut R) -> Result<u64, Error> {
+        self.start_part(part)?;
+        std::io::copy(reader, &mut self.sink)
+    }
+
+    /// Writes the closing delimiter and flushes the sink, returning it
+    pub fn finish(mut self) -> Result<W, Error> {
+        let closing = self.framing.finish();
+        self.sink.write_all(&closing)?;
+        self.sink.flush()?;
+        Ok(self.sink)
+    }
+}
+
+impl<W: Write> Write for MultipartWriter<W> {
+    /// Writes data of the current part
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        self.framing.check_started()?;
+        self.sink.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.sink.flush()
+    }
+}
+
+/// Multipart body writer to a tokio `AsyncWrite` sink (feature `tokio`).
+///
+/// It works like [MultipartWriter], with part data written by [AsyncMultipartWriter::write_all].
+#[cfg(feature = "tokio")]
+pub struct AsyncMultipartWriter<W: tokio::io::AsyncWrite + Unpin> {
+    sink: W,
+    framing: Framing
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncMultipartWriter<W> {
+    /// Creates a writer with a random boundary
+    pub fn new(sink: W) -> AsyncMultipartWriter<W> {
+        AsyncMultipartWriter { sink, framing: Framing { boundary: generate_boundary(), parts: 0 } }
+    }
+
+    /// Creates a writer with the given boundary, that must not appear in the parts data
+    pub fn with_boundary(sink: W, boundary: &str) -> Result<AsyncMultipartWriter<W>, Error> {
+        Ok(AsyncMultipartWriter { sink, framing: Framing::new(boundary.to_string())? })
+    }
+
+    /// Gets the boundary
+    pub fn boundary(&self) -> &str {
+        &self.framing.boundary
+    }
+
+    /// Gets the `Content-Type` header value of the body, `multipart/form-data` with the boundary
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.framing.boundary)
+    }
+
+    /// Writes the delimiter and headers of a part, ending the previous one
+    pub async fn start_part(&mut self, part: &Part) -> Result<(), Error> {
+        let header = self.framing.start(part);
+        self.write_sink(&header).await
+    }
+
+    /// Writes data of the current part
+    pub async fn write_all(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.framing.check_started()?;
+        self.write_sink(data).await
+    }
+
+    /// Writes the closing delimiter and flushes the sink, returning it
+    pub async fn finish(mut self) -> Result<W, Error> {
+        let closing = self.framing.finish();
+        self.write_sink(&closing).await?;
+        std::future::poll_fn(|cx| std::pin::Pin::new(&mut self.sink).poll_flush(cx)).await?;
+        Ok(self.sink)
+    }
+
+    async fn write_sink(&mut self, mut data: &[u8]) -> Result<(), Error> {
+        while !data.is_empty() {
+            let written = std::future::poll_fn(|cx| std::pin::Pin::new(&mut self.sink).poll_write(cx, data)).await?;
+            if written == 0 {
+                return Err(Error::from(ErrorKind::WriteZero));
+            }
+            data = &data[written..];
+        }
+        Ok(())
+    }
+}
+
+/// Gets the boundary of a multipart `Content-Type` header value
+pub fn boundary_from_content_type(content_type: &str) -> Option<String> {
+    let (media_type, params) = content_type.split_once(';')?;
+    if !media_type.trim().to_ascii_lowercase().starts_with("multipart/") {
+        return None;
+    }
+    params.split(';')
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| {
+            let value = value.trim();
+            value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value).to_string()
+        })
+}
+
+/// Maximum size of the headers of a part read by [MultipartReader]
+pub const MAX_PART_HEADERS_SIZE: usize = 16 * 1024;
+
+/// Event of a multipart body read by [MultipartReader]
+#[derive(Clone)]
+pub enum Event {
+    /// A part starts, with its headers
+    PartStart(Box<Part>),
+    /// Chunk of data of the current part
+    Data(Vec<u8>),
+    /// The current part ends
+    PartEnd,
+    /// The closing delimiter was read, remaining data is ignored
+    End
+}
+
+/// Position of [MultipartReader] in the body
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum ReadState {
+    /// Before the first delimiter
+    Preamble,
+    /// After a delimiter, before its line end or the closing `--`
+    Delimiter,
+    /// Reading the part headers
+    Headers,
+    /// Reading the part data
+    Data,
+    /// After the closing delimiter
+    Epilogue
+}
+
+/// Incremental multipart body parser.
+///
+/// The body is [fed](MultipartReader::feed) in chunks of any size and [MultipartReader::next_event]
+/// returns the parsed events, or `None` when more data is needed. Part data is returned as soon as it is
+/// known not to be part of a delimiter, so only the part headers and a delimiter length are buffered.
+pub struct MultipartReader {
+    /// `CRLF--boundary`
+    delimiter: Vec<u8>,
+    buffer: Vec<u8>,
+    state: ReadState
+}
+
+impl MultipartReader {
+    /// Creates a reader of a body with the given boundary
+    pub fn new(boundary: &str) -> Result<MultipartReader, Error> {
+        if !is_valid_boundary(boundary) {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid multipart boundary {:?}", boundary)));
+        }
+        Ok(MultipartReader {
+            delimiter: format!("\r\n--{}", boundary).into_bytes(),
+            // The first delimiter may not be preceded by a line end
+            buffer: b"\r\n".to_vec(),
+            state: ReadState::Preamble
+        })
+    }
+
+    /// Creates a reader of a body with the boundary of its `Content-Type` header value
+    pub fn from_content_type(content_type: &str) -> Result<MultipartReader, Error> {
+        match boundary_from_content_type(content_type) {
+            Some(boundary) => Self::new(&boundary),
+            None => Err(Error::new(ErrorKind::InvalidInput, format!("No multipart boundary in {:?}", content_type)))
+        }
+    }
+
+    /// Adds a chunk of the body
+    pub fn feed(&mut self, chunk: &[u8]) {
+        if self.state != ReadState::Epilogue {
+            self.buffer.extend_from_slice(chunk);
+        }
+    }
+
+    /// Checks the closing delimiter was read
+    pub fn is_finished(&self) -> bool {
+        self.state == ReadState::Epilogue
+    }
+
+    /// Signals the end of the body, failing if the closing delimiter was not read
+    pub fn finish(&self) -> Result<(), Error> {
+        if self.is_finished() {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::UnexpectedEof, "Multipart body ended before its closing delimiter"))
+        }
+    }
+
+    /// Parses the next event from the data fed so far, `None` if more data is needed
+    pub fn next_event(&mut self) -> Result<Option<Event>, Error> {
+        loop {
+            match self.state {
+                ReadState::Preamble => match find(&self.buffer, &self.delimiter) {
+                    Some(i) => {
+                        self.buffer.drain(..i + self.delimiter.len());
+                        self.state = ReadState::Delimiter;
+                    },
+                    None => {
+                        let keep = self.delimiter.len() - 1;
+                        let discard = self.buffer.len().saturating_sub(keep);
+                        self.buffer.drain(..discard);
+                        return Ok(None);
+                    }
+                },
+                ReadState::Delimiter => {
+                    if self.buffer.starts_with(b"--") {
+                        self.buffer = Vec::new();
+                        self.state = ReadState::Epilogue;
+                        return Ok(Some(Event::End));
+                    }
+                    let Some(end) = find(&self.buffer, b"\r\n") else {
+                        if self.buffer.len() > MAX_PART_HEADERS_SIZE {
+                            return Err(invalid_data("Multipart delimiter line too long"));
+                        }
+                        return Ok(None);
+                    };
+                    // Transport padding is allowed after the delimiter
+                    if !self.buffer[..end].iter().all(|c| *c == b' ' || *c == b'\t') {
+                        return Err(invalid_data("Invalid multipart delimiter line"));
+                    }
+                    self.buffer.drain(..end + 2);
+                    self.state = ReadState::Headers;
+                },
+                ReadState::Headers => {
+                    let (headers, consumed) = if self.buffer.starts_with(b"\r\n") {
+                        (HeaderMap::new(), 2)
+                    } else {
+                        match find(&self.buffer, b"\r\n\r\n") {
+                            Some(end) => (parse_headers(&self.buffer[..end])?, end + 4),
+                            None => {
+                                if self.buffer.len() > MAX_PART_HEADERS_SIZE {
+                                    return Err(invalid_data("Multipart part headers too large"));
+                                }
+                                return Ok(None);
+                            }
+                        }
+                    };
+                    self.buffer.drain(..consumed);
+                    self.state = ReadState::Data;
+                    return Ok(Some(Event::PartStart(Box::new(Part::new(headers)))));
+                },
+                ReadState::Data => return Ok(match find(&self.buffer, &self.delimiter) {
+                    Some(0) => {
+                        self.buffer.drain(..self.delimiter.len());
+                        self.state = ReadState::Delimiter;
+                        Some(Event::PartEnd)
+                    },
+                    Some(i) => Some(Event::Data(self.buffer.drain(..i).collect())),
+                    None => {
+                        let keep = self.delimiter.len() - 1;
+                        let available = self.buffer.len().saturating_sub(keep);
+                        if available == 0 {
+                            None
+                        } else {
+                            Some(Event::Data(self.buffer.drain(..available).collect()))
+                        }
+                    }
+                }),
+                ReadState::Epilogue => return Ok(None)
+            }
+        }
+    }
+}
+
+fn parse_headers(data: &[u8]) -> Result<HeaderMap, Error> {
+    let text = std::str::from_utf8(data).map_err(|_| invalid_data("Multipart part headers are not UTF-8"))?;
+    let mut headers = HeaderMap::new();
+    for line in text.split("\r\n") {
+        match line.split_once(':') {
+            Some((name, value)) => headers.try_insert(name.to_string(), value.trim().to_string())
+                .map(|_| ())
+                .map_err(|e| invalid_data(e.to_string()))?,
+            None => return Err(invalid_data(format!("Invalid multipart part header {:?}", line)))
+        }
+    }
+    Ok(headers)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn invalid_data<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidData, e)
+}
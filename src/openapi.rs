@@ -0,0 +1,543 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! OpenAPI 3 contract validation of requests and responses (feature `openapi`)
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::openapi::OpenApi;
+//! use json::object;
+//!
+//! let api = OpenApi::parse(r#"{
+//!     "openapi": "3.0.3",
+//!     "paths": {
+//!         "/users/{id}": {
+//!             "get": {
+//!                 "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer"}}],
+//!                 "responses": {
+//!                     "200": {"content": {"application/json": {"schema": {
+//!                         "type": "object", "required": ["name"], "properties": {"name": {"type": "string"}}
+//!                     }}}}
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }"#).unwrap();
+//!
+//! let request = Request::get("https://service.com/users/12");
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.set_json(&object! { name: "John" }).unwrap();
+//!
+//! assert!(api.validate(&request, &response).is_ok());
+//!
+//! let request = Request::get("https://service.com/users/john");
+//! let violations = api.validate_request(&request).unwrap_err();
+//! assert_eq!(violations[0].location, "path.id");
+//! ```
+//!
+//! Only JSON documents are supported. JSON schemas are validated for the `type`, `nullable`, `enum`,
+//! `const`, `required`, `properties`, `additionalProperties`, `items`, length, size and range keywords,
+//! `allOf`, `anyOf`, `oneOf` and local `$ref` references; other keywords (`pattern`, `format`, ...) are ignored.
+
+use std::fmt;
+use std::collections::HashMap;
+use std::io::{Error, ErrorKind};
+use json::JsonValue;
+use crate::{HttpMessage, Request, Response, HttpStatusCode, CONTENT_TYPE, uri};
+
+/// Maximum depth of nested `$ref` references, to stop on circular references
+const MAX_REF_DEPTH: usize = 32;
+
+/// A contract violation found while validating a message
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Violation {
+    /// Location of the violation, for example, `query.page`, `header.Accept`, `body.name` or `status`
+    pub location: String,
+    /// Description of the violation
+    pub message: String
+}
+
+impl Violation {
+    fn new<L, M>(location: L, message: M) -> Violation
+    where L: Into<String>,
+          M: Into<String> {
+        Violation {
+            location: location.into(),
+            message: message.into()
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.location, self.message)
+    }
+}
+
+/// OpenAPI 3 document
+pub struct OpenApi {
+    /// Parsed document
+    document: JsonValue,
+    /// Base paths of the document servers
+    base_paths: Vec<String>
+}
+
+/// Operation matched for a request
+struct Operation<'a> {
+    /// Path item object
+    path_item: &'a JsonValue,
+    /// Operation object
+    operation: &'a JsonValue,
+    /// Values of the path template params
+    path_params: HashMap<String, String>
+}
+
+impl OpenApi {
+    /// Parses an OpenAPI 3 document in JSON format
+    pub fn parse(document: &str) -> Result<OpenApi, Error> {
+        let document = json::parse(document).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        Self::from_json(document)
+    }
+
+    /// Creates the validator from a parsed OpenAPI 3 document
+    pub fn from_json(document: JsonValue) -> Result<OpenApi, Error> {
+        match document["openapi"].as_str() {
+            Some(version) if version.starts_with("3.") => {},
+            _ => return Err(Error::new(ErrorKind::InvalidData, "Not an OpenAPI 3 document"))
+        }
+        if !document["paths"].is_object() {
+            return Err(Error::new(ErrorKind::InvalidData, "Missing paths object"));
+        }
+
+        let mut base_paths: Vec<String> = document["servers"].members()
+            .filter_map(|server| server["url"].as_str())
+            .map(|url| uri::path(url).trim_end_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+        // Longest base paths are tried first
+        base_paths.sort_by_key(|path| std::cmp::Reverse(path.len()));
+
+        Ok(OpenApi {
+            document,
+            base_paths
+        })
+    }
+
+    /// Validates a request and the response received for it
+    pub fn validate(&self, request: &Request, response: &Response) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        if let Some(operation) = self.check_request(request, &mut violations) {
+            self.check_response(&operation, response, &mut violations);
+        }
+        to_result(violations)
+    }
+
+    /// Validates a request: the path and method match an operation, required params, headers and cookies
+    /// are present and valid, and the body matches the operation request body.
+    pub fn validate_request(&self, request: &Request) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        self.check_request(request, &mut violations);
+        to_result(violations)
+    }
+
+    /// Validates a response against the operation matched by its `request`: the status code is documented,
+    /// required headers are present and the body matches the documented content.
+    pub fn validate_response(&self, request: &Request, response: &Response) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        match self.find_operation(request) {
+            Ok(operation) => self.check_response(&operation, response, &mut violations),
+            Err(violation) => violations.push(violation)
+        }
+        to_result(violations)
+    }
+
+    fn find_operation(&self, request: &Request) -> Result<Operation<'_>, Violation> {
+        let mut path = uri::path(request.url());
+        for base in self.base_paths.iter() {
+            if let Some(stripped) = path.strip_prefix(base.as_str()) {
+                if stripped.is_empty() || stripped.starts_with('/') {
+                    path = stripped;
+                    break;
+                }
+            }
+        }
+        let segments: Vec<&str> = path.split('/').collect();
+
+        let mut best: Option<(usize, &JsonValue, HashMap<String, String>)> = None;
+        for (template, path_item) in self.document["paths"].entries() {
+            let template_segments: Vec<&str> = template.split('/').collect();
+            if template_segments.len() != segments.len() {
+                continue;
+            }
+            let mut params = HashMap::new();
+            let mut matched = true;
+            for (expected, actual) in template_segments.iter().zip(segments.iter()) {
+                if expected.starts_with('{') && expected.ends_with('}') {
+                    if actual.is_empty() {
+                        matched = false;
+                        break;
+                    }
+                    params.insert(expected[1..expected.len() - 1].to_string(), uri::percent_decode(actual));
+                } else if expected != actual {
+                    matched = false;
+                    break;
+                }
+            }
+            // Concrete paths take precedence over templated ones
+            if matched && best.as_ref().is_none_or(|(count, _, _)| params.len() < *count) {
+                best = Some((params.len(), self.resolve(path_item), params));
+            }
+        }
+
+        let (_, path_item, path_params) = best
+            .ok_or_else(|| Violation::new("path", format!("No path matches {}", path)))?;
+        let method = request.method().to_string().to_lowercase();
+        let operation = &path_item[method.as_str()];
+        if !operation.is_object() {
+            return Err(Violation::new("method", format!("Method {} is not defined for path {}", request.method(), path)));
+        }
+
+        Ok(Operation {
+            path_item,
+            operation: self.resolve(operation),
+            path_params
+        })
+    }
+
+    fn check_request<'a>(&'a self, request: &Request, violations: &mut Vec<Violation>) -> Option<Operation<'a>> {
+        let operation = match self.find_operation(request) {
+            Ok(operation) => operation,
+            Err(violation) => {
+                violations.push(violation);
+                return None;
+            }
+        };
+
+        // Operation params override path item ones with the same name and location
+        let mut params: Vec<&JsonValue> = Vec::new();
+        for param in operation.operation["parameters"].members().chain(operation.path_item["parameters"].members()) {
+            let param = self.resolve(param);
+            if !params.iter().any(|p| p["name"] == param["name"] && p["in"] == param["in"]) {
+                params.push(param);
+            }
+        }
+
+        for param in params {
+            let name = param["name"].as_str().unwrap_or("");
+            let location = param["in"].as_str().unwrap_or("");
+            let value = match location {
+                "path" => operation.path_params.get(name).map(String::as_str),
+                "query" => request.params().get(name),
+                "header" => request.headers().get(name),
+                "cookie" => request.cookies().get(name),
+                _ => continue
+            };
+            let param_location = format!("{}.{}", location, name);
+            match value {
+                Some(value) => if param["schema"].is_object() {
+                    self.check_param(value, &param["schema"], &param_location, violations);
+                },
+                None => if param["required"].as_bool().unwrap_or(location == "path") {
+                    violations.push(Violation::new(param_location, "Required parameter is missing"));
+                }
+            }
+        }
+
+        let request_body = self.resolve(&operation.operation["requestBody"]);
+        if request_body.is_object() {
+            if request.body().is_none() {
+                if request_body["required"].as_bool().unwrap_or(false) {
+                    violations.push(Violation::new("body", "Required request body is missing"));
+                }
+            } else {
+                self.check_content(request, &request_body["content"], violations);
+            }
+        }
+
+        Some(operation)
+    }
+
+    fn check_response(&self, operation: &Operation<'_>, response: &Response, violations: &mut Vec<Violation>) {
+        let responses = &operation.operation["responses"];
+        let status = response.status_code();
+        let documented = match find_status(responses, status) {
+            Some(documented) => self.resolve(documented),
+            None => {
+                violations.push(Violation::new("status", format!("Status code {} is not documented", status)));
+                return;
+            }
+        };
+
+        for (name, header) in documented["headers"].entries() {
+            let header = self.resolve(header);
+            let location = format!("header.{}", name);
+            match response.headers().get(name) {
+                Some(value) => if header["schema"].is_object() {
+                    self.check_param(value, &header["schema"], &location, violations);
+                },
+                None => if header["required"].as_bool().unwrap_or(false) {
+                    violations.push(Violation::new(location, "Required header is missing"));
+                }
+            }
+        }
+
+        if documented["content"].is_object() && response.body().is_some() {
+            self.check_content(response, &documented["content"], violations);
+        }
+    }
+
+    fn check_content(&self, message: &HttpMessage, content: &JsonValue, violations: &mut Vec<Violation>) {
+        if !content.is_object() || content.is_empty() {
+            return;
+        }
+        let content_type = message.headers().get(CONTENT_TYPE).unwrap_or("");
+        let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+
+        let media = content.entries()
+            .find(|(key, _)| key.eq_ignore_ascii_case(&mime))
+            .or_else(|| content.entries().find(|(key, _)| {
+                key.strip_suffix("/*").is_some_and(|main| mime.starts_with(&format!("{}/", main.to_lowercase())))
+            }))
+            .or_else(|| content.entries().find(|(key, _)| *key == "*/*"));
+
+        let (_, media) = match media {
+            Some(media) => media,
+            None => {
+                violations.push(Violation::new("header.Content-Type", format!("Content type {} is not documented", content_type)));
+                return;
+            }
+        };
+
+        if media["schema"].is_object() && (mime == "application/json" || mime.ends_with("+json")) {
+            match message.json() {
+                Ok(body) => self.check_schema(&body, &media["schema"], "body", violations, 0),
+                Err(e) => violations.push(Violation::new("body", format!("Invalid JSON body: {}", e)))
+            }
+        }
+    }
+
+    /// Validates a param, header or cookie value, converted to the type required by its schema
+    fn check_param(&self, value: &str, schema: &JsonValue, location: &str, violations: &mut Vec<Violation>) {
+        let schema = self.resolve(schema);
+        let converted: JsonValue = match schema["type"].as_str() {
+            Some("integer") => match value.parse::<i64>() {
+                Ok(number) => number.into(),
+                Err(_) => {
+                    violations.push(Violation::new(location, format!("{} is not an integer", value)));
+                    return;
+                }
+            },
+            Some("number") => match value.parse::<f64>() {
+                Ok(number) => number.into(),
+                Err(_) => {
+                    violations.push(Violation::new(location, format!("{} is not a number", value)));
+                    return;
+                }
+            },
+            Some("boolean") => match value {
+                "true" => true.into(),
+                "false" => false.into(),
+                _ => {
+                    violations.push(Violation::new(location, format!("{} is not a boolean", value)));
+                    return;
+                }
+            },
+            Some("array") | Some("object") => return,
+            _ => value.into()
+        };
+        self.check_schema(&converted, schema, location, violations, 0);
+    }
+
+    fn check_schema(&self, value: &JsonValue, schema: &JsonValue, location: &str, violations: &mut Vec<Violation>, depth: usize) {
+        if depth > MAX_REF_DEPTH {
+            violations.push(Violation::new(location, "Schema is too deep"));
+            return;
+        }
+        let schema = self.resolve(schema);
+        if schema.is_boolean() {
+            if schema == false {
+                violations.push(Violation::new(location, "No value is allowed"));
+            }
+            return;
+        }
+
+        if value.is_null() && schema["nullable"].as_bool().unwrap_or(false) {
+            return;
+        }
+
+        let types: Vec<&str> = if schema["type"].is_array() {
+            schema["type"].members().filter_map(|t| t.as_str()).collect()
+        } else {
+            schema["type"].as_str().into_iter().collect()
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            violations.push(Violation::new(location, format!("Expected type {}", types.join(" or "))));
+            return;
+        }
+
+        if schema["enum"].is_array() && !schema["enum"].members().any(|option| option == value) {
+            violations.push(Violation::new(location, "Value is not one of the enumerated values"));
+        }
+        if schema.has_key("const") && schema["const"] != *value {
+            violations.push(Violation::new(location, "Value is not the constant value"));
+        }
+
+        if let Some(number) = value.as_f64() {
+            if let Some(minimum) = schema["minimum"].as_f64() {
+                let exclusive = schema["exclusiveMinimum"].as_bool().unwrap_or(false);
+                if number < minimum || (exclusive && number == minimum) {
+                    violations.push(Violation::new(location, format!("Value is less than the minimum {}", minimum)));
+                }
+            }
+            if let Some(maximum) = schema["maximum"].as_f64() {
+                let exclusive = schema["exclusiveMaximum"].as_bool().unwrap_or(false);
+                if number > maximum || (exclusive && number == maximum) {
+                    violations.push(Violation::new(location, format!("Value is greater than the maximum {}", maximum)));
+                }
+            }
+            if let Some(minimum) = schema["exclusiveMinimum"].as_f64() {
+                if number <= minimum {
+                    violations.push(Violation::new(location, format!("Value is not greater than {}", minimum)));
+                }
+            }
+            if let Some(maximum) = schema["exclusiveMaximum"].as_f64() {
+                if number >= maximum {
+                    violations.push(Violation::new(location, format!("Value is not less than {}", maximum)));
+                }
+            }
+        }
+
+        if let Some(text) = value.as_str() {
+            let length = text.chars().count();
+            if let Some(min) = schema["minLength"].as_usize() {
+                if length < min {
+                    violations.push(Violation::new(location, format!("Length is less than {}", min)));
+                }
+            }
+            if let Some(max) = schema["maxLength"].as_usize() {
+                if length > max {
+                    violations.push(Violation::new(location, format!("Length is greater than {}", max)));
+                }
+            }
+        }
+
+        if value.is_array() {
+            if let Some(min) = schema["minItems"].as_usize() {
+                if value.len() < min {
+                    violations.push(Violation::new(location, format!("Less than {} items", min)));
+                }
+            }
+            if let Some(max) = schema["maxItems"].as_usize() {
+                if value.len() > max {
+                    violations.push(Violation::new(location, format!("More than {} items", max)));
+                }
+            }
+            if schema.has_key("items") {
+                for (i, item) in value.members().enumerate() {
+                    self.check_schema(item, &schema["items"], &format!("{}[{}]", location, i), violations, depth + 1);
+                }
+            }
+        }
+
+        if value.is_object() {
+            for required in schema["required"].members().filter_map(|r| r.as_str()) {
+                if !value.has_key(required) {
+                    violations.push(Violation::new(format!("{}.{}", location, required), "Required property is missing"));
+                }
+            }
+            for (key, member) in value.entries() {
+                let member_location = format!("{}.{}", location, key);
+                if schema["properties"].has_key(key) {
+                    self.check_schema(member, &schema["properties"][key], &member_location, violations, depth + 1);
+                } else if schema["additionalProperties"] == false {
+                    violations.push(Violation::new(member_location, "Additional property is not allowed"));
+                } else if schema["additionalProperties"].is_object() {
+                    self.check_schema(member, &schema["additionalProperties"], &member_location, violations, depth + 1);
+                }
+            }
+        }
+
+        for sub_schema in schema["allOf"].members() {
+            self.check_schema(value, sub_schema, location, violations, depth + 1);
+        }
+        if schema["anyOf"].is_array() && !schema["anyOf"].members().any(|s| self.matches(value, s, depth + 1)) {
+            violations.push(Violation::new(location, "Value does not match any schema of anyOf"));
+        }
+        if schema["oneOf"].is_array() {
+            let count = schema["oneOf"].members().filter(|s| self.matches(value, s, depth + 1)).count();
+            if count != 1 {
+                violations.push(Violation::new(location, format!("Value matches {} schemas of oneOf", count)));
+            }
+        }
+    }
+
+    fn matches(&self, value: &JsonValue, schema: &JsonValue, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.check_schema(value, schema, "", &mut violations, depth);
+        violations.is_empty()
+    }
+
+    /// Follows local `$ref` references, like `#/components/schemas/User`
+    fn resolve<'a>(&'a self, value: &'a JsonValue) -> &'a JsonValue {
+        let mut current = value;
+        for _ in 0..MAX_REF_DEPTH {
+            let reference = match current["$ref"].as_str() {
+                Some(reference) => reference,
+                None => return current
+            };
+            let pointer = match reference.strip_prefix("#/") {
+                Some(pointer) => pointer,
+                None => return current
+            };
+            let mut target = &self.document;
+            for token in pointer.split('/') {
+                let token = token.replace("~1", "/").replace("~0", "~");
+                target = &target[token.as_str()];
+            }
+            current = target;
+        }
+        current
+    }
+}
+
+/// Finds the response object documented for a status code: exact code, range (`2XX`) or `default`
+fn find_status(responses: &JsonValue, status: HttpStatusCode) -> Option<&JsonValue> {
+    let code = status.to_string();
+    let range = format!("{}XX", status / 100);
+    responses.entries().find(|(key, _)| *key == code)
+        .or_else(|| responses.entries().find(|(key, _)| key.eq_ignore_ascii_case(&range)))
+        .or_else(|| responses.entries().find(|(key, _)| *key == "default"))
+        .map(|(_, value)| value)
+}
+
+fn has_type(value: &JsonValue, json_type: &str) -> bool {
+    match json_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true
+    }
+}
+
+fn to_result(violations: Vec<Violation>) -> Result<(), Vec<Violation>> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Request policies
+//!
+//! A [RequestPolicy] gathers the rules outgoing requests must follow, to enforce them in a single place:
+//!
+//! ```
+//! use wrequest::{HttpMethod, Request};
+//! use wrequest::policy::{RequestPolicy, Violation};
+//!
+//! let policy = RequestPolicy::new()
+//!     .with_allowed_schemes(&["https"])
+//!     .with_allowed_methods(&[HttpMethod::GET, HttpMethod::POST])
+//!     .with_required_header("traceparent")
+//!     .with_max_url_length(2048);
+//!
+//! let mut request = Request::get("https://service.com/users");
+//! request.insert_header("traceparent", "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01");
+//! assert!(request.validate(&policy).is_ok());
+//!
+//! let request = Request::trace("http://service.com/users");
+//! assert_eq!(request.validate(&policy).unwrap_err(), vec![
+//!     Violation::Scheme(Some("http".to_string())),
+//!     Violation::Method(HttpMethod::TRACE),
+//!     Violation::MissingHeader("traceparent".to_string())
+//! ]);
+//! ```
+
+use std::error;
+use std::fmt;
+use crate::{HttpMethod, Request};
+
+/// Rule broken by a request, see [Request::validate]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Violation {
+    /// The URL scheme is not allowed, `None` for relative URLs
+    Scheme(Option<String>),
+    /// The method is not allowed
+    Method(HttpMethod),
+    /// A required header is missing
+    MissingHeader(String),
+    /// A forbidden header is present
+    ForbiddenHeader(String),
+    /// A label of the host mixes scripts, with the host in Unicode
+    MixedScriptHost(String),
+    /// The URL, including the params, is longer than the maximum
+    UrlTooLong {
+        /// URL length, in bytes
+        length: usize,
+        /// Maximum URL length, in bytes
+        max: usize
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scheme(Some(scheme)) => write!(f, "Scheme {} is not allowed", scheme),
+            Self::Scheme(None) => write!(f, "Relative URLs are not allowed"),
+            Self::Method(method) => write!(f, "Method {} is not allowed", method),
+            Self::MissingHeader(name) => write!(f, "Required header {} is missing", name),
+            Self::ForbiddenHeader(name) => write!(f, "Header {} is forbidden", name),
+            Self::MixedScriptHost(host) => write!(f, "Host {} mixes scripts", host),
+            Self::UrlTooLong { length, max } => write!(f, "URL of {} bytes exceeds the maximum of {} bytes", length, max)
+        }
+    }
+}
+
+impl error::Error for Violation {}
+
+/// Rules of the requests. A new policy allows any request, every rule restricts it further.
+#[derive(Clone, Debug, Default)]
+pub struct RequestPolicy {
+    schemes: Option<Vec<String>>,
+    methods: Option<Vec<HttpMethod>>,
+    required_headers: Vec<String>,
+    forbidden_headers: Vec<String>,
+    single_script_hosts: bool,
+    max_url_length: Option<usize>
+}
+
+impl RequestPolicy {
+    /// Constructor of a policy that allows any request
+    pub fn new() -> RequestPolicy {
+        RequestPolicy::default()
+    }
+
+    /// Only allows URLs with one of the `schemes`, compared case-insensitively. Relative URLs are rejected.
+    pub fn with_allowed_schemes<S: AsRef<str>>(mut self, schemes: &[S]) -> Self {
+        self.schemes = Some(schemes.iter().map(|scheme| scheme.as_ref().to_ascii_lowercase()).collect());
+        self
+    }
+
+    /// Only allows the `methods`
+    pub fn with_allowed_methods(mut self, methods: &[HttpMethod]) -> Self {
+        self.methods = Some(methods.to_vec());
+        self
+    }
+
+    /// Requires a header, names are case-insensitive
+    pub fn with_required_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.required_headers.push(name.into());
+        self
+    }
+
+    /// Forbids a header, names are case-insensitive
+    pub fn with_forbidden_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.forbidden_headers.push(name.into());
+        self
+    }
+
+    /// Rejects hosts with labels that mix scripts, see [Request::has_mixed_script_host]
+    pub fn with_single_script_hosts(mut self) -> Self {
+        self.single_script_hosts = true;
+        self
+    }
+
+    /// Limits the length of the URL, including the params, in bytes
+    pub fn with_max_url_length(mut self, max: usize) -> Self {
+        self.max_url_length = Some(max);
+        self
+    }
+
+    /// Checks a request, collecting all the violations in rule order
+    fn check(&self, request: &Request) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if let Some(ref schemes) = self.schemes {
+            let scheme = request.url().split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase());
+            if !scheme.as_ref().is_some_and(|scheme| schemes.contains(scheme)) {
+                violations.push(Violation::Scheme(scheme));
+            }
+        }
+        if let Some(ref methods) = self.methods {
+            if !methods.contains(&request.method()) {
+                violations.push(Violation::Method(request.method()));
+            }
+        }
+        for name in self.required_headers.iter() {
+            if !request.headers().contains_key(name) {
+                violations.push(Violation::MissingHeader(name.clone()));
+            }
+        }
+        for name in self.forbidden_headers.iter() {
+            if request.headers().contains_key(name) {
+                violations.push(Violation::ForbiddenHeader(name.clone()));
+            }
+        }
+        if self.single_script_hosts && request.has_mixed_script_host() {
+            violations.push(Violation::MixedScriptHost(request.unicode_host().unwrap_or_default()));
+        }
+        if let Some(max) = self.max_url_length {
+            let length = request.full_url().len();
+            if length > max {
+                violations.push(Violation::UrlTooLong { length, max });
+            }
+        }
+        violations
+    }
+}
+
+impl Request {
+    /// Validates the request against a `policy`, returning all the rules it breaks
+    pub fn validate(&self, policy: &RequestPolicy) -> Result<(), Vec<Violation>> {
+        let violations = policy.check(self);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `Prefer` and `Preference-Applied` headers (RFC 7240)
+//!
+//! ```
+//! use std::time::Duration;
+//! use wrequest::{Request, Response, HTTP_201_CREATED};
+//! use wrequest::prefer::{Prefer, Return};
+//!
+//! let mut request = Request::post("https://service.com/users");
+//! request.set_prefer(&Prefer::new().with_return(Return::Minimal).with_wait(Duration::from_secs(10)));
+//! assert_eq!(request.headers().get("Prefer"), Some("return=minimal, wait=10"));
+//!
+//! let preferences = request.prefer().unwrap();
+//! let mut response = Response::new(HTTP_201_CREATED);
+//! response.set_preference_applied(&Prefer::new().with_return(preferences.return_preference.unwrap()));
+//! assert_eq!(response.preference_applied().unwrap().return_preference, Some(Return::Minimal));
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+use crate::{Request, Response};
+
+/// `Prefer` header name
+pub const PREFER: &str = "Prefer";
+/// `Preference-Applied` header name
+pub const PREFERENCE_APPLIED: &str = "Preference-Applied";
+
+/// Content of the response to a state-changing request
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Return {
+    /// Only a minimal response (`return=minimal`)
+    Minimal,
+    /// The current representation of the target resource (`return=representation`)
+    Representation
+}
+
+/// Processing of invalid or unsupported request content
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Handling {
+    /// Fail the request (`handling=strict`)
+    Strict,
+    /// Process the request as far as possible (`handling=lenient`)
+    Lenient
+}
+
+/// Set of preferences of a `Prefer` or `Preference-Applied` header
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Prefer {
+    /// `return` preference
+    pub return_preference: Option<Return>,
+    /// `wait` preference, time the client waits for a synchronous response
+    pub wait: Option<Duration>,
+    /// `respond-async` preference
+    pub respond_async: bool,
+    /// `handling` preference
+    pub handling: Option<Handling>,
+    /// Other preferences, as lowercase names and optional values
+    pub others: Vec<(String, Option<String>)>
+}
+
+impl Prefer {
+    /// Constructor of an empty set of preferences
+    pub fn new() -> Prefer {
+        Prefer::default()
+    }
+
+    /// Sets the `return` preference
+    pub fn with_return(mut self, value: Return) -> Self {
+        self.return_preference = Some(value);
+        self
+    }
+
+    /// Sets the `wait` preference, in whole seconds
+    pub fn with_wait(mut self, wait: Duration) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    /// Sets the `respond-async` preference
+    pub fn with_respond_async(mut self) -> Self {
+        self.respond_async = true;
+        self
+    }
+
+    /// Sets the `handling` preference
+    pub fn with_handling(mut self, handling: Handling) -> Self {
+        self.handling = Some(handling);
+        self
+    }
+
+    /// Adds another preference with an optional value
+    pub fn with_other<S: Into<String>>(mut self, name: S, value: Option<&str>) -> Self {
+        self.others.push((name.into().to_ascii_lowercase(), value.map(String::from)));
+        self
+    }
+
+    /// Checks there are no preferences
+    pub fn is_empty(&self) -> bool {
+        *self == Prefer::default()
+    }
+
+    /// Parses a header value. Names are case-insensitive, preference parameters are ignored, and
+    /// unknown or invalid preferences are kept in `others`. The first occurrence of a preference wins.
+    pub fn parse(value: &str) -> Prefer {
+        let mut result = Prefer::new();
+        let mut seen: Vec<String> = Vec::new();
+        for item in value.split(',') {
+            let preference = item.split(';').next().unwrap_or("").trim();
+            if preference.is_empty() {
+                continue;
+            }
+            let (name, value) = match preference.split_once('=') {
+                Some((name, value)) => (name.trim().to_ascii_lowercase(), Some(value.trim().trim_matches('"'))),
+                None => (preference.to_ascii_lowercase(), None)
+            };
+            if seen.contains(&name) {
+                continue;
+            }
+            seen.push(name.clone());
+
+            match (name.as_str(), value.map(str::to_ascii_lowercase).as_deref()) {
+                ("return", Some("minimal")) => result.return_preference = Some(Return::Minimal),
+                ("return", Some("representation")) => result.return_preference = Some(Return::Representation),
+                ("handling", Some("strict")) => result.handling = Some(Handling::Strict),
+                ("handling", Some("lenient")) => result.handling = Some(Handling::Lenient),
+                ("respond-async", None) => result.respond_async = true,
+                ("wait", Some(seconds)) if seconds.parse::<u64>().is_ok() =>
+                    result.wait = seconds.parse().ok().map(Duration::from_secs),
+                _ => result.others.push((name, value.map(String::from)))
+            }
+        }
+        result
+    }
+}
+
+impl fmt::Display for Prefer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut items: Vec<String> = Vec::new();
+        match self.return_preference {
+            Some(Return::Minimal) => items.push("return=minimal".to_string()),
+            Some(Return::Representation) => items.push("return=representation".to_string()),
+            None => {}
+        }
+        if let Some(wait) = self.wait {
+            items.push(format!("wait={}", wait.as_secs()));
+        }
+        if self.respond_async {
+            items.push("respond-async".to_string());
+        }
+        match self.handling {
+            Some(Handling::Strict) => items.push("handling=strict".to_string()),
+            Some(Handling::Lenient) => items.push("handling=lenient".to_string()),
+            None => {}
+        }
+        for (name, value) in self.others.iter() {
+            match value {
+                Some(value) => items.push(format!("{}={}", name, value)),
+                None => items.push(name.clone())
+            }
+        }
+        write!(f, "{}", items.join(", "))
+    }
+}
+
+impl Request {
+    /// Sets the `Prefer` header
+    pub fn set_prefer(&mut self, prefer: &Prefer) -> &mut Self {
+        self.insert_header(PREFER, prefer.to_string());
+        self
+    }
+
+    /// Gets the preferences of the `Prefer` header, if any
+    pub fn prefer(&self) -> Option<Prefer> {
+        self.headers().get(PREFER).map(Prefer::parse)
+    }
+}
+
+impl Response {
+    /// Sets the `Preference-Applied` header
+    pub fn set_preference_applied(&mut self, applied: &Prefer) -> &mut Self {
+        self.insert_header(PREFERENCE_APPLIED, applied.to_string());
+        self
+    }
+
+    /// Gets the preferences of the `Preference-Applied` header, if any
+    pub fn preference_applied(&self) -> Option<Prefer> {
+        self.headers().get(PREFERENCE_APPLIED).map(Prefer::parse)
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{Error, ErrorKind};
+use std::collections::BTreeMap;
+use json::JsonValue;
+use crate::{Response, HttpStatusCode, APPLICATION_PROBLEM_JSON, CONTENT_TYPE};
+
+/// Default problem type, when the problem has no semantics beyond the status code
+pub const ABOUT_BLANK: &str = "about:blank";
+
+/// Problem Details for HTTP APIs
+///
+/// ```
+/// use wrequest::{Problem, Response, HTTP_404_NOT_FOUND};
+///
+/// let mut problem = Problem::new(HTTP_404_NOT_FOUND);
+/// problem.title = Some("User not found".to_string());
+/// problem.extensions.insert("user_id".to_string(), "1234".into());
+///
+/// let response = Response::problem(problem).unwrap();
+///
+/// assert_eq!(response.status_code(), HTTP_404_NOT_FOUND);
+/// assert_eq!(response.headers().get("Content-Type").unwrap(), "application/problem+json");
+///
+/// let parsed = response.as_problem().unwrap();
+/// assert_eq!(parsed.title.as_deref(), Some("User not found"));
+/// assert_eq!(parsed.extensions["user_id"], "1234");
+/// ```
+///
+/// For more information see [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457).
+#[derive(Clone, PartialEq, Debug)]
+pub struct Problem {
+    /// URI reference that identifies the problem type (`type` member)
+    pub problem_type: String,
+    /// Short, human-readable summary of the problem type
+    pub title: Option<String>,
+    /// HTTP status code generated by the origin server
+    pub status: Option<HttpStatusCode>,
+    /// Human-readable explanation specific to this occurrence of the problem
+    pub detail: Option<String>,
+    /// URI reference that identifies the specific occurrence of the problem
+    pub instance: Option<String>,
+    /// Extension members, encoded in the order of their names
+    pub extensions: BTreeMap<String, JsonValue>
+}
+
+impl Problem {
+    /// Constructor of an `about:blank` problem with a `status` code
+    pub fn new(status: HttpStatusCode) -> Problem {
+        Problem {
+            problem_type: ABOUT_BLANK.to_string(),
+            title: None,
+            status: Some(status),
+            detail: None,
+            instance: None,
+            extensions: BTreeMap::new()
+        }
+    }
+
+    /// Encodes the problem as a JSON object
+    pub fn to_json(&self) -> JsonValue {
+        let mut result = JsonValue::new_object();
+        for (key, value) in self.extensions.iter() {
+            result[key.as_str()] = value.clone();
+        }
+        result["type"] = self.problem_type.as_str().into();
+        if let Some(ref title) = self.title {
+            result["title"] = title.as_str().into();
+        }
+        if let Some(status) = self.status {
+            result["status"] = status.into();
+        }
+        if let Some(ref detail) = self.detail {
+            result["detail"] = detail.as_str().into();
+        }
+        if let Some(ref instance) = self.instance {
+            result["instance"] = instance.as_str().into();
+        }
+        result
+    }
+
+    /// Decodes a problem from a JSON object. Standard members with an invalid type are ignored, as RFC 9457 mandates.
+    pub fn from_json(value: &JsonValue) -> Result<Problem, Error> {
+        if !value.is_object() {
+            return Err(Error::new(ErrorKind::InvalidData, "Problem details must be a JSON object"));
+        }
+
+        let mut result = Problem {
+            problem_type: ABOUT_BLANK.to_string(),
+            title: None,
+            status: None,
+            detail: None,
+            instance: None,
+            extensions: BTreeMap::new()
+        };
+
+        for (key, member) in value.entries() {
+            match key {
+                "type" => if let Some(s) = member.as_str() {
+                    result.problem_type = s.to_string();
+                },
+                "title" => result.title = member.as_str().map(String::from),
+                "status" => result.status = member.as_u16(),
+                "detail" => result.detail = member.as_str().map(String::from),
+                "instance" => result.instance = member.as_str().map(String::from),
+                _ => {
+                    result.extensions.insert(key.to_string(), member.clone());
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl Response {
+    /// Creates a response with a Problem Details body. The response status is the problem `status`,
+    /// or `500 Internal Server Error` if it is not set. Returns a [BodyTooLarge](crate::BodyTooLarge) error if
+    /// the encoded problem exceeds the maximum body size.
+    pub fn problem(problem: Problem) -> Result<Response, Error> {
+        let mut response = Response::new(problem.status.unwrap_or(crate::HTTP_500_INTERNAL_SERVE_ERROR));
+        let encoded = problem.to_json().dump();
+        response.set_body(encoded.into_bytes())?;
+        response.insert_header(CONTENT_TYPE, APPLICATION_PROBLEM_JSON);
+        Ok(response)
+    }
+
+    /// Gets the Problem Details of the response, if its `Content-Type` is `application/problem+json` and
+    /// its body can be decoded.
+    pub fn as_problem(&self) -> Option<Problem> {
+        let content_type = self.headers().get(CONTENT_TYPE)?;
+        let mime = content_type.split(';').next().unwrap_or("").trim();
+        if !mime.eq_ignore_ascii_case(APPLICATION_PROBLEM_JSON) {
+            return None;
+        }
+        self.json().ok().and_then(|value| Problem::from_json(&value).ok())
+    }
+}
@@ -0,0 +1,144 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Byte range requests (RFC 9110, section 14)
+//!
+//! ```
+//! use wrequest::{Request, HTTP_416_RANGE_NOT_SATISFIABLE};
+//!
+//! let mut request = Request::get("https://service.com/video.mp4");
+//! request.insert_header("Range", "bytes=0-99, 50-149, -100");
+//!
+//! // Overlapping ranges are coalesced and the suffix range is clamped to the resource length
+//! assert_eq!(request.satisfiable_ranges(120).ok().unwrap(), Some(vec![0..120]));
+//!
+//! request.insert_header("Range", "bytes=500-");
+//! let response = request.satisfiable_ranges(120).err().unwrap();
+//! assert_eq!(response.status_code(), HTTP_416_RANGE_NOT_SATISFIABLE);
+//! assert_eq!(response.headers().get("Content-Range"), Some("bytes */120"));
+//! ```
+
+use std::ops::Range;
+use crate::{Request, Response, HTTP_416_RANGE_NOT_SATISFIABLE};
+
+/// `Range` header name
+pub const RANGE: &str = "Range";
+/// `Content-Range` header name
+pub const CONTENT_RANGE: &str = "Content-Range";
+/// `Accept-Ranges` header name
+pub const ACCEPT_RANGES: &str = "Accept-Ranges";
+
+/// Byte range of a `Range` header, with inclusive positions
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ByteRange {
+    /// `first-last`
+    FromTo(u64, u64),
+    /// `first-`, up to the end
+    From(u64),
+    /// `-length`, the last bytes
+    Suffix(u64)
+}
+
+impl ByteRange {
+    /// Parses a `Range` header value in `bytes` units. Returns `None` if it is malformed or uses other units,
+    /// in which case the header must be ignored.
+    pub fn parse_header(value: &str) -> Option<Vec<ByteRange>> {
+        let (unit, ranges) = value.split_once('=')?;
+        if !unit.trim().eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+        let mut result = Vec::new();
+        for range in ranges.split(',').map(str::trim).filter(|range| !range.is_empty()) {
+            let (first, last) = range.split_once('-')?;
+            let number = |value: &str| -> Option<u64> {
+                value.bytes().all(|c| c.is_ascii_digit()).then(|| value.parse().ok()).flatten()
+            };
+            result.push(match (first.trim(), last.trim()) {
+                ("", "") => return None,
+                ("", suffix) => ByteRange::Suffix(number(suffix)?),
+                (first, "") => ByteRange::From(number(first)?),
+                (first, last) => {
+                    let (first, last) = (number(first)?, number(last)?);
+                    if last < first {
+                        return None;
+                    }
+                    ByteRange::FromTo(first, last)
+                }
+            });
+        }
+        (!result.is_empty()).then_some(result)
+    }
+
+    /// Resolves the range against a resource of `length` bytes, as a half-open range clamped to the resource.
+    /// Returns `None` if the range is not satisfiable: it starts after the end or it is an empty suffix.
+    pub fn resolve(&self, length: u64) -> Option<Range<u64>> {
+        let range = match *self {
+            ByteRange::FromTo(first, last) => first..last.saturating_add(1).min(length),
+            ByteRange::From(first) => first..length,
+            ByteRange::Suffix(suffix) => length.saturating_sub(suffix)..length
+        };
+        (range.start < range.end).then_some(range)
+    }
+}
+
+/// Formats a `Content-Range` header value for a half-open `range` of a resource of `length` bytes, like
+/// `bytes 0-99/1000`
+pub fn content_range(range: &Range<u64>, length: u64) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end.saturating_sub(1), length)
+}
+
+impl Request {
+    /// Gets the byte ranges of the `Range` header, if any and well-formed, see [ByteRange::parse_header]
+    pub fn ranges(&self) -> Option<Vec<ByteRange>> {
+        self.headers().get(RANGE).and_then(ByteRange::parse_header)
+    }
+
+    /// Validates the `Range` header against a resource of `length` bytes, server side. Returns:
+    ///
+    /// * `Ok(None)` if there is no `Range` header or it is malformed, so the whole resource is served
+    /// * `Ok(Some(ranges))` with the half-open ranges to serve in a `206 Partial Content` response, clamped to the
+    ///   resource, sorted and with overlapping or adjacent ranges coalesced
+    /// * `Err(response)` with a [416 response](Response::range_not_satisfiable) if no range is satisfiable
+    pub fn satisfiable_ranges(&self, length: u64) -> Result<Option<Vec<Range<u64>>>, Box<Response>> {
+        let ranges = match self.ranges() {
+            Some(ranges) => ranges,
+            None => return Ok(None)
+        };
+        let mut resolved: Vec<Range<u64>> = ranges.iter().filter_map(|range| range.resolve(length)).collect();
+        if resolved.is_empty() {
+            return Err(Box::new(Response::range_not_satisfiable(length)));
+        }
+        resolved.sort_by_key(|range| range.start);
+        let mut result: Vec<Range<u64>> = Vec::with_capacity(resolved.len());
+        for range in resolved {
+            match result.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => result.push(range)
+            }
+        }
+        Ok(Some(result))
+    }
+}
+
+impl Response {
+    /// Creates a `416 Range Not Satisfiable` response for a resource of `length` bytes, with a
+    /// `Content-Range: bytes */length` header
+    pub fn range_not_satisfiable(length: u64) -> Response {
+        let mut response = Response::new(HTTP_416_RANGE_NOT_SATISFIABLE);
+        response.insert_header(CONTENT_RANGE, format!("bytes */{}", length));
+        response
+    }
+}
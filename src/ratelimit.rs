@@ -0,0 +1,149 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Rate limit headers
+//!
+//! Supports the IETF `RateLimit` and `RateLimit-Policy` headers (both the structured field syntax,
+//! `"default";r=50;t=30`, and the earlier `limit=100, remaining=50, reset=30` one), the
+//! `RateLimit-Limit/Remaining/Reset` headers and the common `X-RateLimit-Limit/Remaining/Reset` variants.
+//!
+//! ```
+//! use std::time::Duration;
+//! use wrequest::{Response, HTTP_200_OK};
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.insert_header("RateLimit", "\"default\";r=50;t=30")
+//!         .insert_header("RateLimit-Policy", "\"default\";q=100;w=60");
+//!
+//! let rate_limit = response.rate_limit().unwrap();
+//! assert_eq!(rate_limit.remaining, 50);
+//! assert_eq!(rate_limit.limit, Some(100));
+//! assert_eq!(rate_limit.reset, Some(Duration::from_secs(30)));
+//! assert_eq!(rate_limit.window, Some(Duration::from_secs(60)));
+//! ```
+
+use std::time::{Duration, SystemTime};
+use chrono::DateTime;
+use crate::Response;
+
+/// `RateLimit` header name
+pub const RATE_LIMIT: &str = "RateLimit";
+/// `RateLimit-Policy` header name
+pub const RATE_LIMIT_POLICY: &str = "RateLimit-Policy";
+
+/// Reset values above this are taken as Unix timestamps instead of delta seconds
+const TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+
+/// Quota state reported by a service
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RateLimit {
+    /// Name of the quota policy, if any
+    pub policy: Option<String>,
+    /// Requests allowed in the time window, if known
+    pub limit: Option<u64>,
+    /// Requests left in the current time window
+    pub remaining: u64,
+    /// Time until the quota is restored, if known
+    pub reset: Option<Duration>,
+    /// Length of the time window, if known
+    pub window: Option<Duration>
+}
+
+impl Response {
+    /// Parses the rate limit headers of the response.
+    ///
+    /// The `RateLimit` header takes precedence over the `RateLimit-*` headers, and these over the
+    /// `X-RateLimit-*` ones. Resets given as Unix timestamps are converted to the time left from the
+    /// response `Date`, or from now if it has no date. Returns `None` if the remaining quota is not found.
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        let headers = self.headers();
+        let policy = headers.get(RATE_LIMIT_POLICY).map(parse_fields).unwrap_or_default();
+
+        let (name, remaining, limit, reset) = if let Some(value) = headers.get(RATE_LIMIT) {
+            let fields = parse_fields(value);
+            (fields.name.clone(), fields.get(&["r", "remaining"])?, fields.get(&["limit"]), fields.get(&["t", "reset"]))
+        } else if let Some(remaining) = parse_number(headers.get("RateLimit-Remaining")) {
+            (None, remaining, parse_number(headers.get("RateLimit-Limit")), parse_number(headers.get("RateLimit-Reset")))
+        } else {
+            (None, parse_number(headers.get("X-RateLimit-Remaining"))?, parse_number(headers.get("X-RateLimit-Limit")),
+             parse_number(headers.get("X-RateLimit-Reset")))
+        };
+
+        Some(RateLimit {
+            policy: name.or(policy.name.clone()),
+            limit: limit.or(policy.get(&["q"])).or(policy.quota),
+            remaining,
+            reset: reset.map(|reset| self.reset_duration(reset)),
+            window: policy.get(&["w"]).map(Duration::from_secs)
+        })
+    }
+
+    /// Converts a reset value, in delta seconds or as a Unix timestamp, to the time left
+    fn reset_duration(&self, reset: u64) -> Duration {
+        if reset < TIMESTAMP_THRESHOLD {
+            return Duration::from_secs(reset);
+        }
+        let now = self.headers().get("Date")
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| date.timestamp().max(0) as u64)
+            .unwrap_or_else(|| SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs());
+        Duration::from_secs(reset.saturating_sub(now))
+    }
+}
+
+/// Members of a rate limit header value
+#[derive(Default)]
+struct Fields {
+    /// Quoted policy name
+    name: Option<String>,
+    /// Bare integer quota (earlier `RateLimit-Policy` syntax)
+    quota: Option<u64>,
+    /// `key=value` parameters, in order
+    params: Vec<(String, String)>
+}
+
+impl Fields {
+    /// Gets the first numeric parameter with any of the `keys`
+    fn get(&self, keys: &[&str]) -> Option<u64> {
+        self.params.iter()
+            .find(|(key, _)| keys.iter().any(|k| key.eq_ignore_ascii_case(k)))
+            .and_then(|(_, value)| value.parse().ok())
+    }
+}
+
+/// Parses both `"name";k=v;k=v` items and `k=v, k=v` lists. Only the first policy of a list is used.
+fn parse_fields(value: &str) -> Fields {
+    let mut fields = Fields::default();
+    for (i, item) in value.split(',').enumerate() {
+        for (j, member) in item.split(';').map(str::trim).enumerate() {
+            match member.split_once('=') {
+                Some((key, value)) => fields.params.push((key.trim().to_string(), value.trim().trim_matches('"').to_string())),
+                None if j == 0 && member.starts_with('"') => {
+                    if i > 0 {
+                        return fields;
+                    }
+                    fields.name = Some(member.trim_matches('"').to_string());
+                },
+                None if j == 0 && i == 0 => fields.quota = member.parse().ok(),
+                None => {}
+            }
+        }
+    }
+    fields
+}
+
+fn parse_number(value: Option<&str>) -> Option<u64> {
+    value.and_then(|value| value.trim().parse().ok())
+}
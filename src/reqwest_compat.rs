@@ -0,0 +1,113 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions to and from reqwest messages (feature `reqwest`)
+
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+use bytes::Bytes;
+use reqwest::header;
+use wcookie::SetCookie;
+use crate::{BodyTooLarge, HttpVersion, MessageBody, Request, Response};
+
+impl Request {
+    /// Creates a reqwest request builder for this request with `client`.
+    ///
+    /// The URL includes the request params and the cookies are sent in a `Cookie` header.
+    /// Invalid URLs or headers are reported by reqwest when the request is built or sent.
+    pub fn into_reqwest(self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        let method = reqwest::Method::from_bytes(self.method.to_string().as_bytes())
+            .expect("HTTP methods are valid tokens");
+        let mut builder = client.request(method, self.full_url());
+        for (name, value) in self.headers().iter() {
+            builder = builder.header(name, value);
+        }
+        if let Some(cookies) = self.cookie_header() {
+            builder = builder.header(header::COOKIE, cookies);
+        }
+        match self.base.body {
+            MessageBody::Single(data) => builder = builder.body(data),
+            MessageBody::Shared(data) => builder = builder.body(Bytes::from_owner(data)),
+            _ => {}
+        }
+        builder
+    }
+}
+
+impl Response {
+    /// Collects a reqwest response into a `Response`, reading its whole body.
+    ///
+    /// `Set-Cookie` headers are parsed into the response cookies (invalid ones are ignored),
+    /// `WWW-Authenticate` and `Proxy-Authenticate` headers into the authorization guides and repeated
+    /// headers are joined with commas.
+    pub async fn from_reqwest(mut response: reqwest::Response) -> Result<Response, Error> {
+        let mut result = Response::new(response.status().as_u16());
+        result.set_version(match response.version() {
+            reqwest::Version::HTTP_09 | reqwest::Version::HTTP_10 => HttpVersion::Http10,
+            reqwest::Version::HTTP_2 => HttpVersion::Http2,
+            reqwest::Version::HTTP_3 => HttpVersion::Http3,
+            _ => HttpVersion::Http11
+        });
+        let headers = response.headers();
+
+        for name in headers.keys() {
+            let values = headers.get_all(name).iter()
+                .map(|v| v.to_str().map_err(|e| Error::new(ErrorKind::InvalidData, e)));
+            if name == header::SET_COOKIE {
+                for value in values {
+                    if let Ok(cookie) = SetCookie::from_str(value?) {
+                        result.insert_cookie(cookie);
+                    }
+                }
+            } else if name == header::WWW_AUTHENTICATE {
+                for value in values {
+                    result.insert_auth_headers(value?);
+                }
+            } else if name == header::PROXY_AUTHENTICATE {
+                for value in values {
+                    result.insert_proxy_auth_header(value?);
+                }
+            } else {
+                let values: Result<Vec<&str>, Error> = values.collect();
+                result.insert_header(name.as_str().to_string(), values?.join(", "));
+            }
+        }
+
+        let limit = result.max_body_size();
+        let declared = response.content_length().and_then(|size| usize::try_from(size).ok());
+        if let Some(size) = declared.filter(|size| *size > limit) {
+            return Err(BodyTooLarge::error(limit, Some(size)));
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(Error::other)? {
+            if data.len() + chunk.len() > limit {
+                return Err(BodyTooLarge::error(limit, None));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        if !data.is_empty() {
+            result.set_body(data)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Asynchronous transport, error status codes are returned as responses (features `reqwest` and `async`)
+#[cfg(feature = "async")]
+impl crate::transport::AsyncTransport for reqwest::Client {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        let response = request.into_reqwest(self).send().await.map_err(Error::other)?;
+        Response::from_reqwest(response).await
+    }
+}
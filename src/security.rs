@@ -0,0 +1,143 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Security response headers
+//!
+//! ```
+//! use wrequest::{Response, HTTP_200_OK};
+//! use wrequest::security::SecurityPreset;
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.apply_security_headers(SecurityPreset::default().with_frame_options(Some("SAMEORIGIN")));
+//!
+//! assert_eq!(response.headers().get("X-Content-Type-Options"), Some("nosniff"));
+//! assert_eq!(response.headers().get("X-Frame-Options"), Some("SAMEORIGIN"));
+//! ```
+
+use crate::Response;
+use crate::csp::CONTENT_SECURITY_POLICY;
+
+/// `Strict-Transport-Security` header name
+pub const STRICT_TRANSPORT_SECURITY: &str = "Strict-Transport-Security";
+/// `X-Content-Type-Options` header name
+pub const X_CONTENT_TYPE_OPTIONS: &str = "X-Content-Type-Options";
+/// `X-Frame-Options` header name
+pub const X_FRAME_OPTIONS: &str = "X-Frame-Options";
+/// `Referrer-Policy` header name
+pub const REFERRER_POLICY: &str = "Referrer-Policy";
+/// `Permissions-Policy` header name
+pub const PERMISSIONS_POLICY: &str = "Permissions-Policy";
+
+/// Set of security headers values, `None` values are not set
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SecurityPreset {
+    /// `Strict-Transport-Security` value
+    pub strict_transport_security: Option<String>,
+    /// `X-Content-Type-Options` value
+    pub content_type_options: Option<String>,
+    /// `X-Frame-Options` value
+    pub frame_options: Option<String>,
+    /// `Referrer-Policy` value
+    pub referrer_policy: Option<String>,
+    /// `Permissions-Policy` value
+    pub permissions_policy: Option<String>,
+    /// `Content-Security-Policy` value, see [crate::csp::Csp]
+    pub content_security_policy: Option<String>
+}
+
+impl Default for SecurityPreset {
+    /// Two years of HSTS including subdomains, `nosniff`, no framing, `strict-origin-when-cross-origin`
+    /// referrers and no access to camera, microphone, geolocation or payment APIs. There is no default
+    /// content security policy, as it depends on the resources of each site.
+    fn default() -> Self {
+        SecurityPreset {
+            strict_transport_security: Some("max-age=63072000; includeSubDomains".to_string()),
+            content_type_options: Some("nosniff".to_string()),
+            frame_options: Some("DENY".to_string()),
+            referrer_policy: Some("strict-origin-when-cross-origin".to_string()),
+            permissions_policy: Some("camera=(), microphone=(), geolocation=(), payment=()".to_string()),
+            content_security_policy: None
+        }
+    }
+}
+
+impl SecurityPreset {
+    /// Constructor with the default values
+    pub fn new() -> SecurityPreset {
+        SecurityPreset::default()
+    }
+
+    /// Overrides the `Strict-Transport-Security` value, `None` disables the header
+    pub fn with_strict_transport_security(mut self, value: Option<&str>) -> Self {
+        self.strict_transport_security = value.map(String::from);
+        self
+    }
+
+    /// Overrides the `X-Content-Type-Options` value, `None` disables the header
+    pub fn with_content_type_options(mut self, value: Option<&str>) -> Self {
+        self.content_type_options = value.map(String::from);
+        self
+    }
+
+    /// Overrides the `X-Frame-Options` value, `None` disables the header
+    pub fn with_frame_options(mut self, value: Option<&str>) -> Self {
+        self.frame_options = value.map(String::from);
+        self
+    }
+
+    /// Overrides the `Referrer-Policy` value, `None` disables the header
+    pub fn with_referrer_policy(mut self, value: Option<&str>) -> Self {
+        self.referrer_policy = value.map(String::from);
+        self
+    }
+
+    /// Overrides the `Permissions-Policy` value, `None` disables the header
+    pub fn with_permissions_policy(mut self, value: Option<&str>) -> Self {
+        self.permissions_policy = value.map(String::from);
+        self
+    }
+
+    /// Overrides the `Content-Security-Policy` value, `None` disables the header
+    pub fn with_content_security_policy(mut self, value: Option<&str>) -> Self {
+        self.content_security_policy = value.map(String::from);
+        self
+    }
+
+    /// Header names and values of the preset
+    pub fn headers(&self) -> Vec<(&'static str, &str)> {
+        [
+            (STRICT_TRANSPORT_SECURITY, &self.strict_transport_security),
+            (X_CONTENT_TYPE_OPTIONS, &self.content_type_options),
+            (X_FRAME_OPTIONS, &self.frame_options),
+            (REFERRER_POLICY, &self.referrer_policy),
+            (PERMISSIONS_POLICY, &self.permissions_policy),
+            (CONTENT_SECURITY_POLICY, &self.content_security_policy)
+        ].into_iter()
+            .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+            .collect()
+    }
+}
+
+impl Response {
+    /// Sets the security headers of `preset`. Headers already in the response are kept.
+    pub fn apply_security_headers(&mut self, preset: SecurityPreset) -> &mut Self {
+        for (name, value) in preset.headers() {
+            if !self.headers().contains_key(name) {
+                self.insert_header(name, value.to_string());
+            }
+        }
+        self
+    }
+}
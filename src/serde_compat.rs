@@ -0,0 +1,322 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Serde-based conversions (feature `serde`)
+//!
+//! Besides query params from serializable values, messages, header maps and key-value maps implement
+//! `Serialize` and `Deserialize` to be stored in any serde format, like JSON or CBOR. This is a structural
+//! representation for persistence, not the wire format: the method, URL, version, headers, params and
+//! cookies are kept as they are and bodies are encoded in base64. Message extensions are not serialized.
+//!
+//! ```json
+//! {"schema_version":1,"method":"POST","url":"https://service.com/users","version":"HTTP/1.1",
+//!  "headers":{"Content-Type":"text/plain"},"params":{"notify":"true"},"cookies":{},"body":"Sm9obg=="}
+//! ```
+//!
+//! Serialized messages carry the [SCHEMA_VERSION] of their layout, so that documents recorded with older
+//! releases can be told apart when the layout evolves. Documents without a `schema_version` field have the
+//! first, unversioned layout (version 0), that only lacks that field, so they are loaded as they are. Documents
+//! of versions newer than the crate are refused.
+
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::str::FromStr;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, MapAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeStruct};
+use wcookie::SetCookie;
+use crate::{Exchange, HeaderMap, HttpMethod, HttpVersion, HttpStatusCode, KeyValueMap, Request, Response, set_cookie_header};
+
+impl Request {
+    /// Serializes `value` into request params, as a form-urlencoded query string. Structs, maps and
+    /// sequences of pairs are supported; `None` fields are skipped. Params already in the request with the
+    /// same keys are replaced and the rest are kept.
+    ///
+    /// Values that cannot be serialized as params, such as nested structs, are reported with an
+    /// `ErrorKind::InvalidInput` error.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Search {
+    ///     query: String,
+    ///     page: u32,
+    ///     lang: Option<String>
+    /// }
+    ///
+    /// let mut request = Request::get("https://service.com/search");
+    /// request.insert_param("page", "1")
+    ///        .query_from(&Search { query: "two words".to_string(), page: 2, lang: None }).unwrap();
+    ///
+    /// assert_eq!(request.params().get("query"), Some("two words"));
+    /// assert_eq!(request.params().get("page"), Some("2"));
+    /// assert!(request.params().get("lang").is_none());
+    /// ```
+    pub fn query_from<T>(&mut self, value: &T) -> Result<&mut Self, Error>
+    where T: Serialize + ?Sized {
+        let encoded = serde_urlencoded::to_string(value).map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        let pairs: Vec<(String, String)> = serde_urlencoded::from_str(&encoded)
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        for (key, value) in pairs {
+            self.insert_param(key, value);
+        }
+        Ok(self)
+    }
+}
+
+impl Serialize for HeaderMap {
+    /// Serializes the headers as a map, in insertion order
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for HeaderMap {
+    /// Deserializes a map of headers, invalid header names or values are rejected
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HeaderMapVisitor;
+
+        impl<'de> Visitor<'de> for HeaderMapVisitor {
+            type Value = HeaderMap;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of header names and values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<HeaderMap, A::Error> {
+                let mut headers = HeaderMap::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((key, value)) = access.next_entry::<String, String>()? {
+                    headers.try_insert(key, value).map_err(de::Error::custom)?;
+                }
+                Ok(headers)
+            }
+        }
+
+        deserializer.deserialize_map(HeaderMapVisitor)
+    }
+}
+
+impl Serialize for KeyValueMap {
+    /// Serializes the entries as a map, in insertion order
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyValueMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyValueMapVisitor;
+
+        impl<'de> Visitor<'de> for KeyValueMapVisitor {
+            type Value = KeyValueMap;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map of string keys and values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<KeyValueMap, A::Error> {
+                let mut map = KeyValueMap::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((key, value)) = access.next_entry::<String, String>()? {
+                    map.insert(key, value);
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(KeyValueMapVisitor)
+    }
+}
+
+/// Version of the serialized layout of requests and responses, written in their `schema_version` field.
+///
+/// * `0`: first layout, without the `schema_version` field
+/// * `1`: `schema_version` field added
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Checks the schema version of a document, `0` if it has none, is not newer than [SCHEMA_VERSION]
+fn check_version<E: de::Error>(version: Option<u32>) -> Result<(), E> {
+    let version = version.unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        return Err(E::custom(format!("Unsupported schema version {}, the latest is {}", version, SCHEMA_VERSION)));
+    }
+    Ok(())
+}
+
+/// Decodes an optional base64 body
+fn decode_body<E: de::Error>(body: Option<String>) -> Result<Option<Vec<u8>>, E> {
+    body.map(|body| BASE64.decode(body).map_err(E::custom)).transpose()
+}
+
+impl Serialize for Request {
+    /// Serializes the request as a struct with `schema_version`, `method`, `url`, `version`, `headers`,
+    /// `params`, `cookies` and `body` fields. The body is encoded in base64, or `None` if the request has no
+    /// body.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Request", 8)?;
+        state.serialize_field("schema_version", &SCHEMA_VERSION)?;
+        state.serialize_field("method", &self.method.to_string())?;
+        state.serialize_field("url", &self.url)?;
+        state.serialize_field("version", &self.version().to_string())?;
+        state.serialize_field("headers", self.headers())?;
+        state.serialize_field("params", &self.params)?;
+        state.serialize_field("cookies", &self.cookies)?;
+        state.serialize_field("body", &self.body().map(|body| BASE64.encode(body)))?;
+        state.end()
+    }
+}
+
+/// Serialized request fields, all but the method and URL are optional
+#[derive(Deserialize)]
+#[serde(rename = "Request")]
+struct RequestFields {
+    #[serde(default)]
+    schema_version: Option<u32>,
+    method: String,
+    url: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    headers: HeaderMap,
+    #[serde(default)]
+    params: KeyValueMap,
+    #[serde(default)]
+    cookies: KeyValueMap,
+    #[serde(default)]
+    body: Option<String>
+}
+
+impl<'de> Deserialize<'de> for Request {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = RequestFields::deserialize(deserializer)?;
+        check_version(fields.schema_version)?;
+        let method = HttpMethod::from_str(&fields.method).map_err(de::Error::custom)?;
+        let mut request = Request::new(method, fields.url);
+        if let Some(version) = fields.version {
+            request.set_version(HttpVersion::from_str(&version).map_err(de::Error::custom)?);
+        }
+        *request.headers_mut() = fields.headers;
+        request.params = fields.params;
+        request.cookies = fields.cookies;
+        if let Some(body) = decode_body(fields.body)? {
+            request.set_body(body).map_err(de::Error::custom)?;
+        }
+        Ok(request)
+    }
+}
+
+impl Serialize for Response {
+    /// Serializes the response as a struct with `schema_version`, `status`, `version`, `headers`, `cookies`
+    /// (as `Set-Cookie` values), `auth`, `proxy_auth`, `body` and `history` fields. The body is encoded in
+    /// base64, or `None` if the response has no body. The redirect history is skipped if empty.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = if self.history.is_empty() { 8 } else { 9 };
+        let mut state = serializer.serialize_struct("Response", fields)?;
+        state.serialize_field("schema_version", &SCHEMA_VERSION)?;
+        state.serialize_field("status", &self.status_code)?;
+        state.serialize_field("version", &self.version().to_string())?;
+        state.serialize_field("headers", self.headers())?;
+        state.serialize_field("cookies", &self.cookies.iter().map(set_cookie_header).collect::<Vec<String>>())?;
+        state.serialize_field("auth", &self.auth)?;
+        state.serialize_field("proxy_auth", &self.proxy_auth)?;
+        state.serialize_field("body", &self.body().map(|body| BASE64.encode(body)))?;
+        if self.history.is_empty() {
+            state.skip_field("history")?;
+        } else {
+            state.serialize_field("history", &self.history)?;
+        }
+        state.end()
+    }
+}
+
+/// Serialized response fields, all but the status code are optional
+#[derive(Deserialize)]
+#[serde(rename = "Response")]
+struct ResponseFields {
+    #[serde(default)]
+    schema_version: Option<u32>,
+    status: HttpStatusCode,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    headers: HeaderMap,
+    #[serde(default)]
+    cookies: Vec<String>,
+    #[serde(default)]
+    auth: Vec<String>,
+    #[serde(default)]
+    proxy_auth: Vec<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    history: Vec<Exchange>
+}
+
+impl<'de> Deserialize<'de> for Response {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = ResponseFields::deserialize(deserializer)?;
+        check_version(fields.schema_version)?;
+        let mut response = Response::try_new(fields.status).map_err(de::Error::custom)?;
+        if let Some(version) = fields.version {
+            response.set_version(HttpVersion::from_str(&version).map_err(de::Error::custom)?);
+        }
+        *response.headers_mut() = fields.headers;
+        for cookie in fields.cookies {
+            response.cookies.push(SetCookie::from_str(&cookie).map_err(de::Error::custom)?);
+        }
+        response.auth = fields.auth;
+        response.proxy_auth = fields.proxy_auth;
+        if let Some(body) = decode_body(fields.body)? {
+            response.set_body(body).map_err(de::Error::custom)?;
+        }
+        response.history = fields.history;
+        Ok(response)
+    }
+}
+
+impl Serialize for Exchange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Exchange", 2)?;
+        state.serialize_field("request", &self.request)?;
+        state.serialize_field("response", &self.response)?;
+        state.end()
+    }
+}
+
+/// Serialized exchange fields
+#[derive(Deserialize)]
+#[serde(rename = "Exchange")]
+struct ExchangeFields {
+    request: Request,
+    response: Response
+}
+
+impl<'de> Deserialize<'de> for Exchange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = ExchangeFields::deserialize(deserializer)?;
+        Ok(Exchange::new(fields.request, fields.response))
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Signed URLs
+//!
+//! Pre-signed links carry an expiry time and an HMAC-SHA256 signature in their query params, so the server
+//! that receives them can check they were issued with the shared secret and are not expired, nor modified.
+//! The signature covers the method, the path and the rest of the params, sorted and percent-encoded; the
+//! scheme and host are not signed, so a link verifies the same behind proxies. Requires the `signed-url`
+//! feature.
+//!
+//! ```
+//! use std::time::{Duration, SystemTime};
+//! use wrequest::Request;
+//! use wrequest::wire;
+//!
+//! let now = SystemTime::now();
+//! let mut request = Request::get("https://files.example.com/reports/2024.pdf");
+//! request.sign_url(b"secret", now + Duration::from_secs(3600));
+//! assert!(request.params().contains_key("X-Signature"));
+//!
+//! // Server side
+//! let received = wire::parse_request(&wire::request_to_bytes(&request).unwrap()).unwrap();
+//! assert!(received.verify_signed_url(b"secret", now).is_ok());
+//! assert!(received.verify_signed_url(b"other", now).is_err());
+//! assert!(received.verify_signed_url(b"secret", now + Duration::from_secs(7200)).is_err());
+//! ```
+
+use std::io::{Error, ErrorKind};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use crate::{Request, uri};
+
+/// Default name of the signature param
+pub const X_SIGNATURE: &str = "X-Signature";
+/// Default name of the expiry param, in seconds since the Unix epoch
+pub const X_EXPIRES: &str = "X-Expires";
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC-SHA256 (RFC 2104) of `data` with `key`
+pub(crate) fn mac(key: &[u8], data: &[u8]) -> HmacSha256 {
+    // HMAC accepts keys of any length, longer keys are hashed first
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC key of any length");
+    mac.update(data);
+    mac
+}
+
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+/// Signer and verifier of URLs with a shared secret
+#[derive(Clone)]
+pub struct UrlSigner {
+    secret: Vec<u8>,
+    signature_param: String,
+    expires_param: String
+}
+
+impl UrlSigner {
+    /// Constructor with the `secret` and the default param names, `X-Signature` and `X-Expires`
+    pub fn new<S: AsRef<[u8]>>(secret: S) -> UrlSigner {
+        UrlSigner {
+            secret: secret.as_ref().to_vec(),
+            signature_param: X_SIGNATURE.to_string(),
+            expires_param: X_EXPIRES.to_string()
+        }
+    }
+
+    /// Sets the names of the signature and expiry params
+    pub fn with_param_names<S, E>(mut self, signature: S, expires: E) -> Self
+    where S: Into<String>,
+          E: Into<String> {
+        self.signature_param = signature.into();
+        self.expires_param = expires.into();
+        self
+    }
+
+    /// Canonical form of the request: method, path and sorted params but the signature, one per line
+    fn canonical(&self, request: &Request) -> String {
+        let url = request.full_url();
+        let mut params: Vec<(String, String)> = uri::split_query(&url).1.map(uri::parse_query).unwrap_or_default();
+        params.retain(|(key, _)| *key != self.signature_param);
+        params.sort();
+        let query: Vec<String> = params.iter()
+            .map(|(key, value)| format!("{}={}", uri::percent_encode(key), uri::percent_encode(value)))
+            .collect();
+        format!("{}\n{}\n{}", request.method(), uri::path(&url), query.join("&"))
+    }
+
+    fn signature(&self, request: &Request) -> String {
+        to_hex(&mac(&self.secret, self.canonical(request).as_bytes()).finalize().into_bytes())
+    }
+
+    /// Adds the expiry and signature params to the request. Expiry times before the Unix epoch are signed
+    /// as the epoch.
+    pub fn sign(&self, request: &mut Request, expires: SystemTime) {
+        let expires = expires.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        request.params_mut().remove(&self.signature_param);
+        request.insert_param(self.expires_param.clone(), expires.to_string());
+        let signature = self.signature(request);
+        request.insert_param(self.signature_param.clone(), signature);
+    }
+
+    /// Verifies the signature and expiry of a request at time `now`. Returns an `ErrorKind::InvalidData`
+    /// error if the params are missing or malformed and an `ErrorKind::PermissionDenied` error if the
+    /// signature does not match or the URL is expired.
+    pub fn verify(&self, request: &Request, now: SystemTime) -> Result<(), Error> {
+        let params = uri::parse_query(uri::split_query(&request.full_url()).1.unwrap_or(""));
+        let param = |name: &str| params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str());
+        let signature = param(&self.signature_param)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing URL signature"))?;
+        let expires = param(&self.expires_param)
+            .and_then(|expires| expires.parse::<u64>().ok())
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing or invalid URL expiry time"))?;
+        // The MAC is compared in constant time, so that the time to reject a signature does not tell how much
+        // of it matched
+        let valid = from_hex(signature)
+            .is_some_and(|signature| mac(&self.secret, self.canonical(request).as_bytes()).verify_slice(&signature).is_ok());
+        if !valid {
+            return Err(Error::new(ErrorKind::PermissionDenied, "Invalid URL signature"));
+        }
+        // Expiry times beyond the range of `SystemTime` cannot be checked
+        match UNIX_EPOCH.checked_add(Duration::from_secs(expires)) {
+            Some(expires) if expires > now => Ok(()),
+            _ => Err(Error::new(ErrorKind::PermissionDenied, "Expired URL"))
+        }
+    }
+}
+
+impl Request {
+    /// Signs the request URL with `secret` until `expires`, with the default params, see [UrlSigner::sign]
+    pub fn sign_url<S: AsRef<[u8]>>(&mut self, secret: S, expires: SystemTime) -> &mut Self {
+        UrlSigner::new(secret).sign(self, expires);
+        self
+    }
+
+    /// Verifies the request URL is signed with `secret` and not expired at time `now`, with the default params,
+    /// see [UrlSigner::verify]
+    pub fn verify_signed_url<S: AsRef<[u8]>>(&self, secret: S, now: SystemTime) -> Result<(), Error> {
+        UrlSigner::new(secret).verify(self, now)
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! SIMD-accelerated JSON parsing of message bodies (feature `simd`)
+//!
+//! The body is parsed with simd-json, which detects the CPU features at runtime and falls back to a portable
+//! implementation when SIMD instructions are not available, and then converted to a `json::JsonValue`.
+
+use std::io::{Error, ErrorKind};
+use json::JsonValue;
+use json::number::Number;
+use simd_json::{BorrowedValue, StaticNode};
+
+/// Parses a JSON document. Object keys keep the document order only for small objects.
+pub(crate) fn parse(data: &[u8]) -> Result<JsonValue, Error> {
+    // simd-json parses in place, so it needs its own copy of the document
+    let mut buffer = data.to_vec();
+    let value = simd_json::to_borrowed_value(&mut buffer).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    Ok(convert(value))
+}
+
+fn convert(value: BorrowedValue) -> JsonValue {
+    match value {
+        BorrowedValue::Static(StaticNode::Null) => JsonValue::Null,
+        BorrowedValue::Static(StaticNode::Bool(b)) => JsonValue::Boolean(b),
+        // Built from parts, json's From<i64> overflows with i64::MIN
+        BorrowedValue::Static(StaticNode::I64(n)) => JsonValue::Number(Number::from_parts(n >= 0, n.unsigned_abs(), 0)),
+        BorrowedValue::Static(StaticNode::U64(n)) => n.into(),
+        BorrowedValue::Static(StaticNode::F64(n)) => n.into(),
+        BorrowedValue::String(s) => JsonValue::String(s.into_owned()),
+        BorrowedValue::Array(members) => JsonValue::Array(members.into_iter().map(convert).collect()),
+        BorrowedValue::Object(entries) => {
+            let mut object = json::object::Object::with_capacity(entries.len());
+            for (key, value) in entries.into_iter() {
+                object.insert(&key, convert(value));
+            }
+            JsonValue::Object(object)
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Message size accounting
+
+use crate::{HttpMessage, Request, Response};
+use crate::wire;
+
+impl HttpMessage {
+    /// Size of the headers in the HTTP/1.1 syntax, `Name: value` lines with their line ends. Cookies, start
+    /// lines and framing headers are not included, see [Request::estimated_wire_size].
+    pub fn header_bytes(&self) -> usize {
+        self.headers().iter().map(|(name, value)| name.len() + value.len() + 4).sum()
+    }
+
+    /// Size of the single body, `0` if there is none
+    pub fn body_bytes(&self) -> usize {
+        self.body().map_or(0, |body| body.len())
+    }
+}
+
+impl Request {
+    /// Size of the request serialized by [wire::write_request]: request line, headers, `Host`, `Cookie` and
+    /// `Content-Length` headers and body, with its chunked framing if any. Only the head is formatted, the
+    /// body is not copied. Requests that cannot be written are estimated by their body only.
+    ///
+    /// ```
+    /// use wrequest::Request;
+    /// use wrequest::wire;
+    ///
+    /// let mut request = Request::post("https://service.com/users");
+    /// request.insert_cookie("session", "1234");
+    /// request.insert_header("Content-Type", "text/plain")
+    ///        .set_body(b"John".to_vec()).unwrap();
+    ///
+    /// assert_eq!(request.header_bytes(), "Content-Type: text/plain\r\n".len());
+    /// assert_eq!(request.body_bytes(), 4);
+    /// assert_eq!(request.estimated_wire_size(), wire::request_to_bytes(&request).unwrap().len());
+    /// ```
+    pub fn estimated_wire_size(&self) -> usize {
+        wire::format_request_head(self).map_or(0, |head| head.len()) + wire::encoded_body_len(self, true)
+    }
+}
+
+impl Response {
+    /// Size of the response serialized by [wire::write_response]: status line, headers, `Set-Cookie`,
+    /// authorization guides and `Content-Length` headers and body, with its chunked framing if any. Responses
+    /// that cannot be written are estimated by their body only.
+    pub fn estimated_wire_size(&self) -> usize {
+        wire::format_response_head(self).map_or(0, |head| head.len()) + wire::encoded_body_len(self, wire::allows_body(self.status_code()))
+    }
+}
@@ -0,0 +1,138 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Content sniffing from the first bytes of the body, a subset of the WHATWG MIME Sniffing standard
+
+use crate::{HttpMessage, APPLICATION_JSON, APPLICATION_OCTET_STREAM, CONTENT_TYPE};
+
+/// Bytes of the body inspected, as in the resource header of the MIME Sniffing standard
+const SNIFF_LENGTH: usize = 1445;
+
+/// File signature: pattern, optional mask and content type
+type Signature = (&'static [u8], Option<&'static [u8]>, &'static str);
+
+const SIGNATURES: &[Signature] = &[
+    (b"%PDF-", None, "application/pdf"),
+    (b"%!PS-Adobe-", None, "application/postscript"),
+    (b"\x89PNG\r\n\x1a\n", None, "image/png"),
+    (b"\xff\xd8\xff", None, "image/jpeg"),
+    (b"GIF87a", None, "image/gif"),
+    (b"GIF89a", None, "image/gif"),
+    (b"RIFF\0\0\0\0WEBPVP", Some(b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff\xff\xff"), "image/webp"),
+    (b"RIFF\0\0\0\0WAVE", Some(b"\xff\xff\xff\xff\0\0\0\0\xff\xff\xff\xff"), "audio/wave"),
+    (b"BM", None, "image/bmp"),
+    (b"\0\0\x01\0", None, "image/x-icon"),
+    (b"OggS\0", None, "application/ogg"),
+    (b"ID3", None, "audio/mpeg"),
+    (b"\x1a\x45\xdf\xa3", None, "video/webm"),
+    (b"\0\0\0\0ftyp", Some(b"\0\0\0\0\xff\xff\xff\xff"), "video/mp4"),
+    (b"\x1f\x8b\x08", None, "application/gzip"),
+    (b"PK\x03\x04", None, "application/zip"),
+    (b"Rar!\x1a\x07\0", None, "application/x-rar-compressed"),
+    (b"\xfe\xff", None, "text/plain"),
+    (b"\xff\xfe", None, "text/plain"),
+    (b"\xef\xbb\xbf", None, "text/plain")
+];
+
+/// Tags that start an HTML document, compared case-insensitively and followed by a space or `>`
+const HTML_TAGS: &[&[u8]] = &[
+    b"<!DOCTYPE HTML", b"<HTML", b"<HEAD", b"<SCRIPT", b"<IFRAME", b"<H1", b"<DIV", b"<FONT", b"<TABLE", b"<A",
+    b"<STYLE", b"<TITLE", b"<B", b"<BODY", b"<BR", b"<P"
+];
+
+fn matches_signature(data: &[u8], pattern: &[u8], mask: Option<&[u8]>) -> bool {
+    data.len() >= pattern.len() && pattern.iter().enumerate().all(|(i, byte)| {
+        let mask = mask.map_or(0xff, |mask| mask[i]);
+        data[i] & mask == *byte
+    })
+}
+
+fn is_html(data: &[u8]) -> bool {
+    HTML_TAGS.iter().any(|tag| data.len() > tag.len()
+        && data[..tag.len()].eq_ignore_ascii_case(tag)
+        && matches!(data[tag.len()], b' ' | b'>'))
+        || data.starts_with(b"<!--")
+}
+
+/// Binary data bytes of the MIME Sniffing standard, never found in text
+fn is_binary(byte: u8) -> bool {
+    matches!(byte, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+/// Sniffs the content type of a body
+pub(crate) fn sniff(body: &[u8]) -> &'static str {
+    let data = &body[..body.len().min(SNIFF_LENGTH)];
+    if let Some((_, _, content_type)) = SIGNATURES.iter().find(|(pattern, mask, _)| matches_signature(data, pattern, *mask)) {
+        return content_type;
+    }
+    let text = data.trim_ascii_start();
+    if is_html(text) {
+        return "text/html";
+    }
+    if text.starts_with(b"<?xml") {
+        return "text/xml";
+    }
+    if data.iter().any(|byte| is_binary(*byte)) {
+        return APPLICATION_OCTET_STREAM;
+    }
+    if text.starts_with(b"{") || text.starts_with(b"[") {
+        if let Ok(json) = std::str::from_utf8(body) {
+            if json::parse(json).is_ok() {
+                return APPLICATION_JSON;
+            }
+        }
+    }
+    "text/plain"
+}
+
+impl HttpMessage {
+    /// Sniffs the content type of the body from its first bytes: known file signatures, like PNG, JPEG,
+    /// PDF or gzip, HTML and XML documents, JSON, plain text and `application/octet-stream` for other binary
+    /// data. Returns `None` if there is no body. The `Content-Type` header is not taken into account.
+    ///
+    /// ```
+    /// use wrequest::{Response, HTTP_200_OK};
+    ///
+    /// let mut response = Response::new(HTTP_200_OK);
+    /// response.insert_header("Content-Type", "application/octet-stream")
+    ///         .set_body(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec()).unwrap();
+    /// assert_eq!(response.sniff_content_type(), Some("image/png"));
+    ///
+    /// assert!(response.fix_content_type());
+    /// assert_eq!(response.headers().get("Content-Type"), Some("image/png"));
+    /// ```
+    pub fn sniff_content_type(&self) -> Option<&'static str> {
+        self.body().map(sniff)
+    }
+
+    /// Sets the `Content-Type` header with the sniffed content type if it is missing, or if it is
+    /// `application/octet-stream` and a more specific type is sniffed. Returns `true` if the header was set.
+    pub fn fix_content_type(&mut self) -> bool {
+        let declared = self.headers().get(CONTENT_TYPE)
+            .map(|value| value.split(';').next().unwrap_or("").trim().to_ascii_lowercase());
+        let sniffed = match self.sniff_content_type() {
+            Some(sniffed) => sniffed,
+            None => return false
+        };
+        match declared.as_deref() {
+            None => {},
+            Some(APPLICATION_OCTET_STREAM) if sniffed != APPLICATION_OCTET_STREAM => {},
+            _ => return false
+        }
+        self.insert_header(CONTENT_TYPE, sniffed);
+        true
+    }
+}
@@ -0,0 +1,269 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Server-Sent Events (`text/event-stream`) support
+//!
+//! ```
+//! use wrequest::Response;
+//! use wrequest::sse::{Event, EventParser};
+//!
+//! let mut response = Response::new(wrequest::HTTP_200_OK);
+//!
+//! let mut event = Event::new("Hello\nWorld");
+//! event.set_event("greeting");
+//! response.append_event(&event).unwrap();
+//!
+//! assert_eq!(response.headers().get("Content-Type").unwrap(), "text/event-stream");
+//!
+//! // Parse the body in arbitrary chunks, as received from a stream
+//! let mut parser = EventParser::new();
+//! let body = response.body().unwrap();
+//! let mut events = parser.feed(&body[..10]);
+//! events.extend(parser.feed(&body[10..]));
+//!
+//! assert_eq!(events, vec![event]);
+//! ```
+//!
+//! For more information see [Server-sent events](https://html.spec.whatwg.org/multipage/server-sent-events.html).
+
+use std::fmt;
+use std::io::Error;
+use std::time::Duration;
+use crate::{HttpMessage, CONTENT_TYPE, TEXT_EVENT_STREAM};
+
+/// A Server-Sent Event
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Event {
+    /// Event ID (`id` field)
+    pub id: Option<String>,
+    /// Event type (`event` field)
+    pub event: Option<String>,
+    /// Event data, lines are separated by `\n` (`data` fields)
+    pub data: String,
+    /// Reconnection time (`retry` field)
+    pub retry: Option<Duration>
+}
+
+impl Event {
+    /// Constructor with the event `data`
+    pub fn new<S>(data: S) -> Event
+    where S: Into<String> {
+        Event {
+            id: None,
+            event: None,
+            data: data.into(),
+            retry: None
+        }
+    }
+
+    /// Sets the event ID. Line breaks would end the field, so `\r` and `\n` are removed, as well as `\0`, as
+    /// IDs with it are ignored by the receivers.
+    pub fn set_id<S>(&mut self, id: S) -> &mut Self
+    where S: Into<String> {
+        let mut id = id.into();
+        id.retain(|c| !matches!(c, '\r' | '\n' | '\0'));
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the event type. Line breaks would end the field, so `\r` and `\n` are removed.
+    pub fn set_event<S>(&mut self, event: S) -> &mut Self
+    where S: Into<String> {
+        let mut event = event.into();
+        event.retain(|c| !matches!(c, '\r' | '\n'));
+        self.event = Some(event);
+        self
+    }
+}
+
+/// Writes a single line field, without the line breaks that would start other fields or events
+fn write_field(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    write!(f, "{}: ", name)?;
+    for part in value.split(['\r', '\n']) {
+        f.write_str(part)?;
+    }
+    writeln!(f)
+}
+
+impl fmt::Display for Event {
+    /// Formats the event using the `text/event-stream` framing, including the blank line that ends the event.
+    /// Line breaks in the `id` and `event` fields are removed, see [Event::set_id] and [Event::set_event].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref id) = self.id {
+            write_field(f, "id", id)?;
+        }
+        if let Some(ref event) = self.event {
+            write_field(f, "event", event)?;
+        }
+        if let Some(retry) = self.retry {
+            writeln!(f, "retry: {}", retry.as_millis())?;
+        }
+        for line in self.data.split('\n') {
+            writeln!(f, "data: {}", line.strip_suffix('\r').unwrap_or(line))?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Incremental `text/event-stream` parser.
+///
+/// Bytes are fed as they are received and complete events are returned once their ending blank
+/// line is found. An incomplete event at the end of the stream is discarded, as the spec mandates.
+pub struct EventParser {
+    /// Bytes of the current incomplete line
+    line: Vec<u8>,
+    /// Last line ended in `\r`, so a following `\n` belongs to the same line break
+    skip_lf: bool,
+    /// First line has been processed (and its BOM removed if any)
+    started: bool,
+    /// Data buffer of the current event
+    data: String,
+    /// Event type of the current event
+    event: Option<String>,
+    /// ID of the current event
+    id: Option<String>,
+    /// Retry time of the current event
+    retry: Option<Duration>,
+    /// Last event ID seen in the stream
+    last_event_id: Option<String>
+}
+
+impl EventParser {
+    /// Constructor
+    pub fn new() -> EventParser {
+        EventParser {
+            line: Vec::new(),
+            skip_lf: false,
+            started: false,
+            data: String::new(),
+            event: None,
+            id: None,
+            retry: None,
+            last_event_id: None
+        }
+    }
+
+    /// Gets the last event ID seen in the stream, used to resume it with the `Last-Event-ID` header.
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    /// Feeds a chunk of the stream and returns the events completed by it.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<Event> {
+        let mut events = Vec::new();
+        for byte in chunk {
+            if self.skip_lf {
+                self.skip_lf = false;
+                if *byte == b'\n' {
+                    continue;
+                }
+            }
+            match *byte {
+                b'\r' => {
+                    self.skip_lf = true;
+                    self.process_line(&mut events);
+                },
+                b'\n' => self.process_line(&mut events),
+                _ => self.line.push(*byte)
+            }
+        }
+        events
+    }
+
+    fn process_line(&mut self, events: &mut Vec<Event>) {
+        let raw = std::mem::take(&mut self.line);
+        let decoded = String::from_utf8_lossy(&raw);
+        let mut line: &str = &decoded;
+        if !self.started {
+            self.started = true;
+            line = line.strip_prefix('\u{feff}').unwrap_or(line);
+        }
+
+        if line.is_empty() {
+            self.dispatch(events);
+            return;
+        }
+
+        if line.starts_with(':') {
+            // Comment line
+            return;
+        }
+
+        let (field, value) = match line.find(':') {
+            Some(pos) => {
+                let value = &line[pos + 1..];
+                (&line[..pos], value.strip_prefix(' ').unwrap_or(value))
+            },
+            None => (line, "")
+        };
+
+        match field {
+            "data" => {
+                self.data.push_str(value);
+                self.data.push('\n');
+            },
+            "event" => self.event = Some(value.to_string()),
+            "id" if !value.contains('\0') => {
+                self.id = Some(value.to_string());
+                self.last_event_id = Some(value.to_string());
+            },
+            "retry" if value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse::<u64>() {
+                    self.retry = Some(Duration::from_millis(millis));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self, events: &mut Vec<Event>) {
+        let mut data = std::mem::take(&mut self.data);
+        let event = self.event.take();
+        let id = self.id.take();
+        let retry = self.retry.take();
+
+        if data.is_empty() {
+            return;
+        }
+        data.pop();
+
+        events.push(Event { id, event, data, retry });
+    }
+}
+
+impl Default for EventParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpMessage {
+    /// Appends an event to a `text/event-stream` body, setting its `Content-Type`. Returns a
+    /// [BodyTooLarge](crate::BodyTooLarge) error, keeping the current body, if the body would exceed the
+    /// maximum body size.
+    pub fn append_event(&mut self, event: &Event) -> Result<&mut Self, Error> {
+        self.append_body(event.to_string().into_bytes())?;
+        self.headers.insert(CONTENT_TYPE, TEXT_EVENT_STREAM);
+        Ok(self)
+    }
+
+    /// Parses the body as a `text/event-stream` and returns its complete events.
+    /// Returns an empty vector if there is no single body.
+    pub fn events(&self) -> Vec<Event> {
+        match self.body() {
+            Some(body) => EventParser::new().feed(body),
+            None => Vec::new()
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Syntactic classification of request targets, a first line of defense against server-side request forgery
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::{Request, uri};
+
+/// Classification of a request URL, from its syntax only: host names are not resolved, so a public name
+/// pointing to a private address is not detected. IPv4 literals are parsed as browsers do, including the
+/// decimal, octal and hexadecimal forms like `2130706433` or `0x7f.1`, and IPv6 addresses that embed an IPv4
+/// one (IPv4-mapped, IPv4-compatible and NAT64 `64:ff9b::/96`) are classified by their IPv4 address. As WHATWG URL
+/// parsers do, a backslash ends the authority, so `http://127.0.0.1\@evil.com/` targets `127.0.0.1`.
+///
+/// ```
+/// use wrequest::Request;
+///
+/// let classification = Request::get("http://0x7f.1:8080/admin").url_classification();
+/// assert!(classification.localhost);
+/// assert!(classification.non_default_port);
+/// assert!(!classification.is_public_http());
+///
+/// let classification = Request::get("http://169.254.169.254/latest/meta-data").url_classification();
+/// assert!(classification.link_local);
+///
+/// assert!(Request::get("https://hooks.example.com/callback").url_classification().is_public_http());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct UrlClassification {
+    /// Lowercase scheme, `None` for relative URLs
+    pub scheme: Option<String>,
+    /// Lowercase host, percent-decoded and without trailing dot, `None` for relative URLs
+    pub host: Option<String>,
+    /// Address of an IP literal host
+    pub ip: Option<IpAddr>,
+    /// Explicit port, if any
+    pub port: Option<u16>,
+    /// The host is `localhost`, a `.localhost` name or a loopback address
+    pub localhost: bool,
+    /// The host is a private (RFC 1918), shared (RFC 6598), unique local (RFC 4193), deprecated site-local
+    /// (RFC 3879) or unspecified address
+    pub private: bool,
+    /// The host is a link-local address, like the `169.254.169.254` metadata service of cloud providers
+    pub link_local: bool,
+    /// The host is a multicast, broadcast or reserved (`240.0.0.0/4`) address
+    pub reserved: bool,
+    /// The scheme is not `http` nor `https`, or the URL is relative
+    pub non_http_scheme: bool,
+    /// The port is not the default one of the scheme, or it is not a valid port number
+    pub non_default_port: bool
+}
+
+impl UrlClassification {
+    /// Classifies a URL
+    pub fn classify(url: &str) -> UrlClassification {
+        let mut result = UrlClassification::default();
+        let (start, end) = match uri::host_range(url) {
+            Some(range) => range,
+            None => {
+                result.non_http_scheme = true;
+                return result;
+            }
+        };
+
+        let scheme = url[..url.find("://").unwrap_or(0)].to_ascii_lowercase();
+        let default_port = match scheme.as_str() {
+            "http" => Some(80),
+            "https" => Some(443),
+            _ => None
+        };
+        result.non_http_scheme = default_port.is_none();
+
+        let rest = &url[end..];
+        if let Some(port) = rest.strip_prefix(':') {
+            let port = &port[..port.find(['/', '?', '#', '\\']).unwrap_or(port.len())];
+            if !port.is_empty() {
+                result.port = port.parse().ok();
+                result.non_default_port = result.port.is_none() || result.port != default_port;
+            }
+        }
+
+        let host = uri::percent_decode(&url[start..end]).to_lowercase();
+        let host = host.strip_suffix('.').unwrap_or(&host).to_string();
+        result.ip = parse_ip(&host);
+        result.localhost = host == "localhost" || host.ends_with(".localhost");
+        match result.ip {
+            Some(IpAddr::V4(ip)) => result.classify_v4(ip),
+            Some(IpAddr::V6(ip)) => match embedded_ipv4(ip) {
+                Some(ip) => result.classify_v4(ip),
+                None => result.classify_v6(ip)
+            },
+            None => {}
+        }
+        result.scheme = Some(scheme);
+        result.host = Some(host);
+        result
+    }
+
+    fn classify_v4(&mut self, ip: Ipv4Addr) {
+        let [a, b, ..] = ip.octets();
+        self.localhost |= ip.is_loopback();
+        self.private = ip.is_private() || ip.is_unspecified() || a == 0 || (a == 100 && (64..128).contains(&b));
+        self.link_local = ip.is_link_local();
+        self.reserved = ip.is_multicast() || a >= 240;
+    }
+
+    fn classify_v6(&mut self, ip: Ipv6Addr) {
+        let first = ip.segments()[0];
+        self.localhost |= ip.is_loopback();
+        self.private = ip.is_unspecified() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfec0;
+        self.link_local = first & 0xffc0 == 0xfe80;
+        self.reserved = ip.is_multicast();
+    }
+
+    /// Checks the URL is an `http` or `https` URL with the default port and a host that is neither local,
+    /// private nor link-local
+    pub fn is_public_http(&self) -> bool {
+        self.host.is_some() && !(self.localhost || self.private || self.link_local || self.reserved || self.non_http_scheme ||
+            self.non_default_port)
+    }
+}
+
+/// IPv4 address embedded in an IPv4-mapped (`::ffff:0:0/96`), IPv4-compatible (`::/96`, but `::` and `::1`) or
+/// NAT64 (`64:ff9b::/96`, RFC 6052) IPv6 address
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let embedded = Ipv4Addr::from((u32::from(segments[6]) << 16) | u32::from(segments[7]));
+    match segments[..6] {
+        [0, 0, 0, 0, 0, 0xffff] | [0x64, 0xff9b, 0, 0, 0, 0] => Some(embedded),
+        [0, 0, 0, 0, 0, 0] if !ip.is_unspecified() && !ip.is_loopback() => Some(embedded),
+        _ => None
+    }
+}
+
+/// Parses an IP literal host: an IPv6 address in brackets or an IPv4 address in any of its legacy forms
+fn parse_ip(host: &str) -> Option<IpAddr> {
+    if let Some(ip) = host.strip_prefix('[').and_then(|host| host.strip_suffix(']')) {
+        return ip.parse::<Ipv6Addr>().ok().map(IpAddr::V6);
+    }
+    parse_ipv4(host).map(IpAddr::V4)
+}
+
+/// Parses an IPv4 address of one to four dot-separated decimal, octal (`0` prefix) or hexadecimal (`0x` prefix)
+/// numbers, the last one filling the remaining bytes (WHATWG URL standard, IPv4 parser)
+fn parse_ipv4(host: &str) -> Option<Ipv4Addr> {
+    let parts: Vec<&str> = host.split('.').collect();
+    if parts.len() > 4 {
+        return None;
+    }
+    let mut numbers = Vec::with_capacity(parts.len());
+    for part in parts {
+        let (digits, radix) = if let Some(hex) = part.strip_prefix("0x") {
+            (hex, 16)
+        } else if part.len() > 1 && part.starts_with('0') {
+            (&part[1..], 8)
+        } else {
+            (part, 10)
+        };
+        let number = if digits.is_empty() && radix == 16 {
+            0
+        } else if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        } else {
+            u64::from_str_radix(digits, radix).ok()?
+        };
+        numbers.push(number);
+    }
+    let (last, first) = numbers.split_last()?;
+    if first.iter().any(|number| *number > 255) || *last >= 256u64.pow(4 - first.len() as u32) {
+        return None;
+    }
+    let address = first.iter().enumerate().fold(*last, |address, (i, number)| address + (number << (8 * (3 - i))));
+    Some(Ipv4Addr::from(address as u32))
+}
+
+impl Request {
+    /// Classifies the request URL to detect local and private targets, see [UrlClassification]
+    pub fn url_classification(&self) -> UrlClassification {
+        UrlClassification::classify(self.url())
+    }
+}
@@ -0,0 +1,315 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Validated status codes
+
+use std::error;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::sync::RwLock;
+use json::JsonValue;
+use crate::{HttpStatusCode, Problem, Response, CONTENT_TYPE};
+
+/// Custom status codes registered with [register_status_code], with their reason phrases
+static CUSTOM_CODES: RwLock<Vec<(u16, &'static str)>> = RwLock::new(Vec::new());
+
+/// Registers a custom status code, for example a vendor `799` code, with its reason phrase.
+/// Registered codes are accepted by [StatusCode::try_from] and their reason phrase is returned by
+/// [crate::reason_phrase]. Codes must have three digits.
+pub fn register_status_code(code: u16, reason: &'static str) -> Result<(), Error> {
+    if !(100..=999).contains(&code) {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid status code {}", code)));
+    }
+    let mut codes = CUSTOM_CODES.write().unwrap_or_else(|e| e.into_inner());
+    match codes.iter_mut().find(|(registered, _)| *registered == code) {
+        Some(entry) => entry.1 = reason,
+        None => codes.push((code, reason))
+    }
+    Ok(())
+}
+
+/// Gets the reason phrase of a registered custom status code
+pub(crate) fn custom_reason_phrase(code: u16) -> Option<&'static str> {
+    let codes = CUSTOM_CODES.read().unwrap_or_else(|e| e.into_inner());
+    codes.iter().find(|(registered, _)| *registered == code).map(|(_, reason)| *reason)
+}
+
+/// Class of a status code, given by its first digit
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StatusClass {
+    /// `1xx`, the request was received, continuing process
+    Informational,
+    /// `2xx`, the request was successfully received, understood, and accepted
+    Success,
+    /// `3xx`, further action needs to be taken to complete the request
+    Redirection,
+    /// `4xx`, the request contains bad syntax or cannot be fulfilled
+    ClientError,
+    /// `5xx`, the server failed to fulfill an apparently valid request
+    ServerError,
+    /// Registered custom code beyond `5xx`
+    Custom
+}
+
+/// Validated HTTP status code: in the `100`–`599` range or registered with [register_status_code].
+///
+/// ```
+/// use wrequest::{StatusCode, StatusClass};
+///
+/// assert!(StatusCode::try_from(9999).is_err());
+///
+/// let status = StatusCode::try_from(429).unwrap();
+/// assert_eq!(status.class(), StatusClass::ClientError);
+/// assert_eq!(status.reason_phrase(), Some("Too Many Requests"));
+///
+/// // Unknown codes are kept, and understood as the `x00` code of their class
+/// let unknown = StatusCode::try_from(499).unwrap();
+/// assert_eq!(unknown.as_u16(), 499);
+/// assert_eq!(unknown.canonical().as_u16(), 400);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct StatusCode(u16);
+
+impl StatusCode {
+    /// Gets the numeric code
+    pub fn as_u16(&self) -> u16 {
+        self.0
+    }
+
+    /// Gets the class of the code
+    pub fn class(&self) -> StatusClass {
+        match self.0 / 100 {
+            1 => StatusClass::Informational,
+            2 => StatusClass::Success,
+            3 => StatusClass::Redirection,
+            4 => StatusClass::ClientError,
+            5 => StatusClass::ServerError,
+            _ => StatusClass::Custom
+        }
+    }
+
+    /// Checks the code is informational, `1xx`
+    pub fn is_informational(&self) -> bool {
+        self.class() == StatusClass::Informational
+    }
+
+    /// Checks the code is successful, `2xx`
+    pub fn is_success(&self) -> bool {
+        self.class() == StatusClass::Success
+    }
+
+    /// Checks the code is a redirection, `3xx`
+    pub fn is_redirection(&self) -> bool {
+        self.class() == StatusClass::Redirection
+    }
+
+    /// Checks the code is a client error, `4xx`
+    pub fn is_client_error(&self) -> bool {
+        self.class() == StatusClass::ClientError
+    }
+
+    /// Checks the code is a server error, `5xx`
+    pub fn is_server_error(&self) -> bool {
+        self.class() == StatusClass::ServerError
+    }
+
+    /// Gets the reason phrase of a standard or registered code
+    pub fn reason_phrase(&self) -> Option<&'static str> {
+        crate::reason_phrase(self.0)
+    }
+
+    /// Gets the code as understood by a recipient: known and registered codes are kept, unknown ones are
+    /// the `x00` code of their class (RFC 9110, section 15)
+    pub fn canonical(&self) -> StatusCode {
+        if self.reason_phrase().is_some() || self.class() == StatusClass::Custom {
+            *self
+        } else {
+            StatusCode(self.0 / 100 * 100)
+        }
+    }
+}
+
+impl TryFrom<u16> for StatusCode {
+    type Error = Error;
+
+    /// Accepts codes in the `100`–`599` range and registered custom codes
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        if (100..=599).contains(&code) || custom_reason_phrase(code).is_some() {
+            Ok(StatusCode(code))
+        } else {
+            Err(Error::new(ErrorKind::InvalidInput, format!("Invalid status code {}", code)))
+        }
+    }
+}
+
+impl From<StatusCode> for u16 {
+    fn from(status: StatusCode) -> u16 {
+        status.0
+    }
+}
+
+impl PartialEq<u16> for StatusCode {
+    fn eq(&self, other: &u16) -> bool {
+        self.0 == *other
+    }
+}
+
+impl fmt::Display for StatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.reason_phrase() {
+            Some(reason) => write!(f, "{} {}", self.0, reason),
+            None => write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl Response {
+    /// Creates a response with a validated status code, unlike [Response::new], failing with
+    /// `ErrorKind::InvalidInput` if the code is not valid
+    pub fn try_new(status: HttpStatusCode) -> Result<Response, Error> {
+        Ok(Response::new(StatusCode::try_from(status)?.as_u16()))
+    }
+
+    /// Gets the validated status code, `None` if the response was created with an invalid one
+    pub fn status(&self) -> Option<StatusCode> {
+        StatusCode::try_from(self.status_code()).ok()
+    }
+}
+
+/// Error of a response with a client or server error status code, see [Response::error_for_status]
+pub struct StatusError {
+    /// Status code
+    pub code: HttpStatusCode,
+    /// Reason phrase of the status code, empty if unknown
+    pub reason: String,
+    /// Error body, if it is JSON
+    pub body: Option<JsonValue>,
+    /// Whole response
+    response: Box<Response>
+}
+
+impl StatusError {
+    fn new(response: Response) -> StatusError {
+        let is_json = response.headers().get(CONTENT_TYPE)
+            .map(|value| value.split(';').next().unwrap_or("").trim().to_ascii_lowercase())
+            .is_some_and(|mime| mime == "application/json" || mime.ends_with("+json"));
+        StatusError {
+            code: response.status_code(),
+            reason: crate::reason_phrase(response.status_code()).unwrap_or("").to_string(),
+            body: if is_json { response.json().ok() } else { None },
+            response: Box::new(response)
+        }
+    }
+
+    /// Gets the response
+    pub fn response(&self) -> &Response {
+        &self.response
+    }
+
+    /// Takes the response
+    pub fn into_response(self) -> Response {
+        *self.response
+    }
+
+    /// Gets the Problem Details of the response, if any, see [Response::as_problem]
+    pub fn problem(&self) -> Option<Problem> {
+        self.response.as_problem()
+    }
+
+    /// Checks the status code is a client error one (`4xx`)
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.code)
+    }
+
+    /// Checks the status code is a server error one (`5xx`)
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.code)
+    }
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_client_error() {
+            "Client error"
+        } else if self.is_server_error() {
+            "Server error"
+        } else {
+            "Error"
+        };
+        write!(f, "{} status {}", kind, self.code)?;
+        if !self.reason.is_empty() {
+            write!(f, " {}", self.reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatusError")
+            .field("code", &self.code)
+            .field("reason", &self.reason)
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
+
+impl error::Error for StatusError {}
+
+impl From<StatusError> for Error {
+    /// Wraps the status error in an `ErrorKind::Other` I/O error
+    fn from(error: StatusError) -> Error {
+        Error::other(error)
+    }
+}
+
+impl Response {
+    /// Turns a response with a client or server error status code (`400` and above) into a [StatusError], so
+    /// it can be propagated with `?`. Other responses are returned as they are.
+    ///
+    /// ```
+    /// use wrequest::{Response, StatusError, HTTP_200_OK, HTTP_404_NOT_FOUND};
+    /// use json::object;
+    ///
+    /// fn fetch(response: Response) -> Result<String, StatusError> {
+    ///     let response = response.error_for_status()?;
+    ///     Ok(String::from_utf8_lossy(response.body().unwrap_or(&[])).into_owned())
+    /// }
+    ///
+    /// let mut ok = Response::new(HTTP_200_OK);
+    /// ok.set_body(b"John".to_vec()).unwrap();
+    /// assert_eq!(fetch(ok).unwrap(), "John");
+    ///
+    /// let error = fetch(Response::json(HTTP_404_NOT_FOUND, &object! { error: "no such user" }).unwrap()).unwrap_err();
+    /// assert_eq!(error.to_string(), "Client error status 404 Not Found");
+    /// assert_eq!(error.body.unwrap()["error"], "no such user");
+    /// ```
+    pub fn error_for_status(self) -> Result<Response, StatusError> {
+        if self.status_code() >= 400 {
+            Err(StatusError::new(self))
+        } else {
+            Ok(self)
+        }
+    }
+
+    /// Checks the status code like [Response::error_for_status], without taking the response
+    pub fn error_for_status_ref(&self) -> Result<&Response, StatusError> {
+        if self.status_code() >= 400 {
+            Err(StatusError::new(self.clone()))
+        } else {
+            Ok(self)
+        }
+    }
+}
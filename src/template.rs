@@ -0,0 +1,105 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Request templates
+
+use std::io::Error;
+use json::JsonValue;
+use crate::{HttpMethod, Request};
+
+/// Prototype of similar requests: method, base URL, default headers, params, cookies and body, that stamps
+/// out concrete requests with per-call overrides.
+///
+/// ```
+/// use wrequest::{HttpMethod, RequestTemplate};
+///
+/// let template = RequestTemplate::new(HttpMethod::GET, "https://service.com/api")
+///     .with_header("Accept", "application/json")
+///     .with_param("page", "1");
+///
+/// let request = template.build_path("users", |r| { r.insert_param("page", "2"); });
+/// assert_eq!(request.url(), "https://service.com/api/users");
+/// assert_eq!(request.params().get("page"), Some("2"));
+/// assert_eq!(request.headers().get("Accept"), Some("application/json"));
+/// ```
+#[derive(Clone)]
+pub struct RequestTemplate {
+    prototype: Request
+}
+
+impl RequestTemplate {
+    /// Creates a template of requests with `method` to `base_url`
+    pub fn new<S: Into<String>>(method: HttpMethod, base_url: S) -> RequestTemplate {
+        RequestTemplate { prototype: Request::new(method, base_url) }
+    }
+
+    /// Adds a default header
+    pub fn with_header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.prototype.insert_header(key.into(), value.into());
+        self
+    }
+
+    /// Adds a default param
+    pub fn with_param<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.prototype.insert_param(key.into(), value.into());
+        self
+    }
+
+    /// Adds a default cookie
+    pub fn with_cookie<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.prototype.insert_cookie(key.into(), value.into());
+        self
+    }
+
+    /// Sets the default body, see [crate::HttpMessage::set_body]
+    pub fn with_body(mut self, data: Vec<u8>) -> Result<Self, Error> {
+        self.prototype.set_body(data)?;
+        Ok(self)
+    }
+
+    /// Sets a default JSON body and its `Content-Type`, see [crate::HttpMessage::set_json]
+    pub fn with_json(mut self, data: &JsonValue) -> Result<Self, Error> {
+        self.prototype.set_json(data)?;
+        Ok(self)
+    }
+
+    /// Gets the prototype request
+    pub fn prototype(&self) -> &Request {
+        &self.prototype
+    }
+
+    /// Creates a request from the template, applying the `overrides` to a copy of the prototype
+    pub fn build<F>(&self, overrides: F) -> Request
+    where F: FnOnce(&mut Request) {
+        let mut request = self.prototype.clone();
+        overrides(&mut request);
+        request
+    }
+
+    /// Creates a request to `path`, relative to the template base URL, applying the `overrides` to it
+    pub fn build_path<F>(&self, path: &str, overrides: F) -> Request
+    where F: FnOnce(&mut Request) {
+        let mut request = self.build(overrides);
+        request.url = format!("{}/{}", self.prototype.url().trim_end_matches('/'), path.trim_start_matches('/'));
+        request
+    }
+}
+
+impl From<Request> for RequestTemplate {
+    /// Uses a request as prototype
+    fn from(prototype: Request) -> Self {
+        RequestTemplate { prototype }
+    }
+}
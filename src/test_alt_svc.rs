@@ -0,0 +1,41 @@
+use crate::*;
+use crate::alt_svc::*;
+use std::time::Duration;
+
+#[test]
+fn parse() {
+    let services = AltService::parse_list("w%3Dx%3Ay=\"[::1]:443\"; ma=60; v=\"a,b\", h2=\"\\\"odd\\\":80\"; unknown, bad");
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].protocol_id, "w=x:y");
+    assert_eq!(services[0].host(), "[::1]");
+    assert_eq!(services[0].port(), Some(443));
+    assert_eq!(services[0].max_age_or_default(), Duration::from_secs(60));
+    assert_eq!(services[0].params, vec![("v".to_string(), "a,b".to_string())]);
+    assert_eq!(services[1].authority, "\"odd\":80");
+    assert_eq!(services[1].max_age_or_default(), DEFAULT_MAX_AGE);
+
+    assert!(AltService::parse_list("h3=\":443\"; ma=soon").is_empty());
+    assert!(AltService::parse_list("h3=:443").is_empty());
+    assert!(AltService::parse_list("clear").is_empty());
+}
+
+#[test]
+fn display() {
+    let service = AltService::new("w=x", "alt.com:443").with_persist(true).with_max_age(Duration::from_secs(10));
+    assert_eq!(service.to_string(), "w%3Dx=\"alt.com:443\"; ma=10; persist=1");
+    assert_eq!(AltService::parse_list(&service.to_string()), vec![service]);
+}
+
+#[test]
+fn response() {
+    let mut response = Response::new(HTTP_200_OK);
+    assert!(response.alt_services().is_empty());
+    response.set_alt_services(&[]);
+    assert!(response.clears_alt_services());
+    assert_eq!(response.headers().get(ALT_SVC), Some(CLEAR));
+
+    response.set_alt_services(&[AltService::new("h3", ":443"), AltService::new("h2", ":443")]);
+    assert!(!response.clears_alt_services());
+    assert_eq!(response.headers().get(ALT_SVC), Some("h3=\":443\", h2=\":443\""));
+    assert_eq!(response.alt_services().len(), 2);
+}
@@ -0,0 +1,54 @@
+use crate::*;
+use crate::batch::{APPLICATION_HTTP, MULTIPART_MIXED};
+use wcookie::SetCookie;
+
+#[test]
+fn batch_roundtrip() {
+    let mut create = Request::post("https://service.com/users");
+    create.insert_header("Content-Type", "application/json")
+          .set_body(b"{\"name\":\"john\"}".to_vec()).unwrap();
+    create.insert_cookie("session", "1234");
+    let calls = vec![Request::get("https://service.com/users?page=2"), create];
+
+    let batch = Request::batch("https://service.com/$batch", &calls).unwrap();
+    assert_eq!(batch.method(), HttpMethod::POST);
+    assert!(batch.headers().get(CONTENT_TYPE).unwrap().starts_with(MULTIPART_MIXED));
+    let body = String::from_utf8(batch.body().unwrap().to_vec()).unwrap();
+    assert!(body.contains(APPLICATION_HTTP));
+    assert!(body.contains("Content-Type: application/http\r\nContent-ID: <2>\r\n\r\nPOST /users HTTP/1.1\r\n"));
+
+    let received = batch.batch_requests().unwrap();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].params().get("page"), Some("2"));
+    assert_eq!(received[1].cookies().get("session"), Some("1234"));
+    assert_eq!(received[1].body().unwrap(), b"{\"name\":\"john\"}");
+
+    let mut created = Response::new(HTTP_201_CREATED);
+    created.insert_cookie(SetCookie::new("id", "7"))
+           .set_body(b"created".to_vec()).unwrap();
+    let answer = Response::batch(&[Response::new(HTTP_200_OK), created]).unwrap();
+    let responses = answer.batch_responses().unwrap();
+    assert_eq!(responses[0].status_code(), HTTP_200_OK);
+    assert_eq!(responses[1].cookies()[0].value, "7");
+    assert_eq!(responses[1].body().unwrap(), b"created");
+
+    let empty = Response::batch(&[]).unwrap();
+    assert!(empty.batch_responses().unwrap().is_empty());
+}
+
+#[test]
+fn batch_errors() {
+    let mut request = Request::post("https://service.com/$batch");
+    request.insert_header(CONTENT_TYPE, "application/json");
+    assert!(request.batch_requests().is_err());
+
+    request.insert_header(CONTENT_TYPE, "multipart/mixed; boundary=b")
+           .set_body(b"--b\r\nContent-Type: text/plain\r\n\r\nhello\r\n--b--".to_vec()).unwrap();
+    assert!(request.batch_requests().is_err());
+
+    request.set_body(b"--b\r\nContent-Type: application/http\r\n\r\nGET / HTTP/1.1\r\nHost: a\r\n\r\n".to_vec()).unwrap();
+    assert_eq!(request.batch_requests().err().unwrap().kind(), std::io::ErrorKind::UnexpectedEof);
+
+    request.set_body(b"--b\r\nContent-Type: application/http\r\n\r\nNOT HTTP\r\n--b--".to_vec()).unwrap();
+    assert!(request.batch_requests().is_err());
+}
@@ -0,0 +1,98 @@
+use crate::*;
+use crate::cassette::{Cassette, MatchOptions, BodyMatch};
+use json::object;
+use std::fs;
+
+fn cassette_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("wrequest-{}-{}.har", name, std::process::id()))
+}
+
+fn create_request(name: &str) -> Request {
+    let mut request = Request::post("https://service.com/users");
+    request.insert_param("lang", "en")
+           .insert_header("User-Agent", "test/1.0")
+           .insert_header("Accept", "application/json")
+           .set_json(&object! { name: name, age: 30 }).unwrap();
+    request
+}
+
+#[test]
+fn record_replay() {
+    let path = cassette_path("record");
+    let _ = fs::remove_file(&path);
+
+    let mut cassette = Cassette::open(&path).unwrap();
+    assert!(cassette.is_recording());
+
+    let mut first = Response::new(HTTP_201_CREATED);
+    first.set_json(&object! { id: 1 }).unwrap();
+    let mut second = Response::new(HTTP_201_CREATED);
+    second.set_json(&object! { id: 2 }).unwrap();
+
+    cassette.record(&create_request("John"), &first);
+    cassette.record(&create_request("John"), &second);
+    cassette.record(&create_request("Jane"), &Response::new(HTTP_409_CONFLICT));
+    cassette.save().unwrap();
+
+    let mut cassette = Cassette::open(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(!cassette.is_recording());
+    assert_eq!(cassette.len(), 3);
+
+    // Volatile headers and JSON formatting are ignored
+    let mut request = create_request("John");
+    request.insert_header("User-Agent", "other/2.0")
+           .set_body(b"{\"age\":30,  \"name\":\"John\"}".to_vec()).unwrap();
+
+    assert_eq!(cassette.replay(&request).unwrap().json().unwrap()["id"], 1);
+    assert_eq!(cassette.replay(&request).unwrap().json().unwrap()["id"], 2);
+    // Last matching interaction is repeated
+    assert_eq!(cassette.replay(&request).unwrap().json().unwrap()["id"], 2);
+    assert!(!cassette.is_exhausted());
+
+    assert_eq!(cassette.replay(&create_request("Jane")).unwrap().status_code(), HTTP_409_CONFLICT);
+    assert!(cassette.is_exhausted());
+
+    assert!(cassette.replay(&create_request("Bob")).is_none());
+    let mut request = create_request("John");
+    request.insert_header("Accept", "text/html");
+    assert!(cassette.replay(&request).is_none());
+    let mut request = create_request("John");
+    request.insert_param("page", "2");
+    assert!(cassette.replay(&request).is_none());
+}
+
+#[test]
+fn match_options() {
+    let recorded = create_request("John");
+    let mut request = create_request("John");
+    request.insert_param("timestamp", "1234")
+           .insert_header("X-Extra", "yes")
+           .set_body(b"not json".to_vec()).unwrap();
+
+    let default = MatchOptions::default();
+    assert!(!default.matches(&recorded, &request));
+
+    let options = MatchOptions {
+        ignore_params: vec!["timestamp".to_string()],
+        match_headers: false,
+        body: BodyMatch::Ignore,
+        ..MatchOptions::default()
+    };
+    assert!(options.matches(&recorded, &request));
+
+    let mut request = create_request("John");
+    request.set_body(b"{\"name\": \"John\", \"age\": 30}".to_vec()).unwrap();
+    let exact = MatchOptions { body: BodyMatch::Exact, ..MatchOptions::default() };
+    assert!(!exact.matches(&recorded, &request));
+    assert!(default.matches(&recorded, &request));
+}
+
+#[test]
+fn open_invalid() {
+    let path = cassette_path("invalid");
+    fs::write(&path, "not a HAR file").unwrap();
+    let result = Cassette::open(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(result.is_err());
+}
@@ -0,0 +1,40 @@
+use crate::*;
+use crate::conditional::*;
+
+#[test]
+fn entity_tags() {
+    assert_eq!(EntityTag::parse("\"abc\""), Some(EntityTag::strong("abc")));
+    assert_eq!(EntityTag::parse(" W/\"\" "), Some(EntityTag::weak("")));
+    assert_eq!(EntityTag::parse("abc"), None);
+    assert_eq!(EntityTag::parse("\"a b\""), None);
+    assert_eq!(EntityTag::weak("1").to_string(), "W/\"1\"");
+
+    assert!(EntityTag::strong("1").strong_eq(&EntityTag::strong("1")));
+    assert!(!EntityTag::weak("1").strong_eq(&EntityTag::weak("1")));
+    assert!(EntityTag::weak("1").weak_eq(&EntityTag::strong("1")));
+}
+
+#[test]
+fn if_match() {
+    let current = EntityTag::strong("v2");
+    let mut request = Request::patch("http://service.com/docs/1");
+    assert!(request.if_match_satisfied(Some(&current)));
+    assert!(request.if_match_satisfied(None));
+
+    request.insert_header(IF_MATCH, "\"v1\", \"v,2\", \"v2\"");
+    assert!(request.if_match_satisfied(Some(&current)));
+    assert!(!request.if_match_satisfied(None));
+
+    request.require_etag_match(&EntityTag::weak("v2"));
+    assert!(!request.if_match_satisfied(Some(&current)));
+
+    request.insert_header(IF_MATCH, "*");
+    assert!(request.if_match_satisfied(Some(&current)));
+    assert!(!request.if_match_satisfied(None));
+
+    let mut response = Response::new(HTTP_200_OK);
+    assert!(response.etag().is_none());
+    response.set_etag(&current);
+    assert_eq!(response.headers().get(ETAG), Some("\"v2\""));
+    assert_eq!(response.etag(), Some(current));
+}
@@ -0,0 +1,194 @@
+use crate::*;
+use crate::connection::{Connection, KeepAlive, Role};
+use std::io::ErrorKind;
+use std::time::Duration;
+
+#[test]
+fn keep_alive_defaults() {
+    let mut request = Request::get("http://service.com/");
+    assert!(request.is_keep_alive());
+    request.insert_header("Connection", "Close");
+    assert!(!request.is_keep_alive());
+
+    let mut request = Request::get("http://service.com/");
+    request.set_version(HttpVersion::Http10);
+    assert!(!request.is_keep_alive());
+    request.insert_header("Connection", "Keep-Alive");
+    assert!(request.is_keep_alive());
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_version(HttpVersion::Http2);
+    assert!(response.is_keep_alive());
+}
+
+#[test]
+fn keep_alive_params() {
+    assert_eq!(KeepAlive::parse("timeout=5, max=1000"),
+               KeepAlive { timeout: Some(Duration::from_secs(5)), max: Some(1000) });
+    assert_eq!(KeepAlive::parse("max=x, other=1, timeout"), KeepAlive::default());
+    assert!(Request::get("http://service.com/").keep_alive().is_none());
+}
+
+#[test]
+fn strip_hop_by_hop() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("Connection", "X-Internal, close")
+            .insert_header("X-Internal", "secret")
+            .insert_header("Transfer-Encoding", "chunked")
+            .insert_header("Proxy-Connection", "keep-alive")
+            .insert_header("Content-Type", "text/plain");
+    response.insert_proxy_auth_header("Basic realm=\"proxy\"");
+
+    response.strip_hop_by_hop_headers();
+    assert_eq!(response.headers().iter().collect::<Vec<_>>(), vec![("Content-Type", "text/plain")]);
+    assert!(response.proxy_auth_headers().is_empty());
+}
+
+#[test]
+fn version_from_wire() {
+    let request = wire::parse_request(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+    assert_eq!(request.version(), HttpVersion::Http10);
+    assert!(!request.is_keep_alive());
+    assert!(wire::request_to_bytes(&request).unwrap().starts_with(b"GET / HTTP/1.0\r\n"));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_version(HttpVersion::Http2);
+    assert!(wire::response_to_bytes(&response).unwrap().starts_with(b"HTTP/1.1 200 OK\r\n"));
+    assert_eq!("HTTP/2".parse::<HttpVersion>().unwrap(), HttpVersion::Http2);
+    assert_eq!(HttpVersion::Http3.to_string(), "HTTP/3");
+}
+
+#[test]
+fn server_connection() {
+    let data = b"\r\nPOST /users HTTP/1.1\r\nContent-Length: 4\r\n\r\nJohn\
+                 PUT /users/1 HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nAnn\r\n0\r\n\r\n\
+                 GET /users HTTP/1.1\r\nConnection: close\r\n\r\n\
+                 GET /ignored HTTP/1.1\r\n\r\n";
+    let mut server = Connection::server();
+    assert_eq!(server.role(), Role::Server);
+    let mut requests = Vec::new();
+    for byte in data.iter() {
+        server.receive(&[*byte]);
+        while let Some(request) = server.next_request().unwrap() {
+            requests.push(request);
+        }
+    }
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].body().unwrap(), b"John");
+    assert_eq!(requests[1].body().unwrap(), b"Ann");
+    assert_eq!(requests[2].url(), "/users");
+    assert_eq!(server.pending_requests(), 3);
+    assert!(!server.is_open() && !server.is_closed());
+
+    let mut response = Response::new(HTTP_201_CREATED);
+    response.set_body(b"1".to_vec()).unwrap();
+    assert!(server.send_response(&response).unwrap().ends_with(b"\r\n\r\n1"));
+    server.send_response(&Response::new(HTTP_204_NO_CONTENT)).unwrap();
+    assert!(!server.is_closed());
+    server.send_response(&Response::new(HTTP_200_OK)).unwrap();
+    assert!(server.is_closed());
+    assert_eq!(server.send_response(&Response::new(HTTP_200_OK)).err().unwrap().kind(), ErrorKind::NotConnected);
+    assert!(server.send_request(&Request::get("/")).is_err());
+
+    let mut server = Connection::server();
+    server.receive(b"HEAD /users HTTP/1.1\r\n\r\n");
+    assert_eq!(server.next_request().unwrap().unwrap().method(), HttpMethod::HEAD);
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(b"ignored".to_vec()).unwrap();
+    assert!(server.send_response(&response).unwrap().ends_with(b"Content-Length: 7\r\n\r\n"));
+    assert_eq!(server.send_response(&response).err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    server.receive(b"GET / HTTP/1.1\r\nBad Header\r\n\r\n");
+    assert!(server.next_request().is_err());
+    assert!(server.is_closed());
+
+    let mut server = Connection::server();
+    server.receive(b"GET / HTTP/1.1\r\n");
+    assert!(server.next_request().unwrap().is_none());
+    server.receive_eof();
+    assert_eq!(server.next_request().err().unwrap().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn client_connection() {
+    let mut client = Connection::client();
+    assert!(client.next_response().unwrap().is_none());
+    client.send_request(&Request::head("http://service.com/users")).unwrap();
+    client.send_request(&Request::post("http://service.com/users")).unwrap();
+    assert_eq!(client.pending_requests(), 2);
+
+    client.receive(b"HTTP/1.1 200 OK\r\nContent-Length: 120\r\n\r\n\
+                     HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nok");
+    assert_eq!(client.next_response().unwrap().unwrap().body(), None);
+    assert_eq!(client.next_response().unwrap().unwrap().status_code(), HTTP_100_CONTINUE);
+    assert_eq!(client.next_response().unwrap().unwrap().body().unwrap(), b"ok");
+    assert_eq!(client.pending_requests(), 0);
+    assert!(client.is_open());
+
+    // Response ended by the connection
+    client.send_request(&Request::get("http://service.com/users")).unwrap();
+    client.receive(b"HTTP/1.1 200 OK\r\n\r\npartial");
+    assert!(client.next_response().unwrap().is_none());
+    client.receive(b" body");
+    client.receive_eof();
+    assert_eq!(client.next_response().unwrap().unwrap().body().unwrap(), b"partial body");
+    assert!(client.is_closed());
+    assert_eq!(client.send_request(&Request::get("http://service.com/")).err().unwrap().kind(), ErrorKind::NotConnected);
+
+    let mut client = Connection::client();
+    let mut request = Request::get("http://service.com/chat");
+    request.insert_header("Connection", "Upgrade").insert_header("Upgrade", "websocket");
+    client.send_request(&request).unwrap();
+    client.receive(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n\x81\x02hi");
+    assert_eq!(client.next_response().unwrap().unwrap().status_code(), HTTP_101_SWITCHING_PROTOCOLS);
+    assert!(client.is_upgraded());
+    assert!(client.next_response().unwrap().is_none());
+    assert_eq!(client.take_buffered(), b"\x81\x02hi");
+
+    let mut client = Connection::client();
+    let mut request = Request::get("http://service.com/");
+    request.insert_header("Connection", "close");
+    client.send_request(&request).unwrap();
+    assert!(!client.is_open());
+    client.receive(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+    client.next_response().unwrap().unwrap();
+    assert!(client.is_closed());
+}
+
+#[test]
+fn received_limits() {
+    let mut server = Connection::server();
+    server.set_max_head_size(64);
+    assert_eq!(server.max_head_size(), 64);
+    server.receive(b"GET / HTTP/1.1\r\n");
+    for _ in 0..3 {
+        assert!(server.next_request().unwrap().is_none());
+        server.receive(b"X-Padding: 0123456789\r\n");
+    }
+    assert_eq!(server.next_request().err().unwrap().kind(), ErrorKind::InvalidData);
+    assert!(server.is_closed());
+
+    let mut server = Connection::server();
+    server.set_max_head_size(64);
+    server.receive(format!("GET / HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "0".repeat(64)).as_bytes());
+    assert_eq!(server.next_request().err().unwrap().kind(), ErrorKind::InvalidData);
+
+    // Bodies received in small parts are scanned once
+    let mut data = b"POST /bulk HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+    for _ in 0..20_000 {
+        data.extend_from_slice(b"2\r\nab\r\n");
+    }
+    data.extend_from_slice(b"0\r\nX-Trailer: 1\r\n\r\nPUT /item HTTP/1.1\r\nContent-Length: 40000\r\n\r\n");
+    data.extend(std::iter::repeat_n(b'c', 40_000));
+    let mut server = Connection::server();
+    let mut requests = Vec::new();
+    for part in data.chunks(5) {
+        server.receive(part);
+        while let Some(request) = server.next_request().unwrap() {
+            requests.push(request);
+        }
+    }
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body().unwrap().len(), 40_000);
+    assert_eq!(requests[1].body().unwrap(), &[b'c'; 40_000][..]);
+}
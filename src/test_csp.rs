@@ -0,0 +1,46 @@
+use crate::*;
+use crate::csp::{Csp, Source};
+use crate::csp::Source::*;
+
+#[test]
+fn build() {
+    let policy = Csp::new()
+        .default_src([None])
+        .script_src([Source::from("'self'"), Nonce("r4nd0m".to_string()), StrictDynamic])
+        .img_src(["data:", "https://*.example.com"])
+        .frame_ancestors([Self_])
+        .report_to("csp-endpoint")
+        .default_src([Self_]);
+
+    assert_eq!(policy.build().unwrap(), "default-src 'self'; script-src 'self' 'nonce-r4nd0m' 'strict-dynamic'; \
+        img-src data: https://*.example.com; frame-ancestors 'self'; report-to csp-endpoint");
+}
+
+#[test]
+fn build_invalid() {
+    assert!(Csp::new().script_src(["https://cdn.example.com; object-src *"]).build().is_err());
+    assert!(Csp::new().script_src([Nonce("a'b".to_string())]).build().is_err());
+    assert!(Csp::new().directive("script src", [Self_]).build().is_err());
+}
+
+#[test]
+fn parse() {
+    let policy = Csp::parse("Default-Src 'SELF'; script-src 'sha256-abc=' 'unsafe-eval'  https:;; script-src *; bogus 'foo'");
+
+    assert_eq!(policy.directives().collect::<Vec<&str>>(), vec!["default-src", "script-src", "bogus"]);
+    assert_eq!(policy.sources("default-src").unwrap(), &[Self_]);
+    assert_eq!(policy.sources("script-src").unwrap(),
+               &[Hash("sha256".to_string(), "abc=".to_string()), UnsafeEval, Host("https:".to_string())]);
+    assert_eq!(policy.sources("bogus").unwrap(), &[Host("'foo'".to_string())]);
+    assert!(policy.sources("img-src").is_none());
+}
+
+#[test]
+fn security_preset() {
+    let policy = Csp::new().default_src([Self_]).build().unwrap();
+    let mut response = Response::new(HTTP_200_OK);
+    response.apply_security_headers(security::SecurityPreset::default().with_content_security_policy(Some(&policy)));
+
+    assert_eq!(response.headers().get("content-security-policy"), Some("default-src 'self'"));
+    assert_eq!(response.content_security_policy().unwrap().sources("default-src").unwrap(), &[Self_]);
+}
@@ -0,0 +1,74 @@
+use crate::*;
+
+#[test]
+fn curl_get() {
+    let request = Request::from_curl("curl https://service.com/users").unwrap();
+    assert_eq!(request.method(), HttpMethod::GET);
+    assert_eq!(request.url(), "https://service.com/users");
+    assert!(request.body().is_none());
+}
+
+#[test]
+fn curl_headers() {
+    let request = Request::from_curl(r#"curl -sSL -XDELETE "https://service.com/users/1" \
+        -H "Accept: application/json" --header 'X-Trace:  a b ' -A "agent/1.0"
+        -u john:secret --cookie 'session=1234; theme=dark'"#).unwrap();
+
+    assert_eq!(request.method(), HttpMethod::DELETE);
+    assert_eq!(request.headers().get("accept"), Some("application/json"));
+    assert_eq!(request.headers().get("x-trace"), Some("a b"));
+    assert_eq!(request.headers().get("user-agent"), Some("agent/1.0"));
+    assert_eq!(request.headers().get("Authorization"), Some("Basic am9objpzZWNyZXQ="));
+    assert_eq!(request.cookies().get("session"), Some("1234"));
+    assert_eq!(request.cookies().get("theme"), Some("dark"));
+}
+
+#[test]
+fn curl_data() {
+    let request = Request::from_curl("curl https://service.com/login -d user=john --data-urlencode 'pass=a b&c'").unwrap();
+    assert_eq!(request.method(), HttpMethod::POST);
+    assert_eq!(request.headers().get("Content-Type"), Some("application/x-www-form-urlencoded"));
+    assert_eq!(request.body().unwrap(), b"user=john&pass=a%20b%26c");
+
+    let request = Request::from_curl("curl -G https://service.com/search?lang=en -d q=rust").unwrap();
+    assert_eq!(request.method(), HttpMethod::GET);
+    assert_eq!(request.params().get("lang"), Some("en"));
+    assert_eq!(request.params().get("q"), Some("rust"));
+    assert!(request.body().is_none());
+
+    let request = Request::from_curl(r#"curl --json '{"a": 1}' https://service.com/items"#).unwrap();
+    assert_eq!(request.headers().get("Content-Type"), Some("application/json"));
+    assert_eq!(request.json().unwrap()["a"], 1);
+}
+
+#[test]
+fn curl_form() {
+    let request = Request::from_curl("curl -F name=John -F 'surname=Smith Jr' https://service.com/upload").unwrap();
+    assert_eq!(request.method(), HttpMethod::POST);
+
+    let content_type = request.headers().get("Content-Type").unwrap();
+    assert!(content_type.starts_with("multipart/form-data; boundary="));
+    let boundary = &content_type["multipart/form-data; boundary=".len()..];
+
+    let body = String::from_utf8(request.body().unwrap().to_vec()).unwrap();
+    assert!(body.starts_with(&format!("--{}\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn\r\n", boundary)));
+    assert!(body.contains("name=\"surname\"\r\n\r\nSmith Jr\r\n"));
+    assert!(body.ends_with(&format!("--{}--\r\n", boundary)));
+}
+
+#[test]
+fn curl_invalid() {
+    assert!(Request::from_curl("curl -X").is_err());
+    assert!(Request::from_curl("curl -H 'Accept' https://service.com").is_err());
+    assert!(Request::from_curl("curl 'https://service.com").is_err());
+    assert!(Request::from_curl("curl -d @body.json https://service.com").is_err());
+    assert!(Request::from_curl("curl --unknown https://service.com").is_err());
+    assert!(Request::from_curl("curl -s").is_err());
+}
+
+#[test]
+fn curl_too_large() {
+    let command = format!("curl -d {} https://service.com/upload", "x".repeat(default_max_body_size() + 1));
+    let error = Request::from_curl(&command).unwrap_err();
+    assert!(error.get_ref().and_then(|e| e.downcast_ref::<BodyTooLarge>()).is_some());
+}
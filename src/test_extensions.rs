@@ -0,0 +1,45 @@
+use crate::*;
+
+#[derive(Clone, PartialEq, Debug)]
+struct User(String);
+
+#[derive(Clone, PartialEq, Debug)]
+struct Attempt(u32);
+
+#[test]
+fn insert_get_remove() {
+    let mut extensions = Extensions::new();
+    assert!(extensions.is_empty());
+
+    assert_eq!(extensions.insert(User("john".to_string())), None);
+    assert_eq!(extensions.insert(Attempt(1)), None);
+    assert_eq!(extensions.insert(Attempt(2)), Some(Attempt(1)));
+    assert_eq!(extensions.len(), 2);
+
+    assert_eq!(extensions.get::<User>(), Some(&User("john".to_string())));
+    extensions.get_mut::<Attempt>().unwrap().0 += 1;
+    assert_eq!(extensions.get::<Attempt>(), Some(&Attempt(3)));
+    assert!(!extensions.contains::<String>());
+
+    assert_eq!(extensions.remove::<User>(), Some(User("john".to_string())));
+    assert_eq!(extensions.get::<User>(), None);
+
+    extensions.clear();
+    assert!(extensions.is_empty());
+}
+
+#[test]
+fn message_extensions() {
+    let mut request = Request::get("https://service.com/users");
+    request.extensions_mut().insert(User("john".to_string()));
+
+    let cloned = request.clone();
+    request.extensions_mut().get_mut::<User>().unwrap().0.push_str("ny");
+
+    assert_eq!(cloned.extensions().get::<User>(), Some(&User("john".to_string())));
+    assert_eq!(request.extensions().get::<User>(), Some(&User("johnny".to_string())));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.extensions_mut().insert(Attempt(1));
+    assert!(response.extensions().contains::<Attempt>());
+}
@@ -0,0 +1,169 @@
+use crate::*;
+use crate::fixtures::*;
+use std::fs;
+use std::io::ErrorKind;
+
+#[test]
+fn load_files() {
+    let dir = std::env::temp_dir().join(format!("wrequest-fixtures-{}", std::process::id()));
+    fs::create_dir_all(dir.join("bodies")).unwrap();
+    fs::write(dir.join("bodies/john.json"), "{\"name\": \"John\"}").unwrap();
+    fs::write(dir.join("create.json"), r#"{
+        "method": "POST",
+        "url": "https://service.com/users",
+        "headers": {"Content-Type": "application/json", "Accept": "*/*"},
+        "params": {"notify": "true"},
+        "cookies": {"session": "1"},
+        "body_file": "bodies/john.json"
+    }"#).unwrap();
+    fs::write(dir.join("created.json"), r#"{"status": 201, "headers": {"Location": "/users/1"}, "body": "{}"}"#).unwrap();
+    fs::write(dir.join("list.json"), r#"[{"url": "https://service.com/a"}, {"url": "https://service.com/b", "method": "DELETE"}]"#).unwrap();
+
+    let request = load_request(dir.join("create.json"), |text| serde_json::from_str(text)).unwrap();
+    assert_eq!(request.method(), HttpMethod::POST);
+    assert_eq!(request.url(), "https://service.com/users");
+    assert_eq!(request.headers().iter().collect::<Vec<(&str, &str)>>(), vec![("Content-Type", "application/json"), ("Accept", "*/*")]);
+    assert_eq!(request.params().get("notify"), Some("true"));
+    assert_eq!(request.cookies().get("session"), Some("1"));
+    assert_eq!(request.json().unwrap()["name"], "John");
+
+    let response = load_response(dir.join("created.json"), |text| serde_json::from_str(text)).unwrap();
+    assert_eq!(response.status_code(), HTTP_201_CREATED);
+    assert_eq!(response.headers().get("location"), Some("/users/1"));
+    assert_eq!(response.body().unwrap(), b"{}");
+
+    let requests = load_requests(dir.join("list.json"), |text| serde_json::from_str(text)).unwrap();
+    assert_eq!(requests.iter().map(|r| r.method()).collect::<Vec<HttpMethod>>(), vec![HttpMethod::GET, HttpMethod::DELETE]);
+    assert!(requests[0].body().is_none());
+
+    fs::write(dir.join("broken.json"), "{\"url\": ").unwrap();
+    assert_eq!(load_request(dir.join("broken.json"), |text| serde_json::from_str(text)).err().unwrap().kind(), ErrorKind::InvalidData);
+    assert_eq!(load_request(dir.join("missing.json"), |text| serde_json::from_str(text)).err().unwrap().kind(), ErrorKind::NotFound);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_fixtures() {
+    let fixture: RequestFixture = serde_json::from_str(r#"{"url": "/", "body": "a", "body_file": "a.txt"}"#).unwrap();
+    assert_eq!(fixture.into_request(".").err().unwrap().kind(), ErrorKind::InvalidData);
+
+    let fixture: RequestFixture = serde_json::from_str(r#"{"url": "/", "method": "FETCH"}"#).unwrap();
+    assert_eq!(fixture.into_request(".").err().unwrap().kind(), ErrorKind::InvalidData);
+
+    let fixture: ResponseFixture = serde_json::from_str(r#"{"status": 200, "body_file": "missing.txt"}"#).unwrap();
+    assert_eq!(fixture.into_response(std::env::temp_dir()).err().unwrap().kind(), ErrorKind::NotFound);
+
+    assert!(serde_json::from_str::<ResponseFixture>(r#"{"headers": {}}"#).is_err());
+
+    let fixture: ResponseFixture = serde_json::from_str(r#"{"status": 9999}"#).unwrap();
+    assert_eq!(fixture.into_response(".").err().unwrap().kind(), ErrorKind::InvalidData);
+}
+
+fn pairs<'a, I: Iterator<Item = (&'a str, &'a str)>>(iter: I) -> Vec<(&'a str, &'a str)> {
+    iter.collect()
+}
+
+#[test]
+fn body_file_too_large() {
+    let dir = std::env::temp_dir().join(format!("wrequest-large-fixture-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // Sparse file, larger than the limit without taking space
+    fs::File::create(dir.join("large.bin")).unwrap().set_len(default_max_body_size() as u64 + 1).unwrap();
+
+    let fixture: ResponseFixture = serde_json::from_str(r#"{"status": 200, "body_file": "large.bin"}"#).unwrap();
+    let error = fixture.into_response(&dir).err().unwrap();
+    let too_large = error.get_ref().and_then(|e| e.downcast_ref::<BodyTooLarge>()).unwrap();
+    assert_eq!(too_large.size, Some(default_max_body_size() + 1));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn yaml_fixtures() {
+    let fixture: RequestFixture = serde_yaml::from_str("
+method: POST
+url: https://service.com/users
+headers:
+  Content-Type: application/json
+  X-Trace: a b
+params:
+  notify: \"true\"
+cookies:
+  session: \"1\"
+body: '{\"name\": \"John\"}'
+").unwrap();
+    let request = fixture.into_request(".").unwrap();
+    assert_eq!(request.method(), HttpMethod::POST);
+    assert_eq!(request.headers().iter().collect::<Vec<(&str, &str)>>(), vec![("Content-Type", "application/json"), ("X-Trace", "a b")]);
+    assert_eq!(request.params().get("notify"), Some("true"));
+    assert_eq!(request.cookies().get("session"), Some("1"));
+    assert_eq!(request.json().unwrap()["name"], "John");
+
+    let fixture: ResponseFixture = serde_yaml::from_str("status: 201\nheaders:\n  Location: /users/1\nbody: created\n").unwrap();
+    let response = fixture.into_response(".").unwrap();
+    assert_eq!(response.status_code(), HTTP_201_CREATED);
+    assert_eq!(response.headers().get("location"), Some("/users/1"));
+    assert_eq!(response.body().unwrap(), b"created");
+
+    // Messages, header maps and key-value maps round-trip
+    let parsed: Request = serde_yaml::from_str(&serde_yaml::to_string(&request).unwrap()).unwrap();
+    assert_eq!(pairs(parsed.headers().iter()), pairs(request.headers().iter()));
+    assert_eq!(pairs(parsed.params().iter()), pairs(request.params().iter()));
+    assert_eq!(pairs(parsed.cookies().iter()), pairs(request.cookies().iter()));
+    assert_eq!(parsed.body(), request.body());
+    let parsed: Response = serde_yaml::from_str(&serde_yaml::to_string(&response).unwrap()).unwrap();
+    assert_eq!(parsed.status_code(), HTTP_201_CREATED);
+    assert_eq!(pairs(parsed.headers().iter()), pairs(response.headers().iter()));
+    assert_eq!(parsed.body(), response.body());
+    let headers: HeaderMap = serde_yaml::from_str(&serde_yaml::to_string(request.headers()).unwrap()).unwrap();
+    assert_eq!(pairs(headers.iter()), pairs(request.headers().iter()));
+    let params: KeyValueMap = serde_yaml::from_str(&serde_yaml::to_string(request.params()).unwrap()).unwrap();
+    assert_eq!(pairs(params.iter()), pairs(request.params().iter()));
+}
+
+#[test]
+fn toml_fixtures() {
+    let fixture: RequestFixture = toml::from_str(r#"
+method = "PUT"
+url = "https://service.com/users/1"
+body = "John"
+
+[headers]
+Content-Type = "text/plain"
+Accept = "*/*"
+
+[params]
+notify = "true"
+
+[cookies]
+session = "1"
+"#).unwrap();
+    let request = fixture.into_request(".").unwrap();
+    assert_eq!(request.method(), HttpMethod::PUT);
+    assert_eq!(request.headers().iter().collect::<Vec<(&str, &str)>>(), vec![("Content-Type", "text/plain"), ("Accept", "*/*")]);
+    assert_eq!(request.params().get("notify"), Some("true"));
+    assert_eq!(request.cookies().get("session"), Some("1"));
+    assert_eq!(request.body().unwrap(), b"John");
+
+    let fixture: ResponseFixture = toml::from_str("status = 404\nbody = 'missing'\n\n[headers]\nContent-Type = 'text/plain'\n").unwrap();
+    let response = fixture.into_response(".").unwrap();
+    assert_eq!(response.status_code(), HTTP_404_NOT_FOUND);
+    assert_eq!(response.headers().get("content-type"), Some("text/plain"));
+    assert_eq!(response.body().unwrap(), b"missing");
+
+    // Messages, header maps and key-value maps round-trip
+    let parsed: Request = toml::from_str(&toml::to_string(&request).unwrap()).unwrap();
+    assert_eq!(pairs(parsed.headers().iter()), pairs(request.headers().iter()));
+    assert_eq!(pairs(parsed.params().iter()), pairs(request.params().iter()));
+    assert_eq!(pairs(parsed.cookies().iter()), pairs(request.cookies().iter()));
+    assert_eq!(parsed.body(), request.body());
+    let parsed: Response = toml::from_str(&toml::to_string(&response).unwrap()).unwrap();
+    assert_eq!(parsed.status_code(), HTTP_404_NOT_FOUND);
+    assert_eq!(pairs(parsed.headers().iter()), pairs(response.headers().iter()));
+    assert_eq!(parsed.body(), response.body());
+    let headers: HeaderMap = toml::from_str(&toml::to_string(request.headers()).unwrap()).unwrap();
+    assert_eq!(pairs(headers.iter()), pairs(request.headers().iter()));
+    let params: KeyValueMap = toml::from_str(&toml::to_string(request.params()).unwrap()).unwrap();
+    assert_eq!(pairs(params.iter()), pairs(request.params().iter()));
+}
@@ -0,0 +1,71 @@
+use crate::*;
+use crate::freshness::*;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+#[test]
+fn http_dates() {
+    let expected = Some(at(784111777));
+    assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), expected);
+    assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), expected);
+    assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), expected);
+    assert_eq!(parse_http_date("0"), None);
+    assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(UNIX_EPOCH));
+}
+
+#[test]
+fn current_age() {
+    let request_time = at(1_000_000);
+    let response_time = at(1_000_004);
+
+    // No headers, the response delay plus the resident time
+    let mut response = Response::new(HTTP_200_OK);
+    assert_eq!(response.current_age(request_time, response_time, at(1_000_010)), Duration::from_secs(10));
+
+    // Apparent age from a clock ahead of ours is ignored
+    response.insert_header("Date", "Mon, 12 Jan 1970 13:46:50 GMT");
+    assert_eq!(response.date(), Some(at(1_000_010)));
+    assert_eq!(response.current_age(request_time, response_time, at(1_000_010)), Duration::from_secs(10));
+
+    // Apparent age larger than the corrected Age
+    response.insert_header("Date", "Mon, 12 Jan 1970 13:45:00 GMT");
+    response.insert_header("Age", "10");
+    assert_eq!(response.current_age(request_time, response_time, response_time), Duration::from_secs(104));
+
+    // Corrected Age larger than the apparent age
+    response.insert_header("Age", "500");
+    assert_eq!(response.current_age(request_time, response_time, at(1_000_014)), Duration::from_secs(514));
+
+    // Malformed Age values are ignored, huge ones are capped
+    response.insert_header("Age", "-5");
+    assert_eq!(response.age(), None);
+    response.insert_header("Age", "99999999999999999999999");
+    assert_eq!(response.age(), Some(Duration::from_secs(u64::MAX)));
+    assert_eq!(response.current_age(request_time, response_time, at(1_000_014)), Duration::MAX);
+}
+
+#[test]
+fn freshness() {
+    let request_time = at(1_000_000);
+    let response_time = at(1_000_000);
+
+    let mut response = Response::new(HTTP_200_OK);
+    assert_eq!(response.freshness_lifetime(), None);
+    assert_eq!(response.freshness_remaining(request_time, response_time, at(1_000_100)), None);
+
+    response.insert_header("Date", "Mon, 12 Jan 1970 13:46:40 GMT")
+            .insert_header("Expires", "Mon, 12 Jan 1970 13:56:40 GMT");
+    assert_eq!(response.freshness_lifetime(), Some(Duration::from_secs(600)));
+    assert_eq!(response.freshness_remaining(request_time, response_time, at(1_000_100)), Some(Duration::from_secs(500)));
+    assert_eq!(response.freshness_remaining(request_time, response_time, at(1_001_000)), Some(Duration::ZERO));
+
+    response.insert_header("Cache-Control", "no-transform, MAX-AGE=\"60\"");
+    assert_eq!(response.freshness_lifetime(), Some(Duration::from_secs(60)));
+
+    response.insert_header("Cache-Control", "no-cache");
+    response.insert_header("Expires", "0");
+    assert_eq!(response.freshness_lifetime(), Some(Duration::ZERO));
+}
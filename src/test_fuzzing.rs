@@ -0,0 +1,62 @@
+use crate::*;
+use crate::fuzzing::*;
+use crate::wire::*;
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+
+#[test]
+fn arbitrary_messages() {
+    let data: Vec<u8> = (0..2048u32).map(|i| (i.wrapping_mul(2654435761) >> 13) as u8).collect();
+    let mut u = Unstructured::new(&data);
+    for _ in 0..20 {
+        let headers = HeaderMap::arbitrary(&mut u).unwrap();
+        assert!(headers.iter().all(|(name, value)| is_valid_header_name(name) && is_valid_header_value(value)));
+        let request = Request::arbitrary(&mut u).unwrap();
+        assert!(request.url().starts_with("http"));
+        let response = Response::arbitrary(&mut u).unwrap();
+        assert!((100..600).contains(&response.status_code()));
+    }
+
+    // Same bytes, same message
+    let first = Request::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+    let second = Request::arbitrary_take_rest(Unstructured::new(&data)).unwrap();
+    assert_eq!(format!("{:?}", first), format!("{:?}", second));
+    assert!(Request::arbitrary_take_rest(Unstructured::new(&[])).is_ok());
+}
+
+proptest! {
+    #[test]
+    fn request_wire_roundtrip(request in any::<Request>()) {
+        let parsed = parse_request(&request_to_bytes(&request).unwrap()).unwrap();
+        prop_assert_eq!(parsed.method(), request.method());
+        prop_assert_eq!(parsed.version(), request.version());
+        prop_assert_eq!(parsed.body(), request.body());
+        prop_assert_eq!(parsed.params().len(), request.params().len());
+        prop_assert_eq!(parsed.cookies().len(), request.cookies().len());
+        for (name, value) in request.headers().iter() {
+            prop_assert_eq!(parsed.headers().get(name), Some(value));
+        }
+    }
+
+    #[test]
+    fn response_wire_roundtrip(response in any::<Response>()) {
+        let parsed = parse_response(&response_to_bytes(&response).unwrap()).unwrap();
+        prop_assert_eq!(parsed.status_code(), response.status_code());
+        prop_assert_eq!(parsed.body(), response.body());
+        prop_assert_eq!(parsed.cookies().len(), response.cookies().len());
+        for (name, value) in response.headers().iter() {
+            prop_assert_eq!(parsed.headers().get(name), Some(value));
+        }
+    }
+
+    #[test]
+    fn body_roundtrip(body in bodies(), headers in header_maps()) {
+        let mut response = Response::new(HTTP_200_OK);
+        for (name, value) in headers.iter() {
+            response.insert_header(name.to_string(), value.to_string());
+        }
+        response.set_body(body.clone()).unwrap();
+        let parsed = parse_response(&response_to_bytes(&response).unwrap()).unwrap();
+        prop_assert_eq!(parsed.body().unwrap(), &body[..]);
+    }
+}
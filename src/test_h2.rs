@@ -0,0 +1,69 @@
+use crate::*;
+use wcookie::SetCookie;
+
+#[test]
+fn request_to_h2() {
+    let mut request = Request::post("https://user@Service.com:8443?lang=en#top");
+    request.insert_header("Host", "ignored.com")
+           .insert_header("Content-Type", "application/json")
+           .insert_header("Transfer-Encoding", "chunked")
+           .insert_header("TE", "trailers")
+           .insert_header("Keep-Alive", "timeout=5");
+    request.insert_cookie("session", "1234");
+
+    let headers = request.to_h2_headers().unwrap();
+    let expected: Vec<(String, String)> = [
+        (":method", "POST"), (":scheme", "https"), (":authority", "Service.com:8443"), (":path", "/?lang=en"),
+        ("content-type", "application/json"), ("te", "trailers"), ("cookie", "session=1234")
+    ].iter().map(|(n, v)| (n.to_string(), v.to_string())).collect();
+    assert_eq!(headers, expected);
+
+    assert!(Request::get("/relative").to_h2_headers().is_err());
+    let connect = Request::connect("proxy.com:443").to_h2_headers().unwrap();
+    assert_eq!(connect, vec![(":method".to_string(), "CONNECT".to_string()), (":authority".to_string(), "proxy.com:443".to_string())]);
+}
+
+#[test]
+fn request_from_h2() {
+    let request = Request::from_h2_headers([
+        (":method", "GET"), (":scheme", "http"), (":path", "/a?x=1"), ("host", "service.com"),
+        ("cookie", "a=1"), ("cookie", "b=2"), ("accept", "text/html"), ("accept", "*/*")
+    ]).unwrap();
+    assert_eq!(request.url(), "http://service.com/a");
+    assert_eq!(request.version(), HttpVersion::Http2);
+    assert_eq!(request.params().get("x"), Some("1"));
+    assert_eq!(request.cookies().get("b"), Some("2"));
+    assert_eq!(request.headers().get("Accept"), Some("text/html, */*"));
+
+    let malformed: [&[(&str, &str)]; 7] = [
+        &[(":method", "GET"), (":scheme", "http")],
+        &[(":method", "GET"), (":scheme", "http"), (":path", "/"), (":path", "/")],
+        &[(":method", "GET"), ("accept", "*/*"), (":scheme", "http"), (":path", "/")],
+        &[(":method", "GET"), (":scheme", "http"), (":path", "/"), ("Accept", "*/*")],
+        &[(":method", "GET"), (":scheme", "http"), (":path", "/"), ("connection", "close")],
+        &[(":method", "GET"), (":scheme", "http"), (":path", "/"), (":status", "200")],
+        &[(":method", "CONNECT"), (":authority", "a.com:443"), (":path", "/")]
+    ];
+    for headers in malformed {
+        assert!(Request::from_h2_headers(headers.iter().copied()).is_err(), "{:?}", headers);
+    }
+}
+
+#[test]
+fn response_h2() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("Content-Type", "text/plain")
+            .insert_header("Connection", "close");
+    response.insert_cookie(SetCookie::new("a", "1"))
+            .insert_cookie(SetCookie::new("b", "2"));
+
+    let headers = response.to_h2_headers();
+    assert_eq!(headers.len(), 4);
+    assert_eq!(headers[0], (":status".to_string(), "200".to_string()));
+
+    let received = Response::from_h2_headers(headers).unwrap();
+    assert_eq!(received.status_code(), HTTP_200_OK);
+    assert_eq!(received.cookies().len(), 2);
+    assert!(!received.headers().contains_key("connection"));
+    assert!(Response::from_h2_headers([("content-type", "text/plain")]).is_err());
+}
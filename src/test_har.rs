@@ -0,0 +1,120 @@
+use crate::*;
+use crate::har::{self, Timings};
+use wcookie::SetCookie;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn export1() {
+    let mut request = Request::post("https://service.com/users");
+    request.insert_param("client_id", "12 34")
+           .insert_cookie("session", "abcd")
+           .insert_header("Accept", "application/json");
+    request.set_body(b"{\"name\":\"John\"}".to_vec()).unwrap();
+    request.insert_header("Content-Type", "application/json");
+
+    let mut response = Response::new(HTTP_201_CREATED);
+    response.insert_header("Location", "https://service.com/users/1")
+            .set_body(vec![0xff, 0x00, 0x10]).unwrap();
+    let mut cookie = SetCookie::new("session", "efgh");
+    cookie.http_only = true;
+    response.insert_cookie(cookie);
+
+    let mut timings = Timings::new(UNIX_EPOCH + Duration::from_secs(1_650_000_000));
+    timings.dns = Some(Duration::from_millis(5));
+    timings.send = Duration::from_millis(10);
+    timings.wait = Duration::from_millis(100);
+    timings.receive = Duration::from_millis(20);
+
+    let document = json::parse(&har::export(&[(request, response, timings)])).unwrap();
+
+    assert_eq!(document["log"]["version"], "1.2");
+    let entry = &document["log"]["entries"][0];
+    assert_eq!(entry["startedDateTime"], "2022-04-15T05:20:00.000Z");
+    assert_eq!(entry["time"], 135.0);
+    assert_eq!(entry["timings"]["connect"], -1.0);
+
+    let har_request = &entry["request"];
+    assert_eq!(har_request["method"], "POST");
+    assert_eq!(har_request["url"], "https://service.com/users?client_id=12%2034");
+    assert_eq!(har_request["queryString"][0]["value"], "12 34");
+    assert_eq!(har_request["cookies"][0]["name"], "session");
+    assert_eq!(har_request["postData"]["mimeType"], "application/json");
+    assert_eq!(har_request["postData"]["text"], "{\"name\":\"John\"}");
+    assert_eq!(har_request["bodySize"], 15);
+
+    let har_response = &entry["response"];
+    assert_eq!(har_response["status"], 201);
+    assert_eq!(har_response["statusText"], "Created");
+    assert_eq!(har_response["redirectURL"], "https://service.com/users/1");
+    assert_eq!(har_response["cookies"][0]["httpOnly"], true);
+    assert_eq!(har_response["content"]["encoding"], "base64");
+    assert_eq!(har_response["content"]["text"], "/wAQ");
+}
+
+#[test]
+fn export_empty() {
+    let document = json::parse(&har::export(&[])).unwrap();
+    assert_eq!(document["log"]["creator"]["name"], "wrequest");
+    assert!(document["log"]["entries"].is_array());
+    assert_eq!(document["log"]["entries"].len(), 0);
+}
+
+#[test]
+fn import1() {
+    let mut request = Request::put("https://service.com/users/1");
+    request.insert_param("lang", "en")
+           .insert_cookie("session", "abcd")
+           .insert_header("Content-Type", "application/json");
+    request.set_body(b"{}".to_vec()).unwrap();
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(vec![0xca, 0xfe]).unwrap();
+    let mut cookie = SetCookie::new("session", "efgh");
+    cookie.path = Some("/".to_string());
+    cookie.secure = true;
+    response.insert_cookie(cookie);
+
+    let document = har::export(&[(request, response, Timings::new(UNIX_EPOCH))]);
+    let imported = har::import(&document).unwrap();
+
+    assert_eq!(imported.len(), 1);
+    let (request, response) = &imported[0];
+    assert_eq!(request.method(), HttpMethod::PUT);
+    assert_eq!(request.url(), "https://service.com/users/1");
+    assert_eq!(request.params().get("lang"), Some("en"));
+    assert_eq!(request.cookies().get("session"), Some("abcd"));
+    assert_eq!(request.headers().get("content-type"), Some("application/json"));
+    assert_eq!(request.body().unwrap(), b"{}");
+
+    assert_eq!(response.status_code(), HTTP_200_OK);
+    assert_eq!(response.body().unwrap(), &vec![0xca, 0xfe]);
+    let cookies = response.cookies();
+    assert_eq!(cookies[0].value, "efgh");
+    assert_eq!(cookies[0].path.as_deref(), Some("/"));
+    assert!(cookies[0].secure);
+}
+
+#[test]
+fn import_query_from_url() {
+    let document = r#"{"log": {"version": "1.2", "entries": [{
+        "request": {"method": "GET", "url": "https://service.com/search?q=a%20b&page=2#top", "headers": []},
+        "response": {"status": 204, "headers": [{"name": "X-Id", "value": "7"}], "content": {"size": 0}}
+    }]}}"#;
+
+    let imported = har::import(document).unwrap();
+    let (request, response) = &imported[0];
+    assert_eq!(request.url(), "https://service.com/search");
+    assert_eq!(request.params().get("q"), Some("a b"));
+    assert_eq!(request.params().get("page"), Some("2"));
+    assert!(request.body().is_none());
+    assert_eq!(response.headers().get("x-id"), Some("7"));
+    assert!(response.body().is_none());
+}
+
+#[test]
+fn import_invalid() {
+    assert!(har::import("{}").is_err());
+    assert!(har::import("not json").is_err());
+    let document = r#"{"log": {"entries": [{"request": {"method": "FETCH", "url": "/"}, "response": {"status": 200}}]}}"#;
+    assert!(har::import(document).is_err());
+}
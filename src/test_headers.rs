@@ -0,0 +1,253 @@
+use crate::*;
+
+#[test]
+fn validation() {
+    assert!(is_valid_header_name("X-Custom_Header.1"));
+    assert!(!is_valid_header_name(""));
+    assert!(!is_valid_header_name("X Custom"));
+    assert!(!is_valid_header_name("X-Custom:"));
+    assert!(!is_valid_header_name("X-Ñ"));
+
+    assert!(is_valid_header_value(""));
+    assert!(is_valid_header_value("text/html;\tcharset=\"utf-8\" ñ"));
+    assert!(!is_valid_header_value("a\r\nb"));
+    assert!(!is_valid_header_value("a\0b"));
+    assert!(!is_valid_header_value("a\x7f"));
+}
+
+#[test]
+fn strict_insert() {
+    let mut headers = HeaderMap::new();
+    assert!(!headers.try_insert("Accept", "*/*").unwrap());
+    assert!(headers.try_insert("accept", "text/html").unwrap());
+    assert_eq!(headers.try_insert("X Bad", "value").unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert!(headers.try_insert("X-Bad", "value\nInjected: yes").is_err());
+    assert!(!headers.contains_key("X-Bad"));
+
+    let mut request = Request::get("http://example.com");
+    assert!(request.try_insert_header("X-Bad", "a\rb").is_err());
+    request.try_insert_header("X-Good", "a b").unwrap()
+           .try_insert_header("X-Other", "c").unwrap();
+    assert_eq!(request.headers().get("x-other"), Some("c"));
+}
+
+#[test]
+fn lenient_insert() {
+    let mut headers = HeaderMap::new();
+    headers.insert("X Bad:", "value\r\nInjected: yes");
+    assert_eq!(headers.get("XBad"), Some("value  Injected: yes"));
+    assert!(!headers.contains_key("Injected"));
+
+    assert!(!headers.insert("(:)", "ignored"));
+    assert_eq!(headers.iter().count(), 1);
+}
+
+#[test]
+fn insertion_order_and_casing() {
+    let mut headers = HeaderMap::new();
+    headers.insert("Host", "example.com");
+    headers.insert("x-trace-ID", "1");
+    headers.insert("Accept", "*/*");
+    assert!(headers.insert("X-TRACE-id", "2"));
+
+    let entries: Vec<(&str, &str)> = headers.iter().collect();
+    assert_eq!(entries, vec![("Host", "example.com"), ("x-trace-ID", "2"), ("Accept", "*/*")]);
+    assert_eq!(headers.get("X-Trace-Id"), Some("2"));
+    assert_eq!(headers.len(), 3);
+
+    assert_eq!(headers.remove("HOST"), Some("example.com".to_string()));
+    assert_eq!(headers.remove("Host"), None);
+    assert_eq!(headers.iter().map(|(k, _)| k).collect::<Vec<&str>>(), vec!["x-trace-ID", "Accept"]);
+}
+
+#[test]
+fn from_vec() {
+    let headers = HeaderMap::from(vec![
+        ("B".to_string(), "1".to_string()),
+        ("A".to_string(), "2".to_string()),
+        ("b".to_string(), "3".to_string())
+    ]);
+    assert_eq!(headers.iter().collect::<Vec<(&str, &str)>>(), vec![("B", "3"), ("A", "2")]);
+
+    let headers = HeaderMap::from(&vec![("Z", "1"), ("Y", "2")]);
+    assert_eq!(headers.iter().collect::<Vec<(&str, &str)>>(), vec![("Z", "1"), ("Y", "2")]);
+    assert!(!headers.is_empty());
+}
+
+#[test]
+fn request_display_order() {
+    let mut request = Request::get("http://example.com");
+    request.insert_header("Host", "example.com")
+           .insert_header("Accept", "*/*")
+           .insert_header("User-Agent", "wrequest");
+    assert_eq!(request.to_string(), "GET http://example.com\nHost=example.com\nAccept=*/*\nUser-Agent=wrequest\n");
+}
+
+#[test]
+fn interned_names() {
+    use crate::header_name::{HeaderName, STANDARD_NAMES};
+
+    for (canonical, lowercase) in STANDARD_NAMES.iter() {
+        assert_eq!(HeaderName::new(*canonical).as_str(), *canonical);
+        assert_eq!(HeaderName::new(*lowercase).as_str(), *lowercase);
+        assert!(HeaderName::new(*lowercase).is_standard());
+    }
+
+    assert!(HeaderName::new("Content-Type").is_standard());
+    assert!(HeaderName::new("content-type").is_standard());
+    assert!(HeaderName::new("WWW-Authenticate").is_standard());
+    assert!(HeaderName::new("te").is_standard());
+    assert!(!HeaderName::new("CONTENT-TYPE").is_standard());
+    assert!(!HeaderName::new("X-Custom").is_standard());
+
+    assert_eq!(HeaderName::new("accept").as_str(), "accept");
+    assert_eq!(HeaderName::new("Accept").as_str(), "Accept");
+    assert_eq!(HeaderName::new("CONTENT-TYPE").as_str(), "CONTENT-TYPE");
+
+    let mut headers = HeaderMap::new();
+    headers.insert("content-type", "text/plain");
+    headers.insert(CONTENT_TYPE, APPLICATION_JSON);
+    assert_eq!(headers.iter().collect::<Vec<(&str, &str)>>(), vec![("content-type", APPLICATION_JSON)]);
+}
+
+#[test]
+fn many_headers() {
+    let mut headers = HeaderMap::new();
+    for i in 0..(HEADERS_INLINE * 2) {
+        headers.insert(format!("X-Header-{}", i), i.to_string());
+    }
+    assert_eq!(headers.len(), HEADERS_INLINE * 2);
+    assert_eq!(headers.get("x-header-20"), Some("20"));
+}
+
+#[test]
+fn static_values_are_borrowed() {
+    use std::borrow::Cow;
+
+    let mut request = Request::post("http://example.com");
+    request.insert_param("page", "1")
+           .insert_header("X-Static", APPLICATION_JSON)
+           .insert_header("X-Owned", format!("{}", 1));
+
+    let entries = &request.headers().entries;
+    assert!(matches!(entries[0].1, Cow::Borrowed(APPLICATION_JSON)));
+    assert!(matches!(entries[1].1, Cow::Owned(_)));
+    assert!(matches!(request.params().entries[0], (Cow::Borrowed("page"), Cow::Borrowed("1"))));
+
+    // Sanitized values are copied
+    request.insert_header("X-Static", "a\nb");
+    assert_eq!(request.headers().get("X-Static"), Some("a b"));
+}
+
+#[test]
+fn capacity_and_extend() {
+    let mut headers = HeaderMap::with_capacity(32);
+    headers.reserve(8);
+    headers.extend([("Accept", "*/*"), ("Host", "example.com")]);
+    headers.extend(vec![("accept".to_string(), "text/html".to_string())]);
+    assert_eq!(headers.iter().collect::<Vec<(&str, &str)>>(), vec![("Accept", "text/html"), ("Host", "example.com")]);
+
+    let mut params = KeyValueMap::with_capacity(4);
+    params.reserve(4);
+    params.extend([("page", "1"), ("size", "20")]);
+    assert_eq!(params.len(), 2);
+    assert_eq!(params.get("size"), Some("20"));
+    assert!(!params.is_empty());
+
+    let message = HttpMessage::with_headers([(CONTENT_TYPE, APPLICATION_JSON), (ACCEPT, APPLICATION_JSON)]);
+    assert_eq!(message.headers().len(), 2);
+    assert_eq!(message.headers().get("accept"), Some(APPLICATION_JSON));
+}
+
+#[test]
+fn key_value_map_collection() {
+    let mut params: KeyValueMap = vec![("b", "1"), ("a", "2"), ("c", "3")].into_iter().collect();
+    assert_eq!(params.len(), 3);
+    assert!(params.insert("b", "4"));
+    params.extend([("d".to_string(), "5".to_string())]);
+
+    let pairs: Vec<(&str, &str)> = (&params).into_iter().collect();
+    assert_eq!(pairs, vec![("b", "4"), ("a", "2"), ("c", "3"), ("d", "5")]);
+
+    assert_eq!(params.remove("a"), Some("2".to_string()));
+    assert_eq!(params.remove("a"), None);
+    params.retain(|key, _| key != "c");
+    let owned: Vec<(String, String)> = params.clone().into_iter().collect();
+    assert_eq!(owned, vec![("b".to_string(), "4".to_string()), ("d".to_string(), "5".to_string())]);
+
+    params.clear();
+    assert!(params.is_empty());
+    assert!(!params.contains_key("b"));
+}
+
+#[test]
+fn key_value_map_typed() {
+    let params: KeyValueMap = [("page", "3"), ("ratio", "0.5"), ("debug", "yes")].into_iter().collect();
+    assert_eq!(params.get_parsed::<u32>("page"), Some(Ok(3)));
+    assert_eq!(params.get_parsed::<f64>("ratio"), Some(Ok(0.5)));
+    assert!(matches!(params.get_parsed::<bool>("debug"), Some(Err(_))));
+    assert_eq!(params.get_parsed::<u32>("missing"), None);
+
+    assert_eq!(params.get_parsed_or("page", 1u32), Ok(3));
+    assert_eq!(params.get_parsed_or("size", 25u32), Ok(25));
+    assert_eq!(params.get_or("debug", "no"), "yes");
+    assert_eq!(params.get_or("sort", "name"), "name");
+}
+
+#[test]
+fn list_values() {
+    let mut headers = HeaderMap::new();
+    headers.insert("Vary", " Accept-Encoding ,, Origin ");
+    headers.insert("Accept-Language", "fr;q=0.7, en-US, de;q=0.9, es;q=bad, it;q=0, \"x,y\";q=0.1");
+    headers.insert("Accept", "text/plain; charset=\"a;q=1\"; q=0.2, */*;q=0.1");
+
+    assert_eq!(headers.get_list("vary"), vec!["Accept-Encoding", "Origin"]);
+    assert!(headers.get_list("Missing").is_empty());
+    assert_eq!(headers.get_weighted_list("Accept-Language"),
+               vec![("en-US", 1.0), ("de", 0.9), ("fr", 0.7), ("\"x,y\"", 0.1), ("it", 0.0)]);
+    assert_eq!(headers.get_weighted_list("Accept"), vec![("text/plain; charset=\"a;q=1\"", 0.2), ("*/*", 0.1)]);
+}
+
+#[test]
+fn accept_builder() {
+    let mut request = Request::get("http://service.com");
+    request.accept(&[("text/plain", 0.3333), ("application/xml", 0.9), (" application/json ", 2.0), ("text/html", 0.9),
+                     ("image/*", -1.0), ("*/*", f32::NAN), ("text/csv", 0.0004), ("text/x", 0.1)]);
+    assert_eq!(request.headers().get("accept"),
+               Some("application/json, application/xml;q=0.9, text/html;q=0.9, text/plain;q=0.333, text/x;q=0.1, image/*;q=0, */*;q=0, text/csv;q=0"));
+    assert_eq!(request.headers().get_weighted_list("Accept")[..4],
+               [("application/json", 1.0), ("application/xml", 0.9), ("text/html", 0.9), ("text/plain", 0.333)]);
+
+    request.accept_json();
+    assert_eq!(request.headers().get("accept"), Some(APPLICATION_JSON));
+    request.accept_any().accept(&[]);
+    assert!(!request.headers().contains_key("accept"));
+    request.accept_any();
+    assert_eq!(request.headers().get("accept"), Some("*/*"));
+}
+
+#[test]
+fn verbose_display() {
+    let mut request = Request::post("http://example.com/users");
+    request.insert_param("lang", "en")
+           .insert_cookie("session", "1")
+           .insert_header("Content-Type", "text/plain")
+           .set_body("héllo".as_bytes().to_vec()).unwrap();
+    assert_eq!(request.to_string(), "POST http://example.com/users\nContent-Type=text/plain\n");
+    assert_eq!(format!("{:#}", request),
+               "POST http://example.com/users?lang=en HTTP/1.1\nContent-Type: text/plain\nCookie: session=1\n\nhéllo\n");
+
+    let mut response = Response::new(HTTP_404_NOT_FOUND);
+    response.insert_header("Content-Type", "application/octet-stream")
+            .set_body((0u8..20).collect()).unwrap();
+    response.insert_cookie(wcookie::SetCookie::new("a", "b"));
+    assert_eq!(response.to_string(), "404 Not Found\nContent-Type=application/octet-stream\n");
+    assert_eq!(format!("{:#}", response), "HTTP/1.1 404 Not Found\nContent-Type: application/octet-stream\n\
+        Set-Cookie: a=b\n\n00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f\n10 11 12 13\n");
+
+    // Long bodies are truncated, even inside a multi-byte character
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body("aé".repeat(1000).into_bytes()).unwrap();
+    let dump = format!("{:#}", response);
+    assert!(dump.ends_with("aéa\n... (1024 of 3000 bytes)\n"), "{}", &dump[dump.len() - 40..]);
+}
@@ -0,0 +1,95 @@
+use crate::*;
+use wcookie::SetCookie;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+/// Polls a future whose I/O is already complete, as in-memory hyper bodies are
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn request_roundtrip() {
+    let mut request = Request::post("http://service.com/users");
+    request.insert_param("lang", "en")
+           .insert_cookie("session", "1234")
+           .insert_cookie("theme", "dark")
+           .insert_header("Accept", "application/json")
+           .set_body(b"data".to_vec()).unwrap();
+
+    let converted = request.into_hyper().unwrap();
+    assert_eq!(converted.method(), hyper::Method::POST);
+    assert_eq!(converted.uri(), "http://service.com/users?lang=en");
+    assert_eq!(converted.headers()["accept"], "application/json");
+    assert_eq!(converted.headers()["cookie"], "session=1234; theme=dark");
+
+    let request = block_on(Request::from_hyper(converted)).unwrap();
+    assert_eq!(request.method(), HttpMethod::POST);
+    assert_eq!(request.url(), "http://service.com/users");
+    assert_eq!(request.params().get("lang"), Some("en"));
+    assert_eq!(request.cookies().get("theme"), Some("dark"));
+    assert!(!request.headers().contains_key("Cookie"));
+    assert_eq!(request.body().unwrap(), b"data");
+}
+
+#[test]
+fn request_streaming_body() {
+    let request = Request::put("http://service.com/files/1");
+    let converted = request.into_hyper_with_body(hyper::Body::from("chunk")).unwrap();
+    let data = block_on(hyper::body::to_bytes(converted.into_body())).unwrap();
+    assert_eq!(&data[..], b"chunk");
+
+    // Invalid header names are sanitized when inserted, so they cannot reach hyper
+    let mut request = Request::get("http://service.com/");
+    request.insert_header("Bad Header", "value\r\n");
+    let converted = request.into_hyper().unwrap();
+    assert_eq!(converted.headers()["badheader"], "value  ");
+}
+
+#[test]
+fn response_roundtrip() {
+    let mut response = Response::new(HTTP_401_UNAUTHORIZED);
+    let mut cookie = SetCookie::new("session", "1234");
+    cookie.path = Some("/".to_string());
+    cookie.http_only = true;
+    response.insert_cookie(cookie)
+            .insert_cookie(SetCookie::new("theme", "dark"))
+            .insert_auth_headers("Basic realm=\"users\"")
+            .insert_header("Content-Type", "text/plain")
+            .set_body(b"denied".to_vec()).unwrap();
+
+    let converted = response.into_hyper().unwrap();
+    assert_eq!(converted.status(), 401);
+    let cookies: Vec<&str> = converted.headers().get_all("set-cookie").iter().map(|v| v.to_str().unwrap()).collect();
+    assert_eq!(cookies, vec!["session=1234; Path=/; HttpOnly", "theme=dark"]);
+    assert_eq!(converted.headers()["www-authenticate"], "Basic realm=\"users\"");
+
+    let mut converted = converted;
+    converted.headers_mut().append("x-multi", "a".parse().unwrap());
+    converted.headers_mut().append("x-multi", "b".parse().unwrap());
+
+    let response = block_on(Response::from_hyper(converted)).unwrap();
+    assert_eq!(response.status_code(), HTTP_401_UNAUTHORIZED);
+    assert_eq!(response.cookies().len(), 2);
+    assert!(response.cookies()[0].http_only);
+    assert_eq!(response.auth_headers(), &vec!["Basic realm=\"users\"".to_string()]);
+    assert!(!response.headers().contains_key("Set-Cookie"));
+    assert_eq!(response.headers().get("x-multi"), Some("a, b"));
+    assert_eq!(response.headers().get("content-type"), Some("text/plain"));
+    assert_eq!(response.body().unwrap(), b"denied");
+}
+
+#[test]
+fn shared_body() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_shared_body(std::sync::Arc::from(&b"cached"[..])).unwrap();
+    let converted = response.into_hyper().unwrap();
+    let data = block_on(hyper::body::to_bytes(converted.into_body())).unwrap();
+    assert_eq!(&data[..], b"cached");
+}
@@ -0,0 +1,39 @@
+use crate::*;
+use crate::language::*;
+
+#[test]
+fn language_tags() {
+    for valid in ["en", "es-419", "zh-Hant-TW", "sl-rozaj-biske", "x-private"] {
+        assert_eq!(LanguageTag::parse(valid).unwrap().as_str(), valid);
+    }
+    for invalid in ["", "e1", "en_US", "en-", "toolongtag", "en-abcdefghi", "*"] {
+        assert!(LanguageTag::parse(invalid).is_none(), "{}", invalid);
+    }
+    assert_eq!(LanguageTag::parse("EN-us"), LanguageTag::parse("en-US"));
+    assert_eq!(LanguageTag::parse("de-AT").unwrap().primary_language(), "de");
+}
+
+#[test]
+fn content_language() {
+    let mut response = Response::new(HTTP_200_OK);
+    assert!(response.content_language().is_empty());
+
+    response.set_content_language(&["mi", "en-NZ"]).unwrap();
+    assert_eq!(response.headers().get(CONTENT_LANGUAGE), Some("mi, en-NZ"));
+    let tags: Vec<String> = response.content_language().iter().map(|tag| tag.to_string()).collect();
+    assert_eq!(tags, vec!["mi", "en-NZ"]);
+
+    assert!(response.set_content_language(&["en", "bad tag"]).is_err());
+    assert_eq!(response.headers().get(CONTENT_LANGUAGE), Some("mi, en-NZ"));
+
+    response.insert_header(CONTENT_LANGUAGE, "da, in valid");
+    assert_eq!(response.content_language().len(), 1);
+}
+
+#[test]
+fn accept_languages() {
+    let mut request = Request::get("http://service.com/");
+    request.insert_header(ACCEPT_LANGUAGE, "*;q=0.1, de;q=0.7, en-GB, en;q=0.8");
+    let languages: Vec<(String, f32)> = request.accept_languages().into_iter().map(|(tag, q)| (tag.to_string(), q)).collect();
+    assert_eq!(languages, vec![("en-GB".to_string(), 1.0), ("en".to_string(), 0.8), ("de".to_string(), 0.7)]);
+}
@@ -0,0 +1,74 @@
+use crate::*;
+use crate::HttpMethod::*;
+use crate::matchers::*;
+use json::object;
+
+fn create_request() -> Request {
+    let mut request = Request::post("https://service.com/users?lang=en");
+    request.insert_param("page", "2")
+           .insert_cookie("session", "1234")
+           .insert_header("Accept", "application/json")
+           .set_json(&object! { name: "John", age: 30 }).unwrap();
+    request
+}
+
+#[test]
+fn matches1() {
+    let request = create_request();
+
+    method(POST)
+        .and(path("/users"))
+        .and(header("content-type", contains("json")))
+        .and(header("ACCEPT", equals_ignore_case("Application/JSON")))
+        .and(param("page", "2"))
+        .and(cookie("session", any_value()))
+        .and(body(starts_with("{")))
+        .and(json_body(|j| j["age"] == 30))
+        .and(not(header("Authorization", any_value())))
+        .assert(&request);
+
+    assert!(any().matches(&request));
+    assert!(method(GET).or(path("/users")).matches(&request));
+}
+
+#[test]
+fn mismatch1() {
+    let request = create_request();
+
+    let mismatch = method(GET)
+        .and(path("/groups"))
+        .and(param("page", "3"))
+        .and(cookie("theme", any_value()))
+        .check(&request)
+        .unwrap_err();
+
+    assert_eq!(mismatch.reasons, vec![
+        "method GET: method is POST",
+        "path /groups: path is /users",
+        "param page equal to \"3\": param is \"2\"",
+        "cookie theme present: cookie is missing"
+    ]);
+
+    let mismatch = method(GET).or(method(PUT)).check(&request).unwrap_err();
+    assert_eq!(mismatch.to_string(),
+               "none of (method GET) or (method PUT) matches: method GET: method is POST; method PUT: method is POST");
+
+    let mismatch = not(method(POST)).check(&request).unwrap_err();
+    assert_eq!(mismatch.to_string(), "not (method POST): it matches");
+
+    assert!(!json_body(|j| j["name"] == "Jane").matches(&request));
+    assert!(!json_body(|_| true).matches(&Request::get("https://service.com/users")));
+}
+
+#[test]
+fn boxed() {
+    let request = create_request();
+    let matchers: Vec<Box<dyn Matcher>> = vec![Box::new(method(POST)), Box::new(path("/users"))];
+    assert!(matchers.iter().all(|m| m.matches(&request)));
+}
+
+#[test]
+#[should_panic(expected = "does not match")]
+fn assert_panics() {
+    method(DELETE).assert(&create_request());
+}
@@ -0,0 +1,54 @@
+use crate::*;
+use crate::middleware::*;
+use std::sync::{Arc, Mutex};
+
+/// Records the order the hooks are called in
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>
+}
+
+impl Middleware for Recorder {
+    fn on_request(&self, request: &mut Request) {
+        self.log.lock().unwrap().push(format!("request {}", self.name));
+        request.insert_header(format!("X-{}", self.name), "seen");
+    }
+
+    fn on_response(&self, _request: &Request, _response: &mut Response) {
+        self.log.lock().unwrap().push(format!("response {}", self.name));
+    }
+}
+
+#[test]
+fn pipeline_order() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut pipeline = Pipeline::new();
+    assert!(pipeline.is_empty());
+    pipeline.add(Recorder { name: "a", log: log.clone() })
+            .add(Recorder { name: "b", log: log.clone() });
+    assert_eq!(pipeline.len(), 2);
+
+    let response = pipeline.execute(Request::get("https://service.com"), |request| {
+        assert!(request.headers().contains_key("X-a"));
+        assert!(request.headers().contains_key("X-b"));
+        Response::new(HTTP_204_NO_CONTENT)
+    });
+
+    assert_eq!(response.status_code(), HTTP_204_NO_CONTENT);
+    assert_eq!(*log.lock().unwrap(), vec!["request a", "request b", "response b", "response a"]);
+}
+
+#[test]
+fn default_header() {
+    let mut pipeline = Pipeline::new();
+    pipeline.add(DefaultHeader::new("Authorization", "Bearer 1234"));
+
+    let mut request = Request::get("https://service.com");
+    pipeline.on_request(&mut request);
+    assert_eq!(request.headers().get("authorization"), Some("Bearer 1234"));
+
+    let mut request = Request::get("https://service.com");
+    request.insert_header("Authorization", "Basic xyz");
+    pipeline.on_request(&mut request);
+    assert_eq!(request.headers().get("authorization"), Some("Basic xyz"));
+}
@@ -0,0 +1,84 @@
+use crate::*;
+use crate::mock::MockRouter;
+use crate::matchers::{param, json_body};
+use json::object;
+
+fn create_router() -> MockRouter {
+    let mut created = Response::new(HTTP_201_CREATED);
+    created.insert_header("Location", "/users/1");
+
+    let mut conflict = Response::new(HTTP_409_CONFLICT);
+    conflict.set_json(&object! { error: "duplicated" }).unwrap();
+
+    let mut router = MockRouter::new();
+    router.route_when(HttpMethod::POST, "/users", json_body(|j| j["name"] == "John"), conflict)
+          .route(HttpMethod::POST, "/users", created)
+          .route(HttpMethod::GET, "/users/me", Response::new(HTTP_204_NO_CONTENT))
+          .route_fn(HttpMethod::GET, "/users/{id}/groups/{group}", |request, params| {
+              let mut response = Response::new(HTTP_200_OK);
+              response.set_json(&object! {
+                  id: params["id"].as_str(),
+                  group: params["group"].as_str(),
+                  page: request.params().get("page")
+              }).unwrap();
+              response
+          })
+          .route_when(HttpMethod::DELETE, "/users/{id}", param("force", "true"), Response::new(HTTP_204_NO_CONTENT));
+    router
+}
+
+#[test]
+fn route1() {
+    let router = create_router();
+
+    let mut request = Request::post("https://service.com/users");
+    request.set_json(&object! { name: "Jane" }).unwrap();
+    let response = router.handle(&request);
+    assert_eq!(response.status_code(), HTTP_201_CREATED);
+    assert_eq!(response.headers().get("Location"), Some("/users/1"));
+
+    request.set_json(&object! { name: "John" }).unwrap();
+    let response = router.handle(&request);
+    assert_eq!(response.status_code(), HTTP_409_CONFLICT);
+    assert_eq!(response.json().unwrap()["error"], "duplicated");
+
+    // Canned responses can be returned many times
+    assert_eq!(router.handle(&request).status_code(), HTTP_409_CONFLICT);
+
+    // Literal routes registered first take precedence
+    let response = router.handle(&Request::get("http://localhost/users/me"));
+    assert_eq!(response.status_code(), HTTP_204_NO_CONTENT);
+}
+
+#[test]
+fn route_params() {
+    let router = create_router();
+
+    let mut request = Request::get("http://localhost/users/john%20smith/groups/admin?page=3");
+    request.insert_param("page", "3");
+    let response = router.handle(&request);
+
+    assert_eq!(response.status_code(), HTTP_200_OK);
+    let data = response.json().unwrap();
+    assert_eq!(data["id"], "john smith");
+    assert_eq!(data["group"], "admin");
+    assert_eq!(data["page"], "3");
+}
+
+#[test]
+fn no_route() {
+    let mut router = create_router();
+
+    let response = router.handle(&Request::put("http://localhost/users"));
+    assert_eq!(response.status_code(), HTTP_405_METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers().get("Allow"), Some("POST"));
+
+    assert_eq!(router.handle(&Request::get("http://localhost/groups")).status_code(), HTTP_404_NOT_FOUND);
+    assert_eq!(router.handle(&Request::get("http://localhost/users//groups/a")).status_code(), HTTP_404_NOT_FOUND);
+
+    // Matcher does not match and there is no other route
+    assert_eq!(router.handle(&Request::delete("http://localhost/users/1")).status_code(), HTTP_404_NOT_FOUND);
+
+    router.fallback(Response::new(HTTP_503_SERVICE_UNAVAILABLE));
+    assert_eq!(router.handle(&Request::get("http://localhost/groups")).status_code(), HTTP_503_SERVICE_UNAVAILABLE);
+}
@@ -0,0 +1,139 @@
+use crate::multipart::*;
+use crate::HeaderMap;
+use std::io::Write;
+
+#[test]
+fn write_parts() {
+    let mut writer = MultipartWriter::with_boundary(Vec::new(), "XyZ").unwrap();
+    assert_eq!(writer.content_type(), "multipart/form-data; boundary=XyZ");
+
+    writer.start_part(&Part::form_data("name")).unwrap();
+    writer.write_all(b"Jo").unwrap();
+    writer.write_all(b"hn").unwrap();
+    let length = writer.write_part(&Part::file("cv", "my \"cv\".txt", "text/plain"), &mut &b"text"[..]).unwrap();
+    assert_eq!(length, 4);
+    let body = writer.finish().unwrap();
+
+    assert_eq!(String::from_utf8(body).unwrap(),
+        "--XyZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn\r\n\
+         --XyZ\r\nContent-Disposition: form-data; name=\"cv\"; filename=\"my %22cv%22.txt\"\r\n\
+         Content-Type: text/plain\r\n\r\ntext\r\n--XyZ--\r\n");
+}
+
+#[test]
+fn length_up_front() {
+    let mut headers = HeaderMap::new();
+    headers.insert("Content-Type", "application/octet-stream");
+    let parts = [Part::form_data("a"), Part::new(headers).with_header("X-Part", "2")];
+
+    let mut writer = MultipartWriter::new(Vec::new());
+    let expected = encoded_length(writer.boundary(), &[(&parts[0], 3), (&parts[1], 5)]);
+    writer.start_part(&parts[0]).unwrap();
+    writer.write_all(b"abc").unwrap();
+    writer.start_part(&parts[1]).unwrap();
+    writer.write_all(b"12345").unwrap();
+    assert_eq!(writer.finish().unwrap().len() as u64, expected);
+
+    let empty = MultipartWriter::with_boundary(Vec::new(), "b").unwrap().finish().unwrap();
+    assert_eq!(empty, b"--b--\r\n");
+    assert_eq!(encoded_length("b", &[]), 7);
+}
+
+#[test]
+fn invalid_use() {
+    assert!(MultipartWriter::with_boundary(Vec::new(), "").is_err());
+    assert!(MultipartWriter::with_boundary(Vec::new(), "a;b").is_err());
+    assert!(MultipartWriter::with_boundary(Vec::new(), &"a".repeat(71)).is_err());
+    assert!(is_valid_boundary(&generate_boundary()));
+
+    let mut writer = MultipartWriter::new(Vec::new());
+    assert!(writer.write_all(b"orphan").is_err());
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_writer() {
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    let future = async {
+        let mut writer = AsyncMultipartWriter::with_boundary(Vec::new(), "XyZ").unwrap();
+        writer.start_part(&Part::form_data("name")).await.unwrap();
+        writer.write_all(b"John").await.unwrap();
+        writer.finish().await.unwrap()
+    };
+    let mut future = Box::pin(future);
+    let body = loop {
+        if let Poll::Ready(body) = future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            break body;
+        }
+    };
+    assert_eq!(body, b"--XyZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn\r\n--XyZ--\r\n");
+}
+
+/// Reads a body fed in chunks of `size` bytes, collecting the parts headers and data
+fn read_chunks(body: &[u8], boundary: &str, size: usize) -> Vec<(Part, Vec<u8>)> {
+    let mut reader = MultipartReader::new(boundary).unwrap();
+    let mut parts: Vec<(Part, Vec<u8>)> = Vec::new();
+    let mut ended = 0;
+    for chunk in body.chunks(size) {
+        reader.feed(chunk);
+        while let Some(event) = reader.next_event().unwrap() {
+            match event {
+                Event::PartStart(part) => parts.push((*part, Vec::new())),
+                Event::Data(data) => parts.last_mut().unwrap().1.extend_from_slice(&data),
+                Event::PartEnd => ended += 1,
+                Event::End => assert!(reader.is_finished())
+            }
+        }
+    }
+    reader.finish().unwrap();
+    assert_eq!(ended, parts.len());
+    parts
+}
+
+#[test]
+fn read_parts() {
+    let body = b"preamble\r\n--XyZ\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn\r\n\
+                 --XyZ  \r\nContent-Disposition: form-data; name=\"cv\"; filename=\"cv.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\nline\r\n--Xyz\r\n--XyZ\r\n\r\nno headers\r\n--XyZ--\r\nepilogue";
+    for size in [1, 2, 5, 13, body.len()] {
+        let parts = read_chunks(body, "XyZ", size);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0].0.name().as_deref(), Some("name"));
+        assert_eq!(parts[0].1, b"John");
+        assert_eq!(parts[1].0.filename().as_deref(), Some("cv.txt"));
+        assert_eq!(parts[1].0.headers().get("content-type"), Some("text/plain"));
+        assert_eq!(parts[1].1, b"line\r\n--Xyz");
+        assert!(parts[2].0.headers().is_empty());
+        assert_eq!(parts[2].1, b"no headers");
+    }
+}
+
+#[test]
+fn read_written() {
+    let mut writer = MultipartWriter::new(Vec::new());
+    let content_type = writer.content_type();
+    writer.write_part(&Part::file("data", "data.bin", "application/octet-stream"), &mut &[0u8, 13, 10, 45, 45][..]).unwrap();
+    let body = writer.finish().unwrap();
+
+    assert_eq!(boundary_from_content_type(&content_type).as_deref(), Some(&content_type[30..]));
+    let boundary = boundary_from_content_type(&content_type).unwrap();
+    let parts = read_chunks(&body, &boundary, 3);
+    assert_eq!(parts[0].1, [0u8, 13, 10, 45, 45]);
+}
+
+#[test]
+fn read_errors() {
+    assert!(MultipartReader::from_content_type("text/plain; boundary=a").is_err());
+    assert_eq!(boundary_from_content_type("multipart/mixed; charset=utf-8; boundary=\"a b\"").as_deref(), Some("a b"));
+
+    let mut reader = MultipartReader::new("b").unwrap();
+    reader.feed(b"--b\r\nInvalid header\r\n\r\n");
+    assert!(reader.next_event().is_err());
+
+    let mut reader = MultipartReader::new("b").unwrap();
+    reader.feed(b"--b\r\n\r\ntruncated");
+    while reader.next_event().unwrap().is_some() {}
+    assert_eq!(reader.finish().unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+}
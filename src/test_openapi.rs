@@ -0,0 +1,129 @@
+use crate::*;
+use crate::openapi::OpenApi;
+use json::object;
+
+const DOCUMENT: &str = r##"{
+    "openapi": "3.0.3",
+    "servers": [{"url": "https://service.com/v1"}],
+    "paths": {
+        "/users": {
+            "post": {
+                "parameters": [
+                    {"name": "X-Client", "in": "header", "required": true, "schema": {"type": "string"}},
+                    {"name": "dry_run", "in": "query", "schema": {"type": "boolean"}}
+                ],
+                "requestBody": {
+                    "required": true,
+                    "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}
+                },
+                "responses": {
+                    "201": {
+                        "headers": {"Location": {"required": true, "schema": {"type": "string"}}},
+                        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/User"}}}
+                    },
+                    "4XX": {"content": {"application/problem+json": {"schema": {"type": "object"}}}}
+                }
+            }
+        },
+        "/users/me": {
+            "get": {"responses": {"200": {"description": "Current user"}}}
+        },
+        "/users/{id}": {
+            "parameters": [{"name": "id", "in": "path", "required": true, "schema": {"type": "integer", "minimum": 1}}],
+            "get": {
+                "parameters": [{"name": "fields", "in": "query", "required": true, "schema": {"type": "string", "enum": ["all", "name"]}}],
+                "responses": {"default": {"description": "Any"}}
+            }
+        }
+    },
+    "components": {
+        "schemas": {
+            "User": {
+                "type": "object",
+                "required": ["name", "age"],
+                "additionalProperties": false,
+                "properties": {
+                    "name": {"type": "string", "minLength": 1},
+                    "age": {"type": "integer", "minimum": 0},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "manager": {"type": "string", "nullable": true}
+                }
+            }
+        }
+    }
+}"##;
+
+fn locations(result: Result<(), Vec<openapi::Violation>>) -> Vec<String> {
+    result.unwrap_err().into_iter().map(|v| v.location).collect()
+}
+
+#[test]
+fn valid_exchange() {
+    let api = OpenApi::parse(DOCUMENT).unwrap();
+
+    let mut request = Request::post("https://service.com/v1/users");
+    request.insert_param("dry_run", "true")
+           .insert_header("X-Client", "test")
+           .set_json(&object! { name: "John", age: 30, tags: ["admin"], manager: null }).unwrap();
+
+    let mut response = Response::new(HTTP_201_CREATED);
+    response.insert_header("Location", "/v1/users/1")
+            .set_json(&object! { name: "John", age: 30 }).unwrap();
+
+    assert!(api.validate(&request, &response).is_ok());
+
+    let request = Request::get("https://service.com/v1/users/me");
+    assert!(api.validate(&request, &Response::new(HTTP_200_OK)).is_ok());
+}
+
+#[test]
+fn invalid_request() {
+    let api = OpenApi::parse(DOCUMENT).unwrap();
+
+    let mut request = Request::post("https://service.com/v1/users");
+    request.insert_param("dry_run", "maybe")
+           .set_json(&object! { name: "", age: 1.5, tags: [1], extra: true }).unwrap();
+
+    assert_eq!(locations(api.validate_request(&request)),
+               vec!["header.X-Client", "query.dry_run", "body.name", "body.age", "body.tags[0]", "body.extra"]);
+
+    let request = Request::post("https://service.com/v1/users");
+    assert!(locations(api.validate_request(&request)).contains(&"body".to_string()));
+
+    let request = Request::get("https://service.com/v1/users/0");
+    assert_eq!(locations(api.validate_request(&request)), vec!["query.fields", "path.id"]);
+
+    let mut request = Request::get("https://service.com/v1/users/3");
+    request.insert_param("fields", "none");
+    assert_eq!(locations(api.validate_request(&request)), vec!["query.fields"]);
+
+    assert_eq!(locations(api.validate_request(&Request::delete("https://service.com/v1/users"))), vec!["method"]);
+    assert_eq!(locations(api.validate_request(&Request::get("https://service.com/v1/groups"))), vec!["path"]);
+}
+
+#[test]
+fn invalid_response() {
+    let api = OpenApi::parse(DOCUMENT).unwrap();
+
+    let mut request = Request::post("https://service.com/v1/users");
+    request.insert_header("X-Client", "test")
+           .set_json(&object! { name: "John", age: 30 }).unwrap();
+
+    let mut response = Response::new(HTTP_201_CREATED);
+    response.set_json(&object! { name: "John" }).unwrap();
+    assert_eq!(locations(api.validate_response(&request, &response)), vec!["header.Location", "body.age"]);
+
+    let mut response = Response::new(HTTP_400_BAD_REQUEST);
+    response.set_json(&object! { title: "Bad" }).unwrap();
+    assert_eq!(locations(api.validate_response(&request, &response)), vec!["header.Content-Type"]);
+
+    let response = Response::new(HTTP_500_INTERNAL_SERVE_ERROR);
+    assert_eq!(locations(api.validate_response(&request, &response)), vec!["status"]);
+}
+
+#[test]
+fn invalid_document() {
+    assert!(OpenApi::parse(r#"{"swagger": "2.0", "paths": {}}"#).is_err());
+    assert!(OpenApi::parse(r#"{"openapi": "3.1.0"}"#).is_err());
+    assert!(OpenApi::parse("openapi: 3.0.0").is_err());
+}
@@ -0,0 +1,38 @@
+use crate::*;
+use crate::policy::*;
+
+#[test]
+fn validate() {
+    let policy = RequestPolicy::new()
+        .with_allowed_schemes(&["HTTPS", "wss"])
+        .with_allowed_methods(&[HttpMethod::GET, HttpMethod::POST])
+        .with_required_header("Traceparent")
+        .with_forbidden_header("X-Debug")
+        .with_max_url_length(40);
+
+    let mut request = Request::get("HTTPS://service.com/users");
+    request.insert_header("traceparent", "00-1-2-01");
+    assert_eq!(request.validate(&policy), Ok(()));
+
+    request.insert_param("query", "a".repeat(20));
+    request.insert_header("x-debug", "1");
+    assert_eq!(request.validate(&policy).unwrap_err(), vec![
+        Violation::ForbiddenHeader("X-Debug".to_string()),
+        Violation::UrlTooLong { length: 52, max: 40 }
+    ]);
+
+    let request = Request::delete("/users/1");
+    let violations = request.validate(&policy).unwrap_err();
+    assert_eq!(violations, vec![
+        Violation::Scheme(None),
+        Violation::Method(HttpMethod::DELETE),
+        Violation::MissingHeader("Traceparent".to_string())
+    ]);
+    assert_eq!(violations.iter().map(|v| v.to_string()).collect::<Vec<String>>(), vec![
+        "Relative URLs are not allowed",
+        "Method DELETE is not allowed",
+        "Required header Traceparent is missing"
+    ]);
+
+    assert!(Request::trace("ftp://host").validate(&RequestPolicy::new()).is_ok());
+}
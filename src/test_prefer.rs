@@ -0,0 +1,45 @@
+use crate::*;
+use crate::prefer::*;
+use std::time::Duration;
+
+#[test]
+fn format() {
+    let prefer = Prefer::new()
+        .with_return(Return::Representation)
+        .with_wait(Duration::from_millis(5500))
+        .with_respond_async()
+        .with_handling(Handling::Lenient)
+        .with_other("odata.maxpagesize", Some("50"));
+    assert_eq!(prefer.to_string(), "return=representation, wait=5, respond-async, handling=lenient, odata.maxpagesize=50");
+    assert!(Prefer::new().is_empty());
+    assert_eq!(Prefer::new().to_string(), "");
+}
+
+#[test]
+fn parse() {
+    let prefer = Prefer::parse("RETURN=Minimal; foo=bar, wait=\"30\",respond-async, handling=strict, return=representation, odata.track-changes");
+    assert_eq!(prefer.return_preference, Some(Return::Minimal));
+    assert_eq!(prefer.wait, Some(Duration::from_secs(30)));
+    assert!(prefer.respond_async);
+    assert_eq!(prefer.handling, Some(Handling::Strict));
+    assert_eq!(prefer.others, vec![("odata.track-changes".to_string(), None)]);
+
+    let prefer = Prefer::parse("wait=soon, return=all");
+    assert_eq!(prefer.wait, None);
+    assert_eq!(prefer.return_preference, None);
+    assert_eq!(prefer.others.len(), 2);
+}
+
+#[test]
+fn messages() {
+    let mut request = Request::patch("https://service.com/users/1");
+    assert!(request.prefer().is_none());
+    request.set_prefer(&Prefer::new().with_handling(Handling::Strict));
+    assert_eq!(request.headers().get("prefer"), Some("handling=strict"));
+    assert_eq!(request.prefer().unwrap().handling, Some(Handling::Strict));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_preference_applied(&Prefer::new().with_return(Return::Representation));
+    assert_eq!(response.headers().get("Preference-Applied"), Some("return=representation"));
+    assert_eq!(response.preference_applied().unwrap().return_preference, Some(Return::Representation));
+}
@@ -0,0 +1,45 @@
+use crate::*;
+use crate::range::*;
+
+#[test]
+fn parse_ranges() {
+    assert_eq!(ByteRange::parse_header("bytes=0-499, 500-, -200"),
+               Some(vec![ByteRange::FromTo(0, 499), ByteRange::From(500), ByteRange::Suffix(200)]));
+    assert_eq!(ByteRange::parse_header("Bytes = 1-1,,"), Some(vec![ByteRange::FromTo(1, 1)]));
+    for value in ["items=0-1", "bytes=", "bytes=5-1", "bytes=-", "bytes=a-b", "bytes=+1-2", "bytes=0-1, x", "0-1"] {
+        assert_eq!(ByteRange::parse_header(value), None, "{}", value);
+    }
+}
+
+#[test]
+fn resolve_ranges() {
+    assert_eq!(ByteRange::FromTo(0, 499).resolve(1000), Some(0..500));
+    assert_eq!(ByteRange::FromTo(900, 2000).resolve(1000), Some(900..1000));
+    assert_eq!(ByteRange::FromTo(1000, 2000).resolve(1000), None);
+    assert_eq!(ByteRange::From(999).resolve(1000), Some(999..1000));
+    assert_eq!(ByteRange::Suffix(2000).resolve(1000), Some(0..1000));
+    assert_eq!(ByteRange::Suffix(0).resolve(1000), None);
+    assert_eq!(ByteRange::Suffix(10).resolve(0), None);
+    assert_eq!(ByteRange::FromTo(0, u64::MAX).resolve(10), Some(0..10));
+    assert_eq!(content_range(&(0..500), 1000), "bytes 0-499/1000");
+}
+
+#[test]
+fn satisfiable_ranges() {
+    let mut request = Request::get("http://service.com/file");
+    assert_eq!(request.satisfiable_ranges(1000).ok().unwrap(), None);
+
+    request.insert_header("Range", "bytes=0-1, 3-3");
+    assert_eq!(request.satisfiable_ranges(1000).ok().unwrap(), Some(vec![0..2, 3..4]));
+
+    request.insert_header("Range", "bytes=500-599, 0-99, 100-199, -50, 2000-");
+    assert_eq!(request.satisfiable_ranges(1000).ok().unwrap(), Some(vec![0..200, 500..600, 950..1000]));
+
+    request.insert_header("Range", "bytes=1000-, -0");
+    let response = request.satisfiable_ranges(1000).err().unwrap();
+    assert_eq!(response.status_code(), HTTP_416_RANGE_NOT_SATISFIABLE);
+    assert_eq!(response.headers().get("content-range"), Some("bytes */1000"));
+
+    request.insert_header("Range", "lines=1-2");
+    assert_eq!(request.satisfiable_ranges(1000).ok().unwrap(), None);
+}
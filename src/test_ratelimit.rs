@@ -0,0 +1,60 @@
+use crate::*;
+use std::time::Duration;
+
+#[test]
+fn structured_fields() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("RateLimit", "\"burst\";r=5;t=2, \"daily\";r=900;t=3600")
+            .insert_header("RateLimit-Policy", "\"burst\";q=10;w=5, \"daily\";q=1000;w=86400");
+
+    let rate_limit = response.rate_limit().unwrap();
+    assert_eq!(rate_limit.policy.as_deref(), Some("burst"));
+    assert_eq!(rate_limit.remaining, 5);
+    assert_eq!(rate_limit.limit, Some(10));
+    assert_eq!(rate_limit.reset, Some(Duration::from_secs(2)));
+    assert_eq!(rate_limit.window, Some(Duration::from_secs(5)));
+}
+
+#[test]
+fn earlier_drafts() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("RateLimit", "limit=100, remaining=50, reset=30")
+            .insert_header("RateLimit-Policy", "100;w=60");
+    let rate_limit = response.rate_limit().unwrap();
+    assert_eq!(rate_limit.policy, None);
+    assert_eq!((rate_limit.limit, rate_limit.remaining), (Some(100), 50));
+    assert_eq!(rate_limit.reset, Some(Duration::from_secs(30)));
+    assert_eq!(rate_limit.window, Some(Duration::from_secs(60)));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("RateLimit-Limit", "10")
+            .insert_header("RateLimit-Remaining", "0")
+            .insert_header("RateLimit-Reset", "7");
+    let rate_limit = response.rate_limit().unwrap();
+    assert_eq!((rate_limit.limit, rate_limit.remaining), (Some(10), 0));
+    assert_eq!(rate_limit.reset, Some(Duration::from_secs(7)));
+}
+
+#[test]
+fn x_rate_limit() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("Date", "Wed, 21 Oct 2015 07:28:00 GMT")
+            .insert_header("X-RateLimit-Limit", "5000")
+            .insert_header("X-RateLimit-Remaining", "4999")
+            .insert_header("X-RateLimit-Reset", "1445412540");
+
+    let rate_limit = response.rate_limit().unwrap();
+    assert_eq!((rate_limit.limit, rate_limit.remaining), (Some(5000), 4999));
+    assert_eq!(rate_limit.reset, Some(Duration::from_secs(60)));
+    assert_eq!(rate_limit.window, None);
+}
+
+#[test]
+fn missing_or_invalid() {
+    assert!(Response::new(HTTP_200_OK).rate_limit().is_none());
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("X-RateLimit-Limit", "10")
+            .insert_header("X-RateLimit-Remaining", "many");
+    assert!(response.rate_limit().is_none());
+}
@@ -0,0 +1,59 @@
+use crate::*;
+use std::future::Future;
+use std::task::{Context, Poll, Waker};
+
+/// Polls a future whose I/O is already complete, as in-memory reqwest bodies are
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[test]
+fn into_reqwest() {
+    let mut request = Request::patch("http://service.com/users/1");
+    request.insert_param("lang", "en")
+           .insert_cookie("session", "1234")
+           .insert_header("Content-Type", "application/json")
+           .set_body(b"{}".to_vec()).unwrap();
+
+    let client = reqwest::Client::new();
+    let converted = request.into_reqwest(&client).build().unwrap();
+
+    assert_eq!(converted.method(), reqwest::Method::PATCH);
+    assert_eq!(converted.url().as_str(), "http://service.com/users/1?lang=en");
+    assert_eq!(converted.headers()["content-type"], "application/json");
+    assert_eq!(converted.headers()["cookie"], "session=1234");
+    assert_eq!(converted.body().unwrap().as_bytes().unwrap(), b"{}");
+
+    let request = Request::get("not a url");
+    assert!(request.into_reqwest(&client).build().is_err());
+}
+
+#[test]
+fn from_reqwest() {
+    let response = http::Response::builder()
+        .status(403)
+        .header("Content-Type", "text/plain")
+        .header("Set-Cookie", "session=1234; Path=/; Secure")
+        .header("Set-Cookie", "invalid")
+        .header("WWW-Authenticate", "Bearer")
+        .header("Vary", "Accept")
+        .header("Vary", "Cookie")
+        .body("forbidden")
+        .unwrap();
+
+    let response = block_on(Response::from_reqwest(reqwest::Response::from(response))).unwrap();
+
+    assert_eq!(response.status_code(), 403);
+    assert_eq!(response.cookies().len(), 1);
+    assert!(response.cookies()[0].secure);
+    assert_eq!(response.auth_headers(), &vec!["Bearer".to_string()]);
+    assert_eq!(response.headers().get("vary"), Some("Accept, Cookie"));
+    assert!(!response.headers().contains_key("set-cookie"));
+    assert_eq!(response.body().unwrap(), b"forbidden");
+}
@@ -79,7 +79,7 @@ fn  json1() {
     };
 
     // JSON Object is encoded at the body
-    response.set_json(&data);
+    response.set_json(&data).unwrap();
 
     assert_eq!(response.headers().get("Content-Type").unwrap(), "application/json" );
 
@@ -88,4 +88,341 @@ fn  json1() {
     assert!(extracted.is_ok());
 
     assert_eq!(extracted.unwrap(), data);
-}
\ No newline at end of file
+}
+
+#[test]
+fn problem1() {
+    let mut problem = Problem::new(HTTP_400_BAD_REQUEST);
+    problem.problem_type = "https://example.com/probs/out-of-credit".to_string();
+    problem.title = Some("You do not have enough credit.".to_string());
+    problem.detail = Some("Your current balance is 30, but that costs 50.".to_string());
+    problem.instance = Some("/account/12345/msgs/abc".to_string());
+    problem.extensions.insert("balance".to_string(), 30.into());
+
+    let response = Response::problem(problem.clone()).unwrap();
+
+    assert_eq!(response.status_code(), HTTP_400_BAD_REQUEST);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "application/problem+json");
+    assert_eq!(response.json().unwrap()["type"], "https://example.com/probs/out-of-credit");
+    assert_eq!(response.as_problem().unwrap(), problem);
+}
+
+#[test]
+fn problem3() {
+    let mut problem = Problem::new(HTTP_400_BAD_REQUEST);
+    for name in ["zone", "balance", "accounts", "limit"] {
+        problem.extensions.insert(name.to_string(), name.len().into());
+    }
+
+    let response = Response::problem(problem.clone()).unwrap();
+    assert_eq!(response.body().unwrap(),
+               br#"{"accounts":8,"balance":7,"limit":5,"zone":4,"type":"about:blank","status":400}"#);
+    assert_eq!(response.as_problem().unwrap(), problem);
+}
+
+#[test]
+fn problem2() {
+    let mut response = Response::new(HTTP_404_NOT_FOUND);
+    response.set_json(&object! { title: "Not found" }).unwrap();
+    // Plain JSON responses are not problems
+    assert!(response.as_problem().is_none());
+
+    response.insert_header("Content-Type", "application/problem+json; charset=utf-8");
+    let problem = response.as_problem().unwrap();
+    assert_eq!(problem.problem_type, "about:blank");
+    assert_eq!(problem.title.as_deref(), Some("Not found"));
+    assert!(problem.status.is_none());
+}
+
+#[test]
+fn mirror_request_id() {
+    let mut request = Request::get("http://example.com/user");
+    let id = request.ensure_request_id(X_REQUEST_ID);
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.mirror_request_id(&request, X_REQUEST_ID);
+    assert_eq!(response.headers().get(X_REQUEST_ID), Some(id.as_str()));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.mirror_request_id(&Request::get("http://example.com/user"), X_REQUEST_ID);
+    assert!(!response.headers().contains_key(X_REQUEST_ID));
+}
+
+#[test]
+fn shared_body() {
+    let mut cached = Response::new(HTTP_200_OK);
+    cached.set_body(b"large document".to_vec()).unwrap();
+
+    let first = cached.clone_shallow();
+    let second = cached.clone_shallow();
+    assert_eq!(first.body().unwrap(), b"large document");
+    assert!(std::ptr::eq(first.body().unwrap(), second.body().unwrap()));
+    assert!(std::ptr::eq(first.body().unwrap(), cached.body().unwrap()));
+
+    let shared = cached.share_body().unwrap();
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_shared_body(shared.clone()).unwrap();
+    assert!(response.has_single_body());
+    assert!(std::ptr::eq(response.body().unwrap(), &shared[..]));
+
+    // Appending copies the shared data
+    response.append_ndjson(&object! { a: 1 }).unwrap();
+    assert_eq!(response.body().unwrap(), b"large document{\"a\":1}\n");
+    assert_eq!(&shared[..], b"large document");
+
+    assert!(Response::new(HTTP_204_NO_CONTENT).share_body().is_none());
+}
+
+#[test]
+fn json_cached() {
+    let mut response = Response::new(HTTP_200_OK);
+    assert!(response.json_cached().is_err());
+
+    response.set_json(&object! { name: "John" }).unwrap();
+    let first = response.json_cached().unwrap() as *const json::JsonValue;
+    assert_eq!(response.json_cached().unwrap()["name"], "John");
+    assert!(std::ptr::eq(first, response.json_cached().unwrap()));
+
+    response.set_body(b"{\"name\": \"Jane\"}".to_vec()).unwrap();
+    assert_eq!(response.json_cached().unwrap()["name"], "Jane");
+
+    response.set_body(b"{broken".to_vec()).unwrap();
+    assert!(response.json_cached().is_err());
+
+    response.set_body(Vec::new()).unwrap();
+    response.append_ndjson(&object! { n: 1 }).unwrap();
+    assert_eq!(response.json_cached().unwrap()["n"], 1);
+    response.append_ndjson(&object! { n: 2 }).unwrap();
+    assert!(response.json_cached().is_err());
+}
+
+#[test]
+fn equivalent() {
+    let options = EquivalenceOptions::default();
+    let mut first = Response::new(HTTP_200_OK);
+    first.insert_header("Date", "Tue, 15 Nov 1994 08:12:31 GMT")
+         .insert_header("Content-Type", "text/plain");
+    first.insert_cookie(SetCookie::new("session", "1"));
+    let mut second = Response::new(HTTP_200_OK);
+    second.insert_header("content-type", "text/plain");
+    second.insert_cookie(SetCookie::new("session", "1"));
+    assert!(first.equivalent(&second, &options));
+
+    second.insert_cookie(SetCookie::new("theme", "dark"));
+    assert!(!first.equivalent(&second, &options));
+    assert!(!first.equivalent(&Response::new(HTTP_404_NOT_FOUND), &options));
+}
+
+#[test]
+fn json_constructor() {
+    let data = object! { name: "John", tags: ["a", "b"] };
+    let response = Response::json(HTTP_200_OK, &data).unwrap();
+    assert_eq!(response.status_code(), HTTP_200_OK);
+    assert_eq!(response.headers().get(CONTENT_TYPE), Some(APPLICATION_JSON));
+    assert_eq!(response.json().unwrap(), data);
+}
+
+#[test]
+fn validated_status_code() {
+    assert!(StatusCode::try_from(99).is_err());
+    assert!(StatusCode::try_from(600).is_err());
+    assert!(Response::try_new(9999).is_err());
+    assert_eq!(Response::new(9999).status(), None);
+
+    let status = Response::try_new(HTTP_503_SERVICE_UNAVAILABLE).unwrap().status().unwrap();
+    assert!(status.is_server_error());
+    assert_eq!(status, HTTP_503_SERVICE_UNAVAILABLE);
+    assert_eq!(status.to_string(), "503 Service Unavailable");
+    assert_eq!(u16::from(status), 503);
+
+    let unknown = StatusCode::try_from(299).unwrap();
+    assert!(unknown.is_success());
+    assert_eq!(unknown.reason_phrase(), None);
+    assert_eq!(unknown.canonical(), HTTP_200_OK);
+    assert_eq!(unknown.to_string(), "299");
+}
+
+#[test]
+fn custom_status_code() {
+    assert!(register_status_code(1000, "Too long").is_err());
+    assert!(StatusCode::try_from(799).is_err());
+    register_status_code(799, "Vendor Hold").unwrap();
+
+    let status = StatusCode::try_from(799).unwrap();
+    assert_eq!(status.class(), StatusClass::Custom);
+    assert_eq!(status.canonical(), status);
+    assert_eq!(reason_phrase(799), Some("Vendor Hold"));
+    assert_eq!(status.to_string(), "799 Vendor Hold");
+}
+
+#[test]
+fn allowed_methods() {
+    let mut response = Response::new(HTTP_204_NO_CONTENT);
+    assert!(response.allowed_methods().is_empty());
+
+    response.set_allowed_methods(&[]);
+    assert_eq!(response.headers().get(ALLOW), Some(""));
+
+    response.insert_header(ALLOW, "GET,PUT , PROPFIND, get, GET, PATCH");
+    assert_eq!(response.allowed_methods(), vec![HttpMethod::GET, HttpMethod::PUT, HttpMethod::PATCH]);
+
+    let response = Response::method_not_allowed(&[HttpMethod::POST, HttpMethod::OPTIONS]);
+    assert_eq!(response.status_code(), HTTP_405_METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers().get(ALLOW), Some("POST, OPTIONS"));
+}
+
+#[test]
+fn locations() {
+    // RFC 3986, section 5.4
+    let base = "http://a/b/c/d;p?q";
+    let cases = [
+        ("g:h", "g:h"), ("g", "http://a/b/c/g"), ("./g", "http://a/b/c/g"), ("g/", "http://a/b/c/g/"),
+        ("/g", "http://a/g"), ("//g", "http://g"), ("?y", "http://a/b/c/d;p?y"), ("g?y", "http://a/b/c/g?y"),
+        ("#s", "http://a/b/c/d;p?q#s"), ("", "http://a/b/c/d;p?q"), (".", "http://a/b/c/"),
+        ("..", "http://a/b/"), ("../..", "http://a/"), ("../../../g", "http://a/g"), ("g;x=1/../y", "http://a/b/c/y")
+    ];
+    for (reference, expected) in cases {
+        let mut response = Response::new(HTTP_200_OK);
+        response.set_content_location(reference);
+        assert_eq!(response.content_location(base).as_deref(), Some(expected), "{}", reference);
+    }
+
+    let response = Response::created_at("https://other.com/items/1");
+    assert_eq!(response.status_code(), HTTP_201_CREATED);
+    assert_eq!(response.location("https://service.com/items").as_deref(), Some("https://other.com/items/1"));
+    assert_eq!(response.location("https://service.com"), Some("https://other.com/items/1".to_string()));
+    assert!(response.content_location("https://service.com").is_none());
+    assert_eq!(Response::created_at("7").location("https://service.com").as_deref(), Some("https://service.com/7"));
+}
+
+#[test]
+fn redirect_history() {
+    let response = Response::new(HTTP_200_OK);
+    assert_eq!(response.redirect_count(), 0);
+    assert!(response.final_url().is_none());
+    assert!(response.original_url().is_none());
+
+    let mut found = Response::new(HTTP_302_FOUND);
+    found.insert_header(LOCATION, "https://cdn.com/a?x=1");
+    let mut see_other = Response::new(HTTP_303_SEE_OTHER);
+    see_other.insert_header(LOCATION, "../b");
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.push_history(Exchange::new(Request::post("http://service.com/files/a"), found))
+            .push_history(Exchange::new(Request::get("https://cdn.com/a?x=1"), see_other));
+    assert_eq!(response.redirect_count(), 2);
+    assert_eq!(response.original_url(), Some("http://service.com/files/a"));
+    assert_eq!(response.final_url().as_deref(), Some("https://cdn.com/b"));
+    assert_eq!(response.history()[0].redirect_url().as_deref(), Some("https://cdn.com/a?x=1"));
+
+    let copy = response.clone();
+    response.set_history(Vec::new());
+    assert_eq!(copy.redirect_count(), 2);
+    assert_eq!(response.redirect_count(), 0);
+}
+
+#[test]
+fn content_sniffing() {
+    let samples: [(&[u8], &str); 14] = [
+        (b"%PDF-1.7\n", "application/pdf"),
+        (b"\xff\xd8\xff\xe0\0\x10JFIF", "image/jpeg"),
+        (b"GIF89a\x01\0", "image/gif"),
+        (b"RIFF\x24\0\0\0WEBPVP8 ", "image/webp"),
+        (b"\0\0\0\x20ftypisom", "video/mp4"),
+        (b"\x1f\x8b\x08\0\0\0\0\0", "application/gzip"),
+        (b"PK\x03\x04\x14\0", "application/zip"),
+        (b"  \n<!doctype html>\n<html>", "text/html"),
+        (b"<p>Hello</p>", "text/html"),
+        (b"<?xml version=\"1.0\"?><a/>", "text/xml"),
+        (b" {\"id\": [1, 2]}\n", APPLICATION_JSON),
+        (b"{not json", "text/plain"),
+        (b"caf\xc3\xa9\tok\r\n", "text/plain"),
+        (b"\x01\x02\x03", APPLICATION_OCTET_STREAM)
+    ];
+    for (body, expected) in samples {
+        let mut response = Response::new(HTTP_200_OK);
+        response.set_body(body.to_vec()).unwrap();
+        assert_eq!(response.sniff_content_type(), Some(expected), "{:?}", body);
+    }
+    assert_eq!(Response::new(HTTP_204_NO_CONTENT).sniff_content_type(), None);
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(b"%PDF-1.4".to_vec()).unwrap();
+    response.insert_header("Content-Type", "Application/Octet-Stream; x=1");
+    assert!(response.fix_content_type());
+    assert_eq!(response.headers().get("content-type"), Some("application/pdf"));
+
+    response.insert_header("Content-Type", "text/plain");
+    assert!(!response.fix_content_type());
+    assert_eq!(response.headers().get("content-type"), Some("text/plain"));
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(vec![0, 1, 2]).unwrap();
+    assert!(response.fix_content_type());
+    assert_eq!(response.headers().get("content-type"), Some(APPLICATION_OCTET_STREAM));
+    assert!(!response.fix_content_type());
+}
+
+#[test]
+fn text_encodings() {
+    let mut response = Response::new(HTTP_200_OK);
+    assert_eq!(response.detected_encoding(), None);
+    assert_eq!(response.text().unwrap(), "");
+
+    // UTF-16 JSON with BOM and a contradicting charset
+    let mut body = vec![0xff, 0xfe];
+    body.extend("{\"name\": \"Zoë\"}".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    response.insert_header("Content-Type", "application/json; charset=utf-8")
+            .set_body(body).unwrap();
+    assert_eq!(response.detected_encoding(), Some(TextEncoding::Utf16Le));
+    assert_eq!(response.json().unwrap()["name"], "Zoë");
+
+    let body: Vec<u8> = "hé".encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect();
+    response.insert_header("Content-Type", "text/plain; charset=\"UTF-16\"")
+            .set_body(body).unwrap();
+    assert_eq!(response.detected_encoding(), Some(TextEncoding::Utf16Be));
+    assert_eq!(response.text().unwrap(), "hé");
+
+    response.insert_header("Content-Type", "text/plain; charset=utf-16le")
+            .set_body(vec![0x41]).unwrap();
+    assert_eq!(response.text().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    response.insert_header("Content-Type", "text/plain; charset=koi8-r")
+            .set_body(b"\xef\xbb\xbf[1]".to_vec()).unwrap();
+    assert_eq!(response.detected_encoding(), Some(TextEncoding::Utf8));
+    assert_eq!(response.json().unwrap()[0], 1);
+    assert!(matches!(response.text().unwrap(), std::borrow::Cow::Borrowed("[1]")));
+
+    assert_eq!(TextEncoding::from_label("LATIN1"), Some(TextEncoding::Latin1));
+    assert_eq!(TextEncoding::Utf16Le.to_string(), "UTF-16LE");
+}
+
+#[test]
+fn error_for_status() {
+    let mut response = Response::new(HTTP_302_FOUND);
+    response.set_body(b"moved".to_vec()).unwrap();
+    assert_eq!(response.error_for_status_ref().ok().unwrap().status_code(), HTTP_302_FOUND);
+    assert_eq!(response.error_for_status().ok().unwrap().body().unwrap(), b"moved");
+
+    let mut problem = Problem::new(HTTP_400_BAD_REQUEST);
+    problem.detail = Some("Missing name".to_string());
+    let error = Response::problem(problem).unwrap().error_for_status().err().unwrap();
+    assert!(error.is_client_error() && !error.is_server_error());
+    assert_eq!(error.code, HTTP_400_BAD_REQUEST);
+    assert_eq!(error.reason, "Bad Request");
+    assert_eq!(error.body.as_ref().unwrap()["detail"], "Missing name");
+    assert_eq!(error.problem().unwrap().detail.as_deref(), Some("Missing name"));
+
+    let mut response = Response::new(HTTP_503_SERVICE_UNAVAILABLE);
+    response.insert_header("Content-Type", "text/plain")
+            .set_body(b"{\"not\": \"json\"}".to_vec()).unwrap();
+    let error = response.error_for_status_ref().err().unwrap();
+    assert!(error.is_server_error());
+    assert!(error.body.is_none());
+    assert_eq!(format!("{:?}", error), "StatusError { code: 503, reason: \"Service Unavailable\", body: None, .. }");
+
+    let io_error: std::io::Error = Response::new(699).error_for_status().err().unwrap().into();
+    assert_eq!(io_error.to_string(), "Error status 699");
+    let inner = io_error.into_inner().unwrap().downcast::<StatusError>().unwrap();
+    assert_eq!(inner.into_response().status_code(), 699);
+}
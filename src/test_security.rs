@@ -0,0 +1,30 @@
+use crate::*;
+use crate::security::*;
+
+#[test]
+fn default_preset() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.apply_security_headers(SecurityPreset::new());
+
+    assert_eq!(response.headers().get("strict-transport-security"), Some("max-age=63072000; includeSubDomains"));
+    assert_eq!(response.headers().get("x-content-type-options"), Some("nosniff"));
+    assert_eq!(response.headers().get("x-frame-options"), Some("DENY"));
+    assert_eq!(response.headers().get("referrer-policy"), Some("strict-origin-when-cross-origin"));
+    assert!(response.headers().contains_key("permissions-policy"));
+}
+
+#[test]
+fn overrides() {
+    let preset = SecurityPreset::default()
+        .with_strict_transport_security(None)
+        .with_referrer_policy(Some("no-referrer"));
+    assert_eq!(preset.headers().len(), 4);
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("X-Frame-Options", "SAMEORIGIN");
+    response.apply_security_headers(preset);
+
+    assert!(!response.headers().contains_key(STRICT_TRANSPORT_SECURITY));
+    assert_eq!(response.headers().get(REFERRER_POLICY), Some("no-referrer"));
+    assert_eq!(response.headers().get(X_FRAME_OPTIONS), Some("SAMEORIGIN"));
+}
@@ -0,0 +1,122 @@
+use crate::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+struct Options {
+    filter: &'static str,
+    limit: u32,
+    verbose: bool,
+    cursor: Option<String>
+}
+
+#[test]
+fn query_from() {
+    let mut request = Request::get("http://service.com/items");
+    request.insert_param("limit", "10")
+           .insert_param("token", "abc");
+
+    request.query_from(&Options { filter: "a&b=c", limit: 50, verbose: true, cursor: None }).unwrap();
+    assert_eq!(request.params().get("filter"), Some("a&b=c"));
+    assert_eq!(request.params().get("limit"), Some("50"));
+    assert_eq!(request.params().get("verbose"), Some("true"));
+    assert_eq!(request.params().get("token"), Some("abc"));
+    assert!(request.params().get("cursor").is_none());
+
+    let mut map = BTreeMap::new();
+    map.insert("sort", "name");
+    request.query_from(&map).unwrap();
+    assert_eq!(request.params().get("sort"), Some("name"));
+
+    let nested = BTreeMap::from([("inner", BTreeMap::from([("a", 1)]))]);
+    assert!(request.query_from(&nested).is_err());
+    assert!(request.query_from(&42).is_err());
+}
+
+#[test]
+fn request_structural() {
+    let mut request = Request::post("http://service.com/users");
+    request.insert_param("notify", "true")
+           .insert_cookie("session", "1234")
+           .insert_header("Content-Type", "text/plain")
+           .insert_header("Accept", "*/*")
+           .set_body(b"John".to_vec()).unwrap();
+    request.set_version(HttpVersion::Http2);
+
+    let json = serde_json::to_string(&request).unwrap();
+    assert_eq!(json, "{\"schema_version\":1,\"method\":\"POST\",\"url\":\"http://service.com/users\",\"version\":\"HTTP/2\",\
+        \"headers\":{\"Content-Type\":\"text/plain\",\"Accept\":\"*/*\"},\"params\":{\"notify\":\"true\"},\
+        \"cookies\":{\"session\":\"1234\"},\"body\":\"Sm9obg==\"}");
+
+    let parsed: Request = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.method(), HttpMethod::POST);
+    assert_eq!(parsed.url(), "http://service.com/users");
+    assert_eq!(parsed.version(), HttpVersion::Http2);
+    assert_eq!(parsed.headers().iter().collect::<Vec<(&str, &str)>>(), vec![("Content-Type", "text/plain"), ("Accept", "*/*")]);
+    assert_eq!(parsed.params().get("notify"), Some("true"));
+    assert_eq!(parsed.cookies().get("session"), Some("1234"));
+    assert_eq!(parsed.body().unwrap(), b"John");
+
+    let minimal: Request = serde_json::from_str("{\"method\":\"GET\",\"url\":\"/\"}").unwrap();
+    assert!(minimal.body().is_none());
+    assert!(minimal.headers().is_empty());
+    assert!(serde_json::from_str::<Request>("{\"method\":\"get\",\"url\":\"/\"}").is_err());
+    assert!(serde_json::from_str::<Request>("{\"method\":\"GET\",\"url\":\"/\",\"body\":\"%%\"}").is_err());
+    assert!(serde_json::from_str::<Request>("{\"method\":\"GET\",\"url\":\"/\",\"headers\":{\"X\":\"a\\nb\"}}").is_err());
+}
+
+#[test]
+fn response_structural() {
+    let mut moved = Response::new(HTTP_301_MOVED_PERMANENTLY);
+    moved.insert_header("Location", "/v2");
+
+    let mut response = Response::new(HTTP_401_UNAUTHORIZED);
+    response.insert_cookie(wcookie::SetCookie::new("session", "1"))
+            .insert_auth_headers("Bearer");
+    response.set_body(vec![0, 255]).unwrap();
+    response.push_history(Exchange::new(Request::get("http://service.com/v1"), moved));
+
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["status"], 401);
+    assert_eq!(json["cookies"][0], "session=1");
+    assert_eq!(json["body"], "AP8=");
+    assert_eq!(json["history"][0]["response"]["headers"]["Location"], "/v2");
+
+    let parsed: Response = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.status_code(), HTTP_401_UNAUTHORIZED);
+    assert_eq!(parsed.cookies()[0].name, "session");
+    assert_eq!(parsed.auth_headers(), &vec!["Bearer".to_string()]);
+    assert_eq!(parsed.body().unwrap(), &[0, 255]);
+    assert_eq!(parsed.final_url().as_deref(), Some("http://service.com/v2"));
+
+    let plain = serde_json::to_value(Response::new(HTTP_204_NO_CONTENT)).unwrap();
+    assert!(plain.get("history").is_none());
+    assert!(plain["body"].is_null());
+    let error = serde_json::from_str::<Response>("{\"schema_version\":1,\"status\":9999}").err().unwrap();
+    assert!(error.to_string().starts_with("Invalid status code 9999"));
+}
+
+#[test]
+fn schema_versions() {
+    let json = serde_json::to_value(Request::get("http://service.com/")).unwrap();
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(serde_json::to_value(Response::new(HTTP_200_OK)).unwrap()["schema_version"], SCHEMA_VERSION);
+
+    // Recorded before the schema was versioned
+    let exchange: Exchange = serde_json::from_str("{\"request\":{\"method\":\"PUT\",\"url\":\"http://service.com/users/1\",\
+        \"version\":\"HTTP/1.1\",\"headers\":{\"Content-Type\":\"text/plain\"},\"params\":{},\"cookies\":{},\
+        \"body\":\"Sm9obg==\"},\"response\":{\"status\":204,\"version\":\"HTTP/1.1\",\"headers\":{},\
+        \"cookies\":[\"session=1\"],\"auth\":[],\"proxy_auth\":[],\"body\":null}}").unwrap();
+    assert_eq!(exchange.request.method(), HttpMethod::PUT);
+    assert_eq!(exchange.request.body().unwrap(), b"John");
+    assert_eq!(exchange.response.status_code(), HTTP_204_NO_CONTENT);
+    assert_eq!(exchange.response.cookies()[0].name, "session");
+    let upgraded = serde_json::to_value(&exchange).unwrap();
+    assert_eq!(upgraded["request"]["schema_version"], SCHEMA_VERSION);
+    assert_eq!(upgraded["response"]["schema_version"], SCHEMA_VERSION);
+
+    let newer = format!("{{\"schema_version\":{},\"method\":\"GET\",\"url\":\"/\"}}", SCHEMA_VERSION + 1);
+    let error = serde_json::from_str::<Request>(&newer).err().unwrap();
+    assert!(error.to_string().starts_with("Unsupported schema version 2"));
+    assert!(serde_json::from_str::<Response>("{\"schema_version\":99,\"status\":200}").is_err());
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use crate::signed_url::*;
+use hmac::Mac;
+use std::io::ErrorKind;
+use std::time::{Duration, UNIX_EPOCH};
+
+fn hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+fn digests() {
+    let hmac = |key: &[u8], data: &[u8]| hex(&mac(key, data).finalize().into_bytes());
+
+    // RFC 4231, test cases 2 and 6
+    assert_eq!(hmac(b"Jefe", b"what do ya want for nothing?"),
+               "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    assert_eq!(hmac(&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First"),
+               "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54");
+}
+
+#[test]
+fn sign_and_verify() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let signer = UrlSigner::new("secret").with_param_names("sig", "exp");
+
+    let mut request = Request::put("https://uploads.example.com/files/a b.txt?size=10");
+    request.insert_param("type", "text/plain");
+    signer.sign(&mut request, now + Duration::from_secs(60));
+    assert_eq!(request.params().get("exp"), Some("1700000060"));
+    assert_eq!(request.params().get("sig").map(str::len), Some(64));
+    assert!(signer.verify(&request, now).is_ok());
+    assert_eq!(request.verify_signed_url("secret", now).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // Relative request as received by the server, with the params in another order
+    let mut received = Request::put("/files/a b.txt");
+    for (key, value) in request.params().iter().collect::<Vec<(&str, &str)>>().into_iter().rev() {
+        received.insert_param(key.to_string(), value.to_string());
+    }
+    received.insert_param("size", "10");
+    assert!(signer.verify(&received, now).is_ok());
+
+    let expired = signer.verify(&received, now + Duration::from_secs(60)).unwrap_err();
+    assert_eq!(expired.kind(), ErrorKind::PermissionDenied);
+
+    let mut tampered = received.clone();
+    tampered.insert_param("size", "1000");
+    assert_eq!(signer.verify(&tampered, now).unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    let mut tampered = received.clone();
+    tampered.insert_param("exp", "1800000000");
+    assert_eq!(signer.verify(&tampered, now).unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    let mut other_method = Request::get("/files/a b.txt");
+    *other_method.params_mut() = received.params().clone();
+    assert_eq!(signer.verify(&other_method, now).unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    let mut resigned = request.clone();
+    resigned.sign_url("secret", now + Duration::from_secs(10));
+    assert!(resigned.verify_signed_url("secret", now).is_ok());
+    assert_eq!(resigned.params().get("X-Expires"), Some("1700000010"));
+}
+
+#[test]
+fn verify_invalid() {
+    let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut request = Request::get("/files/report.pdf");
+    request.sign_url("secret", now + Duration::from_secs(60));
+
+    // Upper case signatures are accepted, malformed ones are rejected
+    let signature = request.params().get(X_SIGNATURE).unwrap().to_string();
+    let mut upper = request.clone();
+    upper.insert_param(X_SIGNATURE, signature.to_ascii_uppercase());
+    assert!(upper.verify_signed_url("secret", now).is_ok());
+    for malformed in [&signature[1..], "+f", "zz", ""] {
+        let mut tampered = request.clone();
+        tampered.insert_param(X_SIGNATURE, malformed.to_string());
+        assert_eq!(tampered.verify_signed_url("secret", now).unwrap_err().kind(), ErrorKind::PermissionDenied);
+    }
+
+    // Expiry times beyond the range of SystemTime do not overflow
+    let mut request = Request::get("/files/report.pdf");
+    request.insert_param(X_EXPIRES, "18446744073709551615");
+    let signature = mac(b"secret", b"GET\n/files/report.pdf\nX-Expires=18446744073709551615").finalize().into_bytes();
+    request.insert_param(X_SIGNATURE, hex(&signature));
+    let error = request.verify_signed_url("secret", now).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+    assert_eq!(error.to_string(), "Expired URL");
+}
@@ -0,0 +1,34 @@
+use crate::*;
+
+#[test]
+fn same_as_default_parser() {
+    let document = r#"{
+        "name": "José \"Pepe\"",
+        "age": 42,
+        "balance": -12.5,
+        "big": 18446744073709551615,
+        "small": -9223372036854775808,
+        "active": true,
+        "manager": null,
+        "tags": ["a", [], {}, [1, 2.5e3]],
+        "address": { "city": "Madrid", "zip": "28001" }
+    }"#;
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(document.as_bytes().to_vec()).unwrap();
+
+    let parsed = response.json().unwrap();
+    assert_eq!(parsed, json::parse(document).unwrap());
+    assert_eq!(parsed["name"], "José \"Pepe\"");
+    assert_eq!(parsed["big"].as_u64(), Some(u64::MAX));
+    assert_eq!(parsed["tags"][3][1], 2500.0);
+}
+
+#[test]
+fn invalid_documents() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_body(b"{\"a\": }".to_vec()).unwrap();
+    assert_eq!(response.json().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    response.set_body(vec![b'"', 0xff, b'"']).unwrap();
+    assert!(response.json().is_err());
+}
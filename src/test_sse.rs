@@ -0,0 +1,78 @@
+use crate::*;
+use crate::sse::{Event, EventParser};
+use std::time::Duration;
+
+#[test]
+fn encode1() {
+    let mut event = Event::new("first\nsecond");
+    event.id = Some("42".to_string());
+    event.event = Some("update".to_string());
+    event.retry = Some(Duration::from_millis(3000));
+
+    assert_eq!(event.to_string(), "id: 42\nevent: update\nretry: 3000\ndata: first\ndata: second\n\n");
+}
+
+#[test]
+fn encode_line_breaks() {
+    let mut event = Event::new("ok");
+    event.set_id("4\r\n2\0").set_event("update\n\ndata: injected");
+    assert_eq!(event.id.as_deref(), Some("42"));
+    assert_eq!(event.event.as_deref(), Some("updatedata: injected"));
+
+    // Fields set directly cannot inject other fields nor events either
+    event.id = Some("1\nretry: 1".to_string());
+    event.event = Some("a\r\rb".to_string());
+    assert_eq!(event.to_string(), "id: 1retry: 1\nevent: ab\ndata: ok\n\n");
+    assert_eq!(EventParser::new().feed(event.to_string().as_bytes()).len(), 1);
+}
+
+#[test]
+fn roundtrip1() {
+    let mut response = Response::new(HTTP_200_OK);
+
+    let mut first = Event::new("one");
+    first.id = Some("1".to_string());
+    let second = Event::new("");
+
+    response.append_event(&first).unwrap()
+            .append_event(&second).unwrap();
+
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/event-stream");
+    assert_eq!(response.events(), vec![first, second]);
+}
+
+#[test]
+fn parse_chunks() {
+    let stream = b"\xEF\xBB\xBF: comment\r\nevent: add\r\ndata: a\r\ndata:b\r\n\r\nid: 7\rdata: c\r\rdata: incomplete";
+    let mut parser = EventParser::new();
+    let mut events = Vec::new();
+    for chunk in stream.chunks(3) {
+        events.extend(parser.feed(chunk));
+    }
+
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event.as_deref(), Some("add"));
+    assert_eq!(events[0].data, "a\nb");
+    assert_eq!(events[1].id.as_deref(), Some("7"));
+    assert_eq!(events[1].data, "c");
+    assert_eq!(parser.last_event_id(), Some("7"));
+}
+
+#[test]
+fn parse_ignored() {
+    let mut parser = EventParser::new();
+    // Events without data are not dispatched and invalid retry values are ignored
+    let events = parser.feed(b"event: empty\n\nretry: 1x\ndata: ok\n\n");
+
+    assert_eq!(events, vec![Event::new("ok")]);
+}
+
+#[test]
+fn append_too_large() {
+    let mut response = Response::new(HTTP_200_OK);
+    response.set_max_body_size(16);
+
+    response.append_event(&Event::new("one")).unwrap();
+    assert!(response.append_event(&Event::new("two")).is_err());
+    assert_eq!(response.events(), vec![Event::new("one")]);
+}
@@ -0,0 +1,88 @@
+use crate::*;
+use crate::mock::MockRouter;
+use crate::tower::*;
+use crate::transport::{AsyncTransport, Transport};
+use std::future::{Future, Ready, ready};
+use std::io::{Error, ErrorKind};
+use std::task::{Context, Poll, Waker};
+use tower_service::Service;
+
+/// Polls a future that never waits, as in-process transports and services do
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+fn router() -> MockRouter {
+    let mut router = MockRouter::new();
+    router.route(HttpMethod::GET, "/users", Response::new(HTTP_200_OK))
+          .fallback(Response::new(HTTP_404_NOT_FOUND));
+    router
+}
+
+/// Service that rejects the requests without a host
+#[derive(Clone)]
+struct HostRequired;
+
+impl Service<Request> for HostRequired {
+    type Response = Response;
+    type Error = &'static str;
+    type Future = Ready<Result<Response, &'static str>>;
+
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        ready(match request.host() {
+            Some(_) => Ok(Response::new(HTTP_204_NO_CONTENT)),
+            None => Err("missing host")
+        })
+    }
+}
+
+/// Transport that always fails
+#[derive(Clone)]
+struct Refused;
+
+impl Transport for Refused {
+    fn send(&self, _request: Request) -> Result<Response, Error> {
+        Err(Error::from(ErrorKind::ConnectionRefused))
+    }
+}
+
+#[test]
+fn transport_services() {
+    let mut service = TransportService::new(router());
+    assert!(block_on(std::future::poll_fn(|context| service.poll_ready(context))).is_ok());
+    let response = service.call(Request::get("https://service.com/users")).into_inner().unwrap();
+    assert_eq!(response.status_code(), HTTP_200_OK);
+
+    let mut service = AsyncTransportService::new(router());
+    let mut clone = service.clone();
+    let response = block_on(clone.call(Request::get("https://service.com/items"))).unwrap();
+    assert_eq!(response.status_code(), HTTP_404_NOT_FOUND);
+    assert_eq!(block_on(service.call(Request::get("https://service.com/users"))).unwrap().status_code(), HTTP_200_OK);
+}
+
+#[test]
+fn service_transport() {
+    let transport = ServiceTransport::new(AsyncTransportService::new(router()));
+    let response = block_on(transport.send(Request::get("https://service.com/users"))).unwrap();
+    assert_eq!(response.status_code(), HTTP_200_OK);
+
+    let transport = ServiceTransport::new(HostRequired);
+    assert_eq!(block_on(transport.send(Request::get("https://service.com"))).unwrap().status_code(), HTTP_204_NO_CONTENT);
+    let error = block_on(transport.send(Request::get("/users"))).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Other);
+    assert_eq!(error.to_string(), "missing host");
+
+    let failing = ServiceTransport::new(TransportService::new(Refused));
+    let error = block_on(failing.send(Request::get("https://service.com"))).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::ConnectionRefused);
+}
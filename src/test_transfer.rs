@@ -0,0 +1,53 @@
+use crate::*;
+use crate::transfer::*;
+
+#[test]
+fn transfer_encodings() {
+    let mut request = Request::post("http://service.com/upload");
+    assert!(request.transfer_encodings().is_empty());
+    request.insert_header("Transfer-Encoding", "X-Gzip, br;level=4 , Chunked");
+    assert_eq!(request.transfer_encodings(),
+               vec![TransferCoding::Gzip, TransferCoding::Other("br;level=4".to_string()), TransferCoding::Chunked]);
+    assert!(request.check_framing().is_ok());
+
+    request.insert_header("Transfer-Encoding", "chunked, gzip");
+    assert!(request.check_framing().is_err());
+    request.insert_header("Transfer-Encoding", "chunked, chunked");
+    assert!(request.check_framing().is_err());
+}
+
+#[test]
+fn te() {
+    let te = Te::parse("deflate, gzip;q=0.3, compress;q=0, chunked, trailers, br;q=x");
+    assert!(te.trailers);
+    assert_eq!(te.codings, vec![(TransferCoding::Deflate, 1.0), (TransferCoding::Gzip, 0.3), (TransferCoding::Compress, 0.0)]);
+    assert!(te.accepts(&TransferCoding::Chunked));
+    assert!(te.accepts(&TransferCoding::Gzip));
+    assert!(!te.accepts(&TransferCoding::Compress));
+
+    let request = Request::get("http://service.com/");
+    assert!(!request.accepts_trailers());
+    assert_eq!(request.te(), Te::default());
+}
+
+#[test]
+fn serializer_refuses_ambiguous_framing() {
+    let mut request = Request::post("http://service.com/upload");
+    request.insert_header("Transfer-Encoding", "chunked")
+           .insert_header("Content-Length", "3")
+           .set_body(b"abc".to_vec()).unwrap();
+    assert_eq!(wire::request_to_bytes(&request).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+
+    request.headers_mut().remove("Content-Length");
+    assert!(wire::request_to_bytes(&request).is_ok());
+    request.insert_header("Transfer-Encoding", "gzip");
+    assert!(wire::request_to_bytes(&request).is_err());
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("Transfer-Encoding", "gzip").set_body(b"raw".to_vec()).unwrap();
+    assert!(wire::response_to_bytes(&response).unwrap().ends_with(b"Transfer-Encoding: gzip\r\n\r\nraw"));
+    response.insert_header("Content-Length", "3");
+    assert!(wire::response_to_bytes(&response).is_err());
+
+    assert!(wire::parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked, gzip\r\n\r\n").is_err());
+}
@@ -0,0 +1,94 @@
+use crate::*;
+use crate::middleware::{DefaultHeader, Pipeline};
+use crate::mock::MockRouter;
+use crate::transport::Transport;
+use crate::matchers::header;
+use std::io::Error;
+use std::sync::Arc;
+
+/// Transport that always fails
+struct Unreachable;
+
+impl Transport for Unreachable {
+    fn send(&self, _request: Request) -> Result<Response, Error> {
+        Err(Error::other("connection refused"))
+    }
+}
+
+fn router() -> MockRouter {
+    let mut router = MockRouter::new();
+    router.route_when(HttpMethod::GET, "/users", header("Authorization", "Bearer 1234"), Response::new(HTTP_200_OK))
+          .fallback(Response::new(HTTP_401_UNAUTHORIZED));
+    router
+}
+
+#[test]
+fn mock_transport() {
+    let router = router();
+    assert_eq!(router.send(Request::get("https://service.com/users")).unwrap().status_code(), HTTP_401_UNAUTHORIZED);
+
+    let shared: Arc<dyn Transport> = Arc::new(router);
+    let boxed: Box<dyn Transport> = Box::new(shared.clone());
+    assert_eq!(boxed.send(Request::get("https://service.com/users")).unwrap().status_code(), HTTP_401_UNAUTHORIZED);
+}
+
+#[test]
+fn pipeline_send() {
+    let mut pipeline = Pipeline::new();
+    pipeline.add(DefaultHeader::new("Authorization", "Bearer 1234"));
+
+    let response = pipeline.send(Request::get("https://service.com/users"), &router()).unwrap();
+    assert_eq!(response.status_code(), HTTP_200_OK);
+
+    assert!(pipeline.send(Request::get("https://service.com/users"), &Unreachable).is_err());
+}
+
+#[cfg(feature = "async")]
+mod asynchronous {
+    use super::*;
+    use crate::transport::AsyncTransport;
+    use std::future::Future;
+    use std::task::{Context, Poll, Waker};
+
+    /// Polls a future that never waits, as in-process transports do
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    /// Asynchronous transport that always fails
+    struct Unreachable;
+
+    impl AsyncTransport for Unreachable {
+        async fn send(&self, _request: Request) -> Result<Response, Error> {
+            Err(Error::other("connection refused"))
+        }
+    }
+
+    fn assert_send<F: Future + Send>(future: F) -> F {
+        future
+    }
+
+    #[test]
+    fn mock_async_transport() {
+        let router = Arc::new(router());
+        let response = block_on(assert_send(AsyncTransport::send(&router, Request::get("https://service.com/users"))));
+        assert_eq!(response.unwrap().status_code(), HTTP_401_UNAUTHORIZED);
+    }
+
+    #[test]
+    fn pipeline_send_async() {
+        let mut pipeline = Pipeline::new();
+        pipeline.add(DefaultHeader::new("Authorization", "Bearer 1234"));
+
+        let response = block_on(pipeline.send_async(Request::get("https://service.com/users"), &router())).unwrap();
+        assert_eq!(response.status_code(), HTTP_200_OK);
+
+        assert!(block_on(pipeline.send_async(Request::get("https://service.com/users"), &Unreachable)).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use crate::*;
+use crate::upgrade::Protocol;
+
+#[test]
+fn parse() {
+    let protocols = Protocol::parse_list("HTTP/2.0, SHTTP/1.3, IRC/6.9 , RTA/x11, websocket");
+    assert_eq!(protocols.len(), 5);
+    assert_eq!(protocols[0], Protocol::new("HTTP").with_version("2.0"));
+    assert_eq!(protocols[4], Protocol::new("websocket"));
+    assert_eq!(protocols[3].to_string(), "RTA/x11");
+}
+
+#[test]
+fn requires_connection_option() {
+    let mut request = Request::get("http://service.com/chat");
+    request.insert_header("Upgrade", "websocket");
+    assert!(request.upgrade_protocols().is_empty());
+
+    request.insert_header("Connection", "keep-alive, upgrade");
+    assert_eq!(request.upgrade_protocols(), vec![Protocol::new("websocket")]);
+}
+
+#[test]
+fn set_upgrade() {
+    let mut request = Request::get("http://service.com/");
+    request.insert_header("Connection", "keep-alive");
+    request.set_upgrade(&[Protocol::new("h2c"), Protocol::new("HTTP").with_version("2.0")]);
+    assert_eq!(request.headers().get("upgrade"), Some("h2c, HTTP/2.0"));
+    assert_eq!(request.headers().get("connection"), Some("keep-alive, Upgrade"));
+
+    request.set_upgrade(&[Protocol::new("websocket")]);
+    assert_eq!(request.headers().get("connection"), Some("keep-alive, Upgrade"));
+
+    let response = Response::switching_protocols(&Protocol::new("websocket"));
+    assert_eq!(response.status_code(), HTTP_101_SWITCHING_PROTOCOLS);
+    assert!(response.is_switching_protocols());
+    assert!(!Response::new(HTTP_101_SWITCHING_PROTOCOLS).is_switching_protocols());
+}
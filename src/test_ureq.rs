@@ -0,0 +1,48 @@
+use crate::*;
+use crate::transport::Transport;
+
+#[test]
+fn to_ureq() {
+    let mut request = Request::put("http://service.com/users/1");
+    request.insert_param("lang", "en")
+           .insert_cookie("session", "1234")
+           .insert_header("Content-Type", "application/json");
+
+    let agent = ureq::Agent::new();
+    let converted = request.to_ureq(&agent);
+
+    assert_eq!(converted.method(), "PUT");
+    assert_eq!(converted.url(), "http://service.com/users/1?lang=en");
+    assert_eq!(converted.header("content-type"), Some("application/json"));
+    assert_eq!(converted.header("cookie"), Some("session=1234"));
+}
+
+#[test]
+fn from_ureq() {
+    let raw = "HTTP/1.1 403 Forbidden\r\n\
+               Content-Type: text/plain\r\n\
+               Set-Cookie: session=1234; Path=/; Secure\r\n\
+               Set-Cookie: invalid\r\n\
+               WWW-Authenticate: Bearer\r\n\
+               Vary: Accept\r\n\
+               Vary: Cookie\r\n\
+               \r\n\
+               forbidden";
+    let response = Response::from_ureq(raw.parse::<ureq::Response>().unwrap()).unwrap();
+
+    assert_eq!(response.status_code(), 403);
+    assert_eq!(response.cookies().len(), 1);
+    assert!(response.cookies()[0].secure);
+    assert_eq!(response.auth_headers(), &vec!["Bearer".to_string()]);
+    assert_eq!(response.headers().get("vary"), Some("Accept, Cookie"));
+    assert!(!response.headers().contains_key("set-cookie"));
+    assert_eq!(response.body().unwrap(), b"forbidden");
+}
+
+#[test]
+fn call_ureq_transport_error() {
+    let agent = ureq::Agent::new();
+    let request = Request::get("http://127.0.0.1:1/");
+    assert!(request.call_ureq(&agent).is_err());
+    assert!(agent.send(request).is_err());
+}
@@ -0,0 +1,33 @@
+use crate::*;
+use crate::via::Via;
+
+#[test]
+fn parse() {
+    let entries = Via::parse_list("1.0 fred, HTTP/1.1 p.example.net:8080 (Apache/1.1, beta), SPDY/3 cache,, broken");
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0], Via::new("1.0", "fred"));
+    assert_eq!(entries[1], Via::new("1.1", "p.example.net:8080").with_comment("Apache/1.1, beta"));
+    assert_eq!(entries[2].protocol.as_deref(), Some("SPDY"));
+    assert_eq!(entries[2].version, "3");
+}
+
+#[test]
+fn format() {
+    let mut via = Via::new("2", "edge").with_comment("cache");
+    assert_eq!(via.to_string(), "2 edge (cache)");
+    via.protocol = Some("QUIC".to_string());
+    assert_eq!(via.to_string(), "QUIC/2 edge (cache)");
+}
+
+#[test]
+fn append_and_loop_detection() {
+    let mut response = Response::new(HTTP_200_OK);
+    assert!(response.via().is_empty());
+    assert!(!response.contains_pseudonym("edge"));
+
+    response.append_via(&Via::new("1.1", "edge"))
+            .append_via(&Via::new("1.1", "gateway"));
+    assert_eq!(response.headers().get("via"), Some("1.1 edge, 1.1 gateway"));
+    assert!(response.contains_pseudonym("EDGE"));
+    assert!(!response.contains_pseudonym("origin"));
+}
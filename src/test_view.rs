@@ -0,0 +1,91 @@
+use crate::*;
+use crate::view::*;
+use std::io::ErrorKind;
+
+#[test]
+fn request_view() {
+    let data = b"PUT /files/1?v=2 HTTP/1.1\r\nHost: service.com\r\nVary: Accept\r\nvary: Cookie\r\n\
+                 Transfer-Encoding: chunked\r\n\r\n4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nExpires: never\r\n\r\n\
+                 GET / HTTP/1.0\r\n\r\n";
+    let view = RequestView::parse(data).unwrap();
+    assert_eq!(view.method(), "PUT");
+    assert_eq!(view.target(), "/files/1?v=2");
+    assert_eq!((view.path(), view.query()), ("/files/1", Some("v=2")));
+    assert_eq!(view.version(), HttpVersion::Http11);
+    assert_eq!(view.header("HOST"), Some("service.com"));
+    assert_eq!(view.header_values("Vary").collect::<Vec<_>>(), vec!["Accept", "Cookie"]);
+    assert_eq!(view.headers().count(), 4);
+    assert!(view.is_chunked());
+    assert!(view.body().starts_with(b"4;ext=1\r\n"));
+    assert_eq!(view.chunks().collect::<Vec<_>>(), vec![&b"Wiki"[..], b"pedia"]);
+    assert!(data[view.len()..].starts_with(b"GET / HTTP/1.0"));
+
+    let owned = view.to_owned().unwrap();
+    assert_eq!(owned.method(), HttpMethod::PUT);
+    assert_eq!(owned.params().get("v"), Some("2"));
+    assert_eq!(owned.body().unwrap(), b"Wikipedia");
+
+    let next = RequestView::parse(&data[view.len()..]).unwrap();
+    assert_eq!(next.version(), HttpVersion::Http10);
+    assert_eq!(next.headers().count(), 0);
+    assert!(next.body().is_empty() && next.chunks().next().is_none());
+    assert_eq!(next.len(), data.len() - view.len());
+}
+
+#[test]
+fn response_view() {
+    let view = ResponseView::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 2, 2\r\n\r\nokextra").unwrap();
+    assert_eq!((view.status_code(), view.reason()), (HTTP_200_OK, "OK"));
+    assert_eq!(view.body(), b"ok");
+    assert_eq!(view.chunks().collect::<Vec<_>>(), vec![&b"ok"[..]]);
+
+    let view = ResponseView::parse(b"HTTP/1.0 404 Not Found\r\nContent-Type: text/plain\r\n\r\nuntil close").unwrap();
+    assert_eq!(view.body(), b"until close");
+    assert_eq!(view.to_owned().unwrap().body().unwrap(), b"until close");
+
+    let data = b"HTTP/1.1 304 Not Modified\r\nETag: \"1\"\r\n\r\nHTTP/1.1 200 OK\r\n\r\n";
+    let view = ResponseView::parse(data).unwrap();
+    assert!(view.body().is_empty());
+    assert_eq!(ResponseView::parse(&data[view.len()..]).unwrap().status_code(), HTTP_200_OK);
+}
+
+#[test]
+fn view_errors() {
+    let invalid = [
+        &b"GET /users HTTP/2\r\n\r\n"[..],
+        b"GET /users HTTP/1.1\r\nHost : a.com\r\n\r\n",
+        b"GET /users HTTP/1.1\r\nX-Folded: a\r\n b\r\n\r\n",
+        b"POST /users HTTP/1.1\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+        b"POST /users HTTP/1.1\r\nContent-Length: 3, 4\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nTransfer-Encoding: gzip\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabcd\r\n0\r\n\r\n",
+        b"G@T /users HTTP/1.1\r\n\r\n",
+        b"HTTP/1.1 20 OK\r\n\r\n"
+    ];
+    for data in invalid {
+        let result = match data.starts_with(b"HTTP") {
+            true => ResponseView::parse(data).map(|_| ()),
+            false => RequestView::parse(data).map(|_| ())
+        };
+        assert_eq!(result.err().map(|e| e.kind()), Some(ErrorKind::InvalidData), "{}", String::from_utf8_lossy(data));
+    }
+
+    let incomplete = [
+        &b"GET /users HTTP/1.1\r\nHost: a.com\r\n"[..],
+        b"POST /users HTTP/1.1\r\nContent-Length: 5\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n"
+    ];
+    for data in incomplete {
+        assert_eq!(RequestView::parse(data).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    }
+}
+
+#[test]
+fn huge_chunk_size() {
+    let data = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\nFFFFFFFFFFFFFFFF\r\n";
+    assert_eq!(ResponseView::parse(data).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    let data = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n1\r\na\r\nFFFFFFFFFFFFFFFFFF\r\nabc";
+    assert_eq!(ResponseView::parse(data).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    let data = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n-1\r\na\r\n0\r\n\r\n";
+    assert_eq!(RequestView::parse(data).err().unwrap().kind(), ErrorKind::InvalidData);
+}
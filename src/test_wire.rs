@@ -0,0 +1,276 @@
+use crate::*;
+use crate::wire::*;
+use std::io::ErrorKind;
+use wcookie::SetCookie;
+
+#[test]
+fn request_roundtrip() {
+    let mut request = Request::put("http://user@service.com:8080?lang=en");
+    request.insert_cookie("session", "1234")
+           .insert_header("Content-Type", "application/json")
+           .set_body(b"{}".to_vec()).unwrap();
+
+    let data = request_to_bytes(&request).unwrap();
+    assert_eq!(String::from_utf8(data.clone()).unwrap(),
+        "PUT /?lang=en HTTP/1.1\r\nContent-Type: application/json\r\nHost: service.com:8080\r\n\
+         Cookie: session=1234\r\nContent-Length: 2\r\n\r\n{}");
+
+    let parsed = parse_request(&data).unwrap();
+    assert_eq!(parsed.method(), HttpMethod::PUT);
+    assert_eq!(parsed.url(), "/");
+    assert_eq!(parsed.params().get("lang"), Some("en"));
+    assert_eq!(parsed.cookies().get("session"), Some("1234"));
+    assert!(!parsed.headers().contains_key("cookie"));
+    assert_eq!(parsed.headers().get("host"), Some("service.com:8080"));
+    assert_eq!(parsed.body().unwrap(), b"{}");
+}
+
+#[test]
+fn response_roundtrip() {
+    let mut response = Response::new(HTTP_401_UNAUTHORIZED);
+    response.insert_cookie(SetCookie::new("session", "1234"))
+            .insert_auth_headers("Basic realm=\"users\"")
+            .insert_auth_headers("Bearer");
+    response.set_body(b"denied".to_vec()).unwrap();
+
+    let data = response_to_bytes(&response).unwrap();
+    assert!(data.starts_with(b"HTTP/1.1 401 Unauthorized\r\n"));
+
+    let parsed = parse_response(&data).unwrap();
+    assert_eq!(parsed.status_code(), HTTP_401_UNAUTHORIZED);
+    assert_eq!(parsed.cookies().len(), 1);
+    assert_eq!(parsed.auth_headers(), &vec!["Basic realm=\"users\"".to_string(), "Bearer".to_string()]);
+    assert_eq!(parsed.headers().get("content-length"), Some("6"));
+    assert_eq!(parsed.body().unwrap(), b"denied");
+
+    let no_content = response_to_bytes(&Response::new(HTTP_204_NO_CONTENT)).unwrap();
+    assert_eq!(no_content, b"HTTP/1.1 204 No Content\r\n\r\n");
+}
+
+#[test]
+fn parse_framing() {
+    let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nVary: Accept\r\nVary: Cookie\r\n\r\n\
+                    4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nExpires: never\r\n\r\n";
+    let response = parse_response(chunked).unwrap();
+    assert_eq!(response.body().unwrap(), b"Wikipedia");
+    assert_eq!(response.headers().get("vary"), Some("Accept, Cookie"));
+
+    let to_end = parse_response(b"HTTP/1.0 200 OK\r\n\r\nuntil close").unwrap();
+    assert_eq!(to_end.body().unwrap(), b"until close");
+
+    let request = parse_request(b"GET /users HTTP/1.1\r\nHost: a.com\r\n\r\n").unwrap();
+    assert!(request.body().is_none());
+
+    let mut chunked_request = Request::post("/upload");
+    chunked_request.insert_header("Transfer-Encoding", "chunked").set_body(b"abc".to_vec()).unwrap();
+    let data = request_to_bytes(&chunked_request).unwrap();
+    assert!(data.ends_with(b"\r\n\r\n3\r\nabc\r\n0\r\n\r\n"));
+    assert_eq!(parse_request(&data).unwrap().body().unwrap(), b"abc");
+}
+
+#[test]
+fn parse_errors() {
+    let invalid = [
+        &b"GET /users HTTP/1.1\r\nHost: a.com\r\n"[..],
+        b"GET /users HTTP/2\r\n\r\n",
+        b"GET /users HTTP/1.1\r\nHost : a.com\r\n\r\n",
+        b"GET /users HTTP/1.1\r\nX-Folded: a\r\n b\r\n\r\n",
+        b"POST /users HTTP/1.1\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+        b"POST /users HTTP/1.1\r\nContent-Length: 3, 4\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nContent-Length: 5\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nContent-Length: 1\r\n\r\nabc",
+        b"POST /users HTTP/1.1\r\nTransfer-Encoding: gzip\r\n\r\nabc",
+        b"HTTP/1.1 20 OK\r\n\r\n"
+    ];
+    for data in invalid {
+        let result = match data.starts_with(b"HTTP") {
+            true => parse_response(data).map(|_| ()),
+            false => parse_request(data).map(|_| ())
+        };
+        assert!(result.is_err(), "{}", String::from_utf8_lossy(data));
+    }
+    let error = parse_request(b"GET / HTTP/1.1\r\n").err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn parse_body_limit() {
+    let data = format!("POST /users HTTP/1.1\r\nContent-Length: {}\r\n\r\n", DEFAULT_MAX_BODY_SIZE + 1);
+    let error = parse_request(data.as_bytes()).err().unwrap();
+    let too_large = error.get_ref().and_then(|e| e.downcast_ref::<BodyTooLarge>()).unwrap();
+    assert_eq!(too_large.size, Some(DEFAULT_MAX_BODY_SIZE + 1));
+}
+
+#[test]
+fn early_hints_sequence() {
+    let mut ok = Response::new(HTTP_200_OK);
+    ok.set_body(b"<html/>".to_vec()).unwrap();
+    let sequence = ResponseSequence::new(ok)
+        .with_interim(Response::early_hints(&["</style.css>; rel=preload; as=style"]))
+        .with_interim(Response::early_hints(&["</script.js>; rel=preload; as=script"]));
+
+    let mut data = Vec::new();
+    write_response_sequence(&sequence, &mut data).unwrap();
+    assert!(data.starts_with(b"HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload; as=style\r\n\r\n\
+                               HTTP/1.1 103 Early Hints\r\n"));
+
+    let parsed = parse_response_sequence(&data).unwrap();
+    assert_eq!(parsed.interim.len(), 2);
+    assert_eq!(parsed.interim[1].headers().get("link"), Some("</script.js>; rel=preload; as=script"));
+    assert_eq!(parsed.response.status_code(), HTTP_200_OK);
+    assert_eq!(parsed.response.body().unwrap(), b"<html/>");
+
+    let invalid = ResponseSequence::new(Response::new(HTTP_200_OK)).with_interim(Response::new(HTTP_200_OK));
+    assert!(write_response_sequence(&invalid, &mut Vec::new()).is_err());
+}
+
+#[test]
+fn expect_continue() {
+    let mut request = Request::post("http://service.com/upload");
+    request.set_body(b"data".to_vec()).unwrap();
+    assert!(!request.expects_continue());
+
+    let mut out = Vec::new();
+    assert!(write_request_with_continue(&request, &mut out, |_| panic!("not expected")).unwrap());
+    assert!(out.ends_with(b"\r\n\r\ndata"));
+
+    request.set_expect_continue();
+    assert!(request.expects_continue());
+    let mut out = Vec::new();
+    let sent = write_request_with_continue(&request, &mut out, |head| {
+        assert!(head.ends_with(b"Content-Length: 4\r\n\r\n"));
+        Ok(true)
+    }).unwrap();
+    assert!(sent);
+    assert_eq!(out, request_to_bytes(&request).unwrap());
+
+    let mut out = Vec::new();
+    let error = write_request_with_continue(&request, &mut out, |_| Err(std::io::Error::from(ErrorKind::TimedOut)));
+    assert_eq!(error.unwrap_err().kind(), ErrorKind::TimedOut);
+}
+
+#[test]
+fn estimated_wire_size() {
+    let mut request = Request::put("http://service.com/files/1?v=2");
+    assert_eq!(request.estimated_wire_size(), request_to_bytes(&request).unwrap().len());
+    request.insert_header("Transfer-Encoding", "chunked")
+           .set_body(vec![b'x'; 300]).unwrap();
+    assert_eq!(request.body_bytes(), 300);
+    assert_eq!(request.estimated_wire_size(), request_to_bytes(&request).unwrap().len());
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_header("Content-Type", "text/plain")
+            .set_body(b"hello".to_vec()).unwrap();
+    response.insert_cookie(SetCookie::new("a", "1"));
+    assert_eq!(response.header_bytes(), 26);
+    assert_eq!(response.estimated_wire_size(), response_to_bytes(&response).unwrap().len());
+
+    let mut not_modified = Response::new(304);
+    not_modified.set_body(b"ignored".to_vec()).unwrap();
+    assert_eq!(not_modified.estimated_wire_size(), response_to_bytes(&not_modified).unwrap().len());
+}
+
+#[test]
+fn parse_many_headers() {
+    let mut data = b"GET /items HTTP/1.1\r\nHost: service.com\r\n".to_vec();
+    for i in 0..100 {
+        data.extend_from_slice(format!("X-Header-{}: {}\r\n", i, i).as_bytes());
+    }
+    data.extend_from_slice(b"X-Header-0: again\r\n\r\n");
+    let request = parse_request(&data).unwrap();
+    assert_eq!(request.headers().len(), 101);
+    assert_eq!(request.headers().get("x-header-0"), Some("0, again"));
+    assert_eq!(request.headers().get("X-Header-99"), Some("99"));
+
+    let truncated = &data[..data.len() - 2];
+    assert_eq!(parse_request(truncated).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    assert!(parse_request(b"GET / HTTP/1.1\r\nBad Name: x\r\n\r\n").is_err());
+    assert!(parse_request(b"GET / HTTP/1.1\r\nX-Value: \xff\r\n\r\n").is_err());
+}
+
+#[test]
+fn parse_too_many_headers() {
+    let mut data = b"POST /items HTTP/1.1\r\n".to_vec();
+    for i in 0..MAX_HEADERS {
+        data.extend_from_slice(format!("X-Header-{}: {}\r\n", i, i).as_bytes());
+    }
+    data.extend_from_slice(b"Host: service.com\r\n\r\n");
+    assert_eq!(parse_request(&data).err().unwrap().kind(), ErrorKind::InvalidData);
+
+    // Line breaks in the body are not header lines
+    let mut data = b"POST /items HTTP/1.1\r\n".to_vec();
+    for i in 0..100 {
+        data.extend_from_slice(format!("X-Header-{}: {}\r\n", i, i).as_bytes());
+    }
+    let body = "\r\n".repeat(MAX_HEADERS * 2);
+    data.extend_from_slice(format!("Content-Length: {}\r\n\r\n{}", body.len(), body).as_bytes());
+    assert_eq!(parse_request(&data).unwrap().body().unwrap(), body.as_bytes());
+}
+
+#[test]
+fn standard_conversions() {
+    fn parse_message<T: TryFrom<&'static [u8], Error = std::io::Error>>(data: &'static [u8]) -> Result<T, std::io::Error> {
+        let message = T::try_from(data)?;
+        Ok(message)
+    }
+
+    let request: Request = "GET /users?page=2 HTTP/1.1\r\nHost: service.com\r\n\r\n".parse().unwrap();
+    assert_eq!(request.params().get("page"), Some("2"));
+    let data = Vec::<u8>::try_from(&request).unwrap();
+    assert_eq!(data, request_to_bytes(&request).unwrap());
+    assert_eq!(Request::try_from(data.as_slice()).unwrap().headers().get("host"), Some("service.com"));
+    assert!("GET\r\n\r\n".parse::<Request>().is_err());
+
+    let response: Response = parse_message(b"HTTP/1.1 404 Not Found\r\nContent-Length: 2\r\n\r\nno").unwrap();
+    assert_eq!(response.status_code(), HTTP_404_NOT_FOUND);
+    assert_eq!(Vec::<u8>::try_from(&response).unwrap(), response_to_bytes(&response).unwrap());
+    assert_eq!("HTTP/1.1 204 No Content\r\n\r\n".parse::<Response>().unwrap().status_code(), HTTP_204_NO_CONTENT);
+    assert!(Response::try_from(&b"HTTP/1.1 abc\r\n\r\n"[..]).is_err());
+}
+
+#[test]
+fn parse_huge_chunk_size() {
+    let data = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n1\r\na\r\nFFFFFFFFFFFFFFFF\r\n";
+    let error = parse_response(data).err().unwrap();
+    assert!(error.get_ref().and_then(|e| e.downcast_ref::<BodyTooLarge>()).is_some());
+
+    let data = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nFFFFFFFFFFFFFFFFFFFF\r\n";
+    assert!(parse_request(data).is_err());
+
+    assert_eq!(next_chunk(b"FFFFFFFFFFFFFFFF\r\nabc", usize::MAX).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(parse_request(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n+1\r\na\r\n0\r\n\r\n")
+        .err().unwrap().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn write_injection() {
+    let request = Request::get("http://service.com/x HTTP/1.1\r\nInjected: yes");
+    assert_eq!(request_to_bytes(&request).unwrap_err().kind(), ErrorKind::InvalidInput);
+    let request = Request::get("http://service.com/a b");
+    assert_eq!(request_to_bytes(&request).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let mut request = Request::get("http://service.com/x");
+    request.insert_cookie("a", "b\r\nInjected: 1");
+    assert_eq!(request_to_bytes(&request).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(request.estimated_wire_size(), 0);
+
+    let mut response = Response::new(HTTP_401_UNAUTHORIZED);
+    response.insert_auth_headers("Basic\r\nInjected: 1");
+    assert_eq!(response_to_bytes(&response).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let mut response = Response::new(407);
+    response.insert_proxy_auth_header("Basic\nInjected: 1");
+    assert_eq!(response_to_bytes(&response).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let mut response = Response::new(HTTP_200_OK);
+    response.insert_cookie(SetCookie::new("a", "b\r\nInjected: 1"));
+    assert_eq!(response_to_bytes(&response).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn write_invalid_status() {
+    assert_eq!(response_to_bytes(&Response::new(9999)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(response_to_bytes(&Response::new(99)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    let custom = response_to_bytes(&Response::new(999)).unwrap();
+    assert_eq!(parse_response(&custom).unwrap().status_code(), 999);
+}
@@ -0,0 +1,187 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Adapters between transports and tower services (feature `tower`)
+//!
+//! [TransportService] and [AsyncTransportService] expose a [Transport] or an [AsyncTransport] as a
+//! `tower::Service`, so requests can flow through tower middleware stacks like retries, rate limits or
+//! timeouts. The other way around, [ServiceTransport] sends requests through a service.
+//!
+//! ```
+//! use std::io::Error;
+//! use tower_service::Service;
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::transport::Transport;
+//! use wrequest::tower::TransportService;
+//!
+//! struct Ok200;
+//!
+//! impl Transport for Ok200 {
+//!     fn send(&self, _request: Request) -> Result<Response, Error> {
+//!         Ok(Response::new(HTTP_200_OK))
+//!     }
+//! }
+//!
+//! let mut service = TransportService::new(Ok200);
+//! let response = service.call(Request::get("https://service.com/users")).into_inner().unwrap();
+//! assert_eq!(response.status_code(), HTTP_200_OK);
+//! ```
+
+use std::error;
+use std::future::{Future, Ready, poll_fn, ready};
+use std::io::Error;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tower_service::Service;
+use crate::{Request, Response};
+use crate::transport::{AsyncTransport, Transport};
+
+/// Boxed error of tower services
+type BoxError = Box<dyn error::Error + Send + Sync>;
+
+/// Boxed future of the responses
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<Response, Error>> + Send>>;
+
+/// Service that sends the requests with a blocking [Transport], the exchange happens when the service is called
+#[derive(Clone)]
+pub struct TransportService<T> {
+    transport: T
+}
+
+impl<T> TransportService<T> {
+    /// Constructor with the `transport`
+    pub fn new(transport: T) -> TransportService<T> {
+        TransportService { transport }
+    }
+
+    /// Gets the transport
+    pub fn get_ref(&self) -> &T {
+        &self.transport
+    }
+
+    /// Takes the transport
+    pub fn into_inner(self) -> T {
+        self.transport
+    }
+}
+
+impl<T: Transport> Service<Request> for TransportService<T> {
+    type Response = Response;
+    type Error = Error;
+    type Future = Ready<Result<Response, Error>>;
+
+    /// Transports are always ready
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        ready(self.transport.send(request))
+    }
+}
+
+/// Service that sends the requests with an [AsyncTransport], shared by the service clones
+pub struct AsyncTransportService<T> {
+    transport: Arc<T>
+}
+
+impl<T> AsyncTransportService<T> {
+    /// Constructor with the `transport`
+    pub fn new(transport: T) -> AsyncTransportService<T> {
+        AsyncTransportService { transport: Arc::new(transport) }
+    }
+
+    /// Gets the transport
+    pub fn get_ref(&self) -> &T {
+        &self.transport
+    }
+}
+
+impl<T> From<Arc<T>> for AsyncTransportService<T> {
+    fn from(transport: Arc<T>) -> Self {
+        AsyncTransportService { transport }
+    }
+}
+
+impl<T> Clone for AsyncTransportService<T> {
+    fn clone(&self) -> Self {
+        AsyncTransportService { transport: self.transport.clone() }
+    }
+}
+
+impl<T: AsyncTransport + Send + Sync + 'static> Service<Request> for AsyncTransportService<T> {
+    type Response = Response;
+    type Error = Error;
+    type Future = ResponseFuture;
+
+    /// Transports are always ready
+    fn poll_ready(&mut self, _context: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        let transport = self.transport.clone();
+        Box::pin(async move { transport.send(request).await })
+    }
+}
+
+/// [AsyncTransport] that sends the requests through a tower service.
+///
+/// Every request is sent by a clone of the service, as tower services must be mutably borrowed to be
+/// called. Service errors become I/O errors, unless they already are.
+#[derive(Clone)]
+pub struct ServiceTransport<S> {
+    service: S
+}
+
+impl<S> ServiceTransport<S> {
+    /// Constructor with the `service`
+    pub fn new(service: S) -> ServiceTransport<S> {
+        ServiceTransport { service }
+    }
+
+    /// Gets the service
+    pub fn get_ref(&self) -> &S {
+        &self.service
+    }
+
+    /// Takes the service
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+}
+
+impl<S> AsyncTransport for ServiceTransport<S>
+where S: Service<Request, Response = Response> + Clone + Send,
+      S::Future: Send,
+      S::Error: Into<BoxError> {
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Error>> + Send {
+        let mut service = self.service.clone();
+        async move {
+            poll_fn(|context| service.poll_ready(context)).await.map_err(io_error)?;
+            service.call(request).await.map_err(io_error)
+        }
+    }
+}
+
+/// Converts a service error into an I/O error, unwrapping it if it already is one
+fn io_error<E: Into<BoxError>>(error: E) -> Error {
+    match error.into().downcast::<Error>() {
+        Ok(error) => *error,
+        Err(error) => Error::other(error)
+    }
+}
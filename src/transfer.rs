@@ -0,0 +1,170 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `Transfer-Encoding` and `TE` headers
+//!
+//! ```
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::transfer::TransferCoding;
+//!
+//! let mut response = Response::new(HTTP_200_OK);
+//! response.insert_header("Transfer-Encoding", "gzip, chunked");
+//! assert_eq!(response.transfer_encodings(), vec![TransferCoding::Gzip, TransferCoding::Chunked]);
+//! assert!(response.check_framing().is_ok());
+//!
+//! // Both framings are a request smuggling vector
+//! response.insert_header("Content-Length", "42");
+//! assert!(response.check_framing().is_err());
+//!
+//! let mut request = Request::get("https://service.com/feed");
+//! request.insert_header("TE", "trailers, deflate;q=0.5");
+//! assert!(request.te().trailers);
+//! assert_eq!(request.te().codings, vec![(TransferCoding::Deflate, 0.5)]);
+//! ```
+
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use crate::{HttpMessage, Request};
+
+/// `Transfer-Encoding` header name
+pub const TRANSFER_ENCODING: &str = "Transfer-Encoding";
+/// `TE` header name
+pub const TE: &str = "TE";
+/// `Content-Length` header name
+pub const CONTENT_LENGTH: &str = "Content-Length";
+
+/// Transfer coding of a `Transfer-Encoding` or `TE` header. Names are case-insensitive.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum TransferCoding {
+    /// `chunked`
+    Chunked,
+    /// `gzip` (or `x-gzip`)
+    Gzip,
+    /// `deflate`
+    Deflate,
+    /// `compress` (or `x-compress`)
+    Compress,
+    /// Other coding, in lowercase with its parameters
+    Other(String)
+}
+
+impl TransferCoding {
+    /// Parses a transfer coding, its parameters are kept only for unknown codings
+    pub fn parse(coding: &str) -> TransferCoding {
+        let name = coding.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+        match name.as_str() {
+            "chunked" => Self::Chunked,
+            "gzip" | "x-gzip" => Self::Gzip,
+            "deflate" => Self::Deflate,
+            "compress" | "x-compress" => Self::Compress,
+            _ => Self::Other(coding.trim().to_ascii_lowercase())
+        }
+    }
+}
+
+impl fmt::Display for TransferCoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}",
+               match self {
+                   Self::Chunked => "chunked",
+                   Self::Gzip => "gzip",
+                   Self::Deflate => "deflate",
+                   Self::Compress => "compress",
+                   Self::Other(coding) => coding
+               }
+        )
+    }
+}
+
+/// Transfer codings accepted by the client in a `TE` header
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Te {
+    /// The client accepts trailer fields in chunked responses (`trailers`)
+    pub trailers: bool,
+    /// Accepted codings with their quality values, `chunked` is always accepted and never listed
+    pub codings: Vec<(TransferCoding, f32)>
+}
+
+impl Te {
+    /// Parses a `TE` header value, codings with malformed quality values are skipped
+    pub fn parse(value: &str) -> Te {
+        let mut result = Te::default();
+        for member in value.split(',').map(str::trim).filter(|member| !member.is_empty()) {
+            if member.eq_ignore_ascii_case("trailers") {
+                result.trailers = true;
+                continue;
+            }
+            let mut params = member.split(';');
+            let coding = TransferCoding::parse(params.next().unwrap_or(""));
+            let quality = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .map(|(_, q)| q.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q)));
+            match quality {
+                Some(None) => continue,
+                _ if coding == TransferCoding::Chunked => continue,
+                quality => result.codings.push((coding, quality.flatten().unwrap_or(1.0)))
+            }
+        }
+        result
+    }
+
+    /// Checks a coding is accepted, with a non-zero quality value. `chunked` is always accepted.
+    pub fn accepts(&self, coding: &TransferCoding) -> bool {
+        *coding == TransferCoding::Chunked || self.codings.iter().any(|(c, q)| c == coding && *q > 0.0)
+    }
+}
+
+impl HttpMessage {
+    /// Gets the transfer codings of the `Transfer-Encoding` header, in the order they were applied
+    pub fn transfer_encodings(&self) -> Vec<TransferCoding> {
+        self.headers().get(TRANSFER_ENCODING)
+            .map(|codings| codings.split(',')
+                .filter(|coding| !coding.trim().is_empty())
+                .map(TransferCoding::parse)
+                .collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks the message framing is not ambiguous: a message must not have both `Content-Length` and
+    /// `Transfer-Encoding` headers, and `chunked` may only be applied once, as the last coding.
+    /// Returns an `ErrorKind::InvalidData` error otherwise.
+    pub fn check_framing(&self) -> Result<(), Error> {
+        let codings = self.transfer_encodings();
+        if codings.is_empty() {
+            return Ok(());
+        }
+        if self.headers().contains_key(CONTENT_LENGTH) {
+            return Err(Error::new(ErrorKind::InvalidData, "Message with both Content-Length and Transfer-Encoding"));
+        }
+        let chunked = codings.iter().filter(|coding| **coding == TransferCoding::Chunked).count();
+        if chunked > 1 || (chunked == 1 && codings.last() != Some(&TransferCoding::Chunked)) {
+            return Err(Error::new(ErrorKind::InvalidData, "Transfer coding chunked is not the last one"));
+        }
+        Ok(())
+    }
+}
+
+impl Request {
+    /// Gets the transfer codings accepted by the client in the `TE` header
+    pub fn te(&self) -> Te {
+        self.headers().get(TE).map(Te::parse).unwrap_or_default()
+    }
+
+    /// Checks the client accepts trailer fields in chunked responses
+    pub fn accepts_trailers(&self) -> bool {
+        self.te().trailers
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Pluggable HTTP clients
+//!
+//! A [Transport] performs a single exchange: it sends the request once and returns the response
+//! received, whatever its status. Retries, redirects and authentication challenges are left to the
+//! caller, so all the transports behave the same.
+//!
+//! ```
+//! use std::io::Error;
+//! use wrequest::{Request, Response, HTTP_200_OK};
+//! use wrequest::transport::Transport;
+//!
+//! struct Echo;
+//!
+//! impl Transport for Echo {
+//!     fn send(&self, request: Request) -> Result<Response, Error> {
+//!         let mut response = Response::new(HTTP_200_OK);
+//!         response.set_body(request.full_url().into_bytes()).unwrap();
+//!         Ok(response)
+//!     }
+//! }
+//!
+//! fn fetch<T: Transport>(transport: &T) -> Result<Response, Error> {
+//!     transport.send(Request::get("https://service.com/users"))
+//! }
+//!
+//! assert_eq!(fetch(&Echo).unwrap().body().unwrap(), b"https://service.com/users");
+//! ```
+//!
+//! With the `async` feature, asynchronous clients implement [AsyncTransport] with the same contract.
+//! With the `tower` feature, the `tower` module adapts transports to tower services and back.
+
+use std::io::Error;
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::sync::Arc;
+use crate::{Request, Response};
+use crate::mock::MockRouter;
+
+/// Client that sends requests and receives their responses
+pub trait Transport {
+    /// Sends a `request` and receives its response.
+    ///
+    /// Responses with error status codes are returned as `Ok`, errors are reserved to failures of
+    /// the exchange itself, like connection or protocol errors.
+    fn send(&self, request: Request) -> Result<Response, Error>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn send(&self, request: Request) -> Result<Response, Error> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn send(&self, request: Request) -> Result<Response, Error> {
+        (**self).send(request)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn send(&self, request: Request) -> Result<Response, Error> {
+        (**self).send(request)
+    }
+}
+
+/// The mock router answers the requests in-process, it never fails
+impl Transport for MockRouter {
+    fn send(&self, request: Request) -> Result<Response, Error> {
+        Ok(self.handle(&request))
+    }
+}
+
+/// Asynchronous client that sends requests and receives their responses (feature `async`)
+///
+/// Implementations can use `async fn send`, as long as the returned future is `Send`
+/// so it can run on multi-threaded executors.
+#[cfg(feature = "async")]
+pub trait AsyncTransport {
+    /// Sends a `request` and receives its response.
+    ///
+    /// Responses with error status codes are returned as `Ok`, errors are reserved to failures of
+    /// the exchange itself, like connection or protocol errors.
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Error>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync + ?Sized> AsyncTransport for &T {
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Error>> + Send {
+        (**self).send(request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Send + Sync + ?Sized> AsyncTransport for Arc<T> {
+    fn send(&self, request: Request) -> impl Future<Output = Result<Response, Error>> + Send {
+        (**self).send(request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for MockRouter {
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        Ok(self.handle(&request))
+    }
+}
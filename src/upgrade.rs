@@ -0,0 +1,129 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Protocol upgrade with the `Upgrade` and `Connection: upgrade` headers
+//!
+//! ```
+//! use wrequest::{Request, Response};
+//! use wrequest::upgrade::Protocol;
+//!
+//! let mut request = Request::get("http://service.com/");
+//! request.set_upgrade(&[Protocol::new("h2c")]);
+//! assert_eq!(request.headers().get("Connection"), Some("Upgrade"));
+//!
+//! let offered = request.upgrade_protocols();
+//! assert_eq!(offered[0].name, "h2c");
+//!
+//! let response = Response::switching_protocols(&offered[0]);
+//! assert!(response.is_switching_protocols());
+//! assert_eq!(response.upgrade_protocols(), offered);
+//! ```
+
+use std::fmt;
+use crate::{HttpMessage, Request, Response, HTTP_101_SWITCHING_PROTOCOLS};
+
+/// `Upgrade` header name
+pub const UPGRADE: &str = "Upgrade";
+/// `Connection` header name
+pub const CONNECTION: &str = "Connection";
+
+/// Protocol of an `Upgrade` header, like `websocket` or `HTTP/2.0`
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Protocol {
+    /// Protocol name
+    pub name: String,
+    /// Protocol version, if any
+    pub version: Option<String>
+}
+
+impl Protocol {
+    /// Constructor of a protocol without version
+    pub fn new<S: Into<String>>(name: S) -> Protocol {
+        Protocol { name: name.into(), version: None }
+    }
+
+    /// Sets the protocol version
+    pub fn with_version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Parses an `Upgrade` header value into its protocols, in order of preference
+    pub fn parse_list(value: &str) -> Vec<Protocol> {
+        value.split(',')
+            .map(str::trim)
+            .filter(|protocol| !protocol.is_empty())
+            .map(|protocol| match protocol.split_once('/') {
+                Some((name, version)) => Protocol::new(name.trim()).with_version(version.trim()),
+                None => Protocol::new(protocol)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            Some(ref version) => write!(f, "{}/{}", self.name, version),
+            None => write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl HttpMessage {
+    /// Gets the protocols of the `Upgrade` header, only if the `Connection` header has the `upgrade` option
+    pub fn upgrade_protocols(&self) -> Vec<Protocol> {
+        let upgrade_option = self.headers().get(CONNECTION)
+            .is_some_and(|options| options.split(',').any(|option| option.trim().eq_ignore_ascii_case(UPGRADE)));
+        match self.headers().get(UPGRADE) {
+            Some(protocols) if upgrade_option => Protocol::parse_list(protocols),
+            _ => Vec::new()
+        }
+    }
+
+    /// Sets the `Upgrade` header and adds the `upgrade` option to the `Connection` header
+    fn set_upgrade_headers(&mut self, protocols: &[Protocol]) {
+        let protocols: Vec<String> = protocols.iter().map(|protocol| protocol.to_string()).collect();
+        self.headers.insert(UPGRADE, protocols.join(", "));
+        let connection = match self.headers().get(CONNECTION) {
+            Some(options) if options.split(',').any(|option| option.trim().eq_ignore_ascii_case(UPGRADE)) => return,
+            Some(options) => format!("{}, {}", options, UPGRADE),
+            None => UPGRADE.to_string()
+        };
+        self.headers.insert(CONNECTION, connection);
+    }
+}
+
+impl Request {
+    /// Asks the server to switch to one of the `protocols`, in order of preference
+    pub fn set_upgrade(&mut self, protocols: &[Protocol]) -> &mut Self {
+        self.set_upgrade_headers(protocols);
+        self
+    }
+}
+
+impl Response {
+    /// Creates a `101 Switching Protocols` response that switches to `protocol`
+    pub fn switching_protocols(protocol: &Protocol) -> Response {
+        let mut response = Response::new(HTTP_101_SWITCHING_PROTOCOLS);
+        response.set_upgrade_headers(std::slice::from_ref(protocol));
+        response
+    }
+
+    /// Checks the response is a `101 Switching Protocols` response with an `Upgrade` header
+    pub fn is_switching_protocols(&self) -> bool {
+        self.status_code() == HTTP_101_SWITCHING_PROTOCOLS && !self.upgrade_protocols().is_empty()
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Conversions to and from ureq messages (feature `ureq`)
+
+use std::collections::HashSet;
+use std::io::{Error, ErrorKind, Read};
+use std::str::FromStr;
+use wcookie::SetCookie;
+use crate::{BodyTooLarge, Request, Response};
+use crate::transport::Transport;
+
+impl Request {
+    /// Creates a ureq request for this request with `agent`, without its body.
+    ///
+    /// The URL includes the request params and the cookies are sent in a `Cookie` header.
+    pub fn to_ureq(&self, agent: &ureq::Agent) -> ureq::Request {
+        let mut request = agent.request(&self.method.to_string(), &self.full_url());
+        for (name, value) in self.headers().iter() {
+            request = request.set(name, value);
+        }
+        if let Some(cookies) = self.cookie_header() {
+            request = request.set("Cookie", &cookies);
+        }
+        request
+    }
+
+    /// Sends the request with `agent`, blocking until the whole response is read.
+    ///
+    /// Error status codes (4xx and 5xx) are returned as regular responses, only transport errors
+    /// are reported as errors.
+    pub fn call_ureq(&self, agent: &ureq::Agent) -> Result<Response, Error> {
+        let request = self.to_ureq(agent);
+        let result = match self.body() {
+            Some(body) => request.send_bytes(body),
+            None => request.call()
+        };
+        match result {
+            Ok(response) | Err(ureq::Error::Status(_, response)) => Response::from_ureq(response),
+            Err(e) => Err(Error::other(e))
+        }
+    }
+}
+
+impl Response {
+    /// Converts a ureq response into a `Response`, reading its whole body.
+    ///
+    /// `Set-Cookie` headers are parsed into the response cookies (invalid ones are ignored),
+    /// `WWW-Authenticate` and `Proxy-Authenticate` headers into the authorization guides and repeated
+    /// headers are joined with commas.
+    pub fn from_ureq(response: ureq::Response) -> Result<Response, Error> {
+        let mut result = Response::new(response.status());
+        if let Ok(version) = response.http_version().parse() {
+            result.set_version(version);
+        }
+
+        let mut seen = HashSet::new();
+        for name in response.headers_names() {
+            if !seen.insert(name.clone()) {
+                continue;
+            }
+            let values = response.all(&name);
+            match name.as_str() {
+                "set-cookie" => for value in values {
+                    if let Ok(cookie) = SetCookie::from_str(value) {
+                        result.insert_cookie(cookie);
+                    }
+                },
+                "www-authenticate" => for value in values {
+                    result.insert_auth_headers(value);
+                },
+                "proxy-authenticate" => for value in values {
+                    result.insert_proxy_auth_header(value);
+                },
+                _ => {
+                    result.insert_header(name.clone(), values.join(", "));
+                }
+            }
+        }
+
+        let limit = result.max_body_size();
+        let declared = response.header("Content-Length").and_then(|size| size.trim().parse::<usize>().ok());
+        if let Some(size) = declared.filter(|size| *size > limit) {
+            return Err(BodyTooLarge::error(limit, Some(size)));
+        }
+        let mut data = Vec::new();
+        response.into_reader().take(limit as u64 + 1).read_to_end(&mut data)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        if data.len() > limit {
+            return Err(BodyTooLarge::error(limit, None));
+        }
+        if !data.is_empty() {
+            result.set_body(data)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Blocking transport, error status codes are returned as responses
+impl Transport for ureq::Agent {
+    fn send(&self, request: Request) -> Result<Response, Error> {
+        request.call_ureq(self)
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! URI helpers shared by the crate modules
+
+/// Checks `c` is an unreserved URI character (RFC 3986)
+pub(crate) fn is_unreserved(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, b'-' | b'.' | b'_' | b'~')
+}
+
+/// Percent-encodes all the characters of `input` but the unreserved ones
+pub(crate) fn percent_encode(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for c in input.bytes() {
+        if is_unreserved(c) {
+            result.push(c as char);
+        } else {
+            result.push_str(&format!("%{:02X}", c));
+        }
+    }
+    result
+}
+
+/// Decodes the percent-encoded characters of `input`. Invalid escapes are kept as they are and
+/// invalid UTF-8 sequences are replaced.
+pub(crate) fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                result.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+        }
+        result.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+/// Encodes a form-urlencoded name or value: spaces as `+` and the rest of characters percent-encoded
+/// but the unreserved ones
+pub(crate) fn form_encode(input: &str) -> String {
+    percent_encode(input).replace("%20", "+")
+}
+
+/// Decodes a form-urlencoded name or value, with `+` as space
+pub(crate) fn form_decode(input: &str) -> String {
+    percent_decode(&input.replace('+', " "))
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        _ => None
+    }
+}
+
+/// Splits a URL into the part before the query string and the query string itself, if any.
+/// The fragment is discarded.
+pub(crate) fn split_query(url: &str) -> (&str, Option<&str>) {
+    let url = url.split('#').next().unwrap_or(url);
+    match url.find('?') {
+        Some(pos) => (&url[..pos], Some(&url[pos + 1..])),
+        None => (url, None)
+    }
+}
+
+/// Parses a query string into percent-decoded `(key, value)` pairs
+pub(crate) fn parse_query(query: &str) -> Vec<(String, String)> {
+    query.split('&')
+         .filter(|pair| !pair.is_empty())
+         .map(|pair| match pair.find('=') {
+             Some(pos) => (percent_decode(&pair[..pos]), percent_decode(&pair[pos + 1..])),
+             None => (percent_decode(pair), String::new())
+         })
+         .collect()
+}
+
+/// Gets the path of a URL, without query string nor fragment. Relative references are returned as they are.
+pub(crate) fn path(url: &str) -> &str {
+    let (url, _) = split_query(url);
+    match url.find("://") {
+        Some(pos) => {
+            let rest = &url[pos + 3..];
+            match rest.find('/') {
+                Some(slash) => &rest[slash..],
+                None => "/"
+            }
+        },
+        None => url
+    }
+}
+
+/// Gets the byte range of the host of an absolute URL, without user info nor port. IPv6 literals keep
+/// their brackets.
+pub(crate) fn host_range(url: &str) -> Option<(usize, usize)> {
+    let authority_start = url.find("://")? + 3;
+    let rest = &url[authority_start..];
+    // Backslashes end the authority too, as WHATWG URL parsers take them as slashes in `http` URLs
+    let authority = &rest[..rest.find(['/', '?', '#', '\\']).unwrap_or(rest.len())];
+    let host_start = authority.rfind('@').map_or(0, |at| at + 1);
+    let host_port = &authority[host_start..];
+    let host_len = if host_port.starts_with('[') {
+        host_port.find(']').map_or(host_port.len(), |bracket| bracket + 1)
+    } else {
+        host_port.rfind(':').unwrap_or(host_port.len())
+    };
+    let start = authority_start + host_start;
+    Some((start, start + host_len))
+}
+
+/// Normalizes a URL (RFC 3986, section 6): lowercase scheme and host, default port removed, dot segments
+/// resolved, percent-encoding normalized and query params sorted. The fragment is removed.
+pub(crate) fn normalize(url: &str) -> String {
+    let (url, query) = split_query(url);
+    let mut result = String::with_capacity(url.len() + query.map_or(0, |q| q.len() + 1));
+
+    let path = match url.find("://") {
+        Some(pos) => {
+            let scheme = url[..pos].to_ascii_lowercase();
+            let rest = &url[pos + 3..];
+            let (authority, path) = match rest.find('/') {
+                Some(slash) => rest.split_at(slash),
+                None => (rest, "")
+            };
+            result.push_str(&scheme);
+            result.push_str("://");
+            result.push_str(&normalize_authority(&scheme, authority));
+            if path.is_empty() { "/" } else { path }
+        },
+        None => url
+    };
+    result.push_str(&remove_dot_segments(&normalize_percent(path)));
+
+    if let Some(query) = query {
+        let mut pairs: Vec<String> = query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(normalize_percent)
+            .collect();
+        pairs.sort();
+        if !pairs.is_empty() {
+            result.push('?');
+            result.push_str(&pairs.join("&"));
+        }
+    }
+    result
+}
+
+/// Lowercases the host and removes the default port of the scheme, keeping the user info as it is
+fn normalize_authority(scheme: &str, authority: &str) -> String {
+    let (user_info, host_port) = match authority.rfind('@') {
+        Some(at) => authority.split_at(at + 1),
+        None => ("", authority)
+    };
+    // The port follows the last colon, unless it is inside an IPv6 literal
+    let (host, port) = match host_port.rfind(':') {
+        Some(colon) if !host_port[colon..].contains(']') => (&host_port[..colon], Some(&host_port[colon + 1..])),
+        _ => (host_port, None)
+    };
+    let default_port = match scheme {
+        "http" | "ws" => Some("80"),
+        "https" | "wss" => Some("443"),
+        _ => None
+    };
+    let mut result = String::with_capacity(authority.len());
+    result.push_str(user_info);
+    result.push_str(&normalize_percent(host).to_ascii_lowercase());
+    if let Some(port) = port.filter(|port| !port.is_empty() && Some(*port) != default_port) {
+        result.push(':');
+        result.push_str(port);
+    }
+    result
+}
+
+/// Decodes the percent-encoded unreserved characters and uppercases the hex digits of the other escapes
+fn normalize_percent(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut result = String::with_capacity(input.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex_value(bytes[i + 1]), hex_value(bytes[i + 2])) {
+                let c = high << 4 | low;
+                if is_unreserved(c) {
+                    result.push(c as char);
+                } else {
+                    result.push_str(&format!("%{:02X}", c));
+                }
+                i += 3;
+                continue;
+            }
+        }
+        let len = utf8_len(bytes[i]);
+        result.push_str(&input[i..i + len]);
+        i += len;
+    }
+    result
+}
+
+/// Length of the UTF-8 sequence starting with byte `c`
+fn utf8_len(c: u8) -> usize {
+    match c {
+        0xF0.. => 4,
+        0xE0.. => 3,
+        0xC0.. => 2,
+        _ => 1
+    }
+}
+
+/// Components of a URI reference (RFC 3986, section 3)
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>
+}
+
+impl<'a> Components<'a> {
+    fn parse(reference: &'a str) -> Components<'a> {
+        let (rest, fragment) = match reference.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (reference, None)
+        };
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None)
+        };
+        let (scheme, rest) = match rest.split_once(':') {
+            Some((scheme, rest)) if scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.bytes().all(|c| c.is_ascii_alphanumeric() || matches!(c, b'+' | b'-' | b'.')) => (Some(scheme), rest),
+            _ => (None, rest)
+        };
+        let (authority, path) = match rest.strip_prefix("//") {
+            Some(rest) => {
+                let end = rest.find('/').unwrap_or(rest.len());
+                (Some(&rest[..end]), &rest[end..])
+            },
+            None => (None, rest)
+        };
+        Components { scheme, authority, path, query, fragment }
+    }
+}
+
+/// Resolves a URI reference, like a `Location` header value, against a base URL (RFC 3986, section 5.2)
+pub(crate) fn resolve(base: &str, reference: &str) -> String {
+    let base = Components::parse(base);
+    let reference = Components::parse(reference);
+    let (scheme, authority, path, query) = if reference.scheme.is_some() {
+        (reference.scheme, reference.authority, remove_dot_segments(reference.path), reference.query)
+    } else if reference.authority.is_some() {
+        (base.scheme, reference.authority, remove_dot_segments(reference.path), reference.query)
+    } else if reference.path.is_empty() {
+        (base.scheme, base.authority, base.path.to_string(), reference.query.or(base.query))
+    } else if reference.path.starts_with('/') {
+        (base.scheme, base.authority, remove_dot_segments(reference.path), reference.query)
+    } else {
+        let merged = if base.authority.is_some() && base.path.is_empty() {
+            format!("/{}", reference.path)
+        } else {
+            let directory = base.path.rfind('/').map_or("", |slash| &base.path[..=slash]);
+            format!("{}{}", directory, reference.path)
+        };
+        (base.scheme, base.authority, remove_dot_segments(&merged), reference.query)
+    };
+
+    let mut result = String::with_capacity(path.len() + 32);
+    if let Some(scheme) = scheme {
+        result.push_str(scheme);
+        result.push(':');
+    }
+    if let Some(authority) = authority {
+        result.push_str("//");
+        result.push_str(authority);
+    }
+    result.push_str(&path);
+    if let Some(query) = query {
+        result.push('?');
+        result.push_str(query);
+    }
+    if let Some(fragment) = reference.fragment {
+        result.push('#');
+        result.push_str(fragment);
+    }
+    result
+}
+
+/// Removes the `.` and `..` segments of a path (RFC 3986, section 5.2.4)
+fn remove_dot_segments(path: &str) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    let mut input = path;
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../").or_else(|| input.strip_prefix("./")) {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = if input.starts_with('/') { 1 } else { 0 };
+            let end = input[start..].find('/').map_or(input.len(), |slash| slash + start);
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
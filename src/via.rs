@@ -0,0 +1,157 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! `Via` header handling for intermediaries
+//!
+//! ```
+//! use wrequest::Request;
+//! use wrequest::via::Via;
+//!
+//! let mut request = Request::get("https://service.com/users");
+//! request.insert_header("Via", "1.0 fred, 1.1 p.example.net");
+//!
+//! if request.contains_pseudonym("edge-1") {
+//!     panic!("Forwarding loop");
+//! }
+//! request.append_via(&Via::new("1.1", "edge-1"));
+//!
+//! assert_eq!(request.headers().get("Via"), Some("1.0 fred, 1.1 p.example.net, 1.1 edge-1"));
+//! assert_eq!(request.via()[1].received_by, "p.example.net");
+//! ```
+
+use std::fmt;
+use crate::HttpMessage;
+
+/// `Via` header name
+pub const VIA: &str = "Via";
+
+/// Intermediary entry of a `Via` header
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Via {
+    /// Protocol name of the received message, `None` for HTTP
+    pub protocol: Option<String>,
+    /// Protocol version of the received message, like `1.1`
+    pub version: String,
+    /// Host and port or pseudonym of the intermediary
+    pub received_by: String,
+    /// Optional comment, without the parentheses
+    pub comment: Option<String>
+}
+
+impl Via {
+    /// Constructor of an HTTP entry with the protocol `version` and the intermediary `pseudonym`
+    pub fn new<V: Into<String>, P: Into<String>>(version: V, pseudonym: P) -> Via {
+        Via {
+            protocol: None,
+            version: version.into(),
+            received_by: pseudonym.into(),
+            comment: None
+        }
+    }
+
+    /// Sets the comment
+    pub fn with_comment<S: Into<String>>(mut self, comment: S) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    /// Parses a `Via` header value, skipping malformed entries
+    pub fn parse_list(value: &str) -> Vec<Via> {
+        split_entries(value).into_iter().filter_map(Via::parse).collect()
+    }
+
+    fn parse(entry: &str) -> Option<Via> {
+        let (received, comment) = match entry.find('(') {
+            Some(start) => {
+                let comment = entry[start + 1..].trim_end();
+                (&entry[..start], Some(comment.strip_suffix(')').unwrap_or(comment).to_string()))
+            },
+            None => (entry, None)
+        };
+        let mut parts = received.split_whitespace();
+        let protocol = parts.next()?;
+        let received_by = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        let (protocol, version) = match protocol.split_once('/') {
+            Some((name, version)) if name.eq_ignore_ascii_case("HTTP") => (None, version),
+            Some((name, version)) => (Some(name.to_string()), version),
+            None => (None, protocol)
+        };
+        Some(Via {
+            protocol,
+            version: version.to_string(),
+            received_by: received_by.to_string(),
+            comment
+        })
+    }
+}
+
+impl fmt::Display for Via {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref protocol) = self.protocol {
+            write!(f, "{}/", protocol)?;
+        }
+        write!(f, "{} {}", self.version, self.received_by)?;
+        if let Some(ref comment) = self.comment {
+            write!(f, " ({})", comment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a list at the commas that are not inside comments
+fn split_entries(value: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                entries.push(value[start..i].trim());
+                start = i + 1;
+            },
+            _ => {}
+        }
+    }
+    entries.push(value[start..].trim());
+    entries.retain(|entry| !entry.is_empty());
+    entries
+}
+
+impl HttpMessage {
+    /// Gets the intermediaries of the `Via` header, in forwarding order
+    pub fn via(&self) -> Vec<Via> {
+        self.headers.get(VIA).map(Via::parse_list).unwrap_or_default()
+    }
+
+    /// Appends the entry of this intermediary at the end of the `Via` header
+    pub fn append_via(&mut self, via: &Via) -> &mut Self {
+        let value = match self.headers.get(VIA) {
+            Some(current) if !current.trim().is_empty() => format!("{}, {}", current, via),
+            _ => via.to_string()
+        };
+        self.insert_header(VIA, value)
+    }
+
+    /// Checks if an intermediary with `pseudonym` (case-insensitive) already forwarded the message,
+    /// to detect forwarding loops
+    pub fn contains_pseudonym(&self, pseudonym: &str) -> bool {
+        self.via().iter().any(|via| via.received_by.eq_ignore_ascii_case(pseudonym))
+    }
+}
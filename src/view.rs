@@ -0,0 +1,392 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+
+//! Zero-copy message views
+//!
+//! [RequestView] and [ResponseView] read HTTP/1.1 messages in place: parsing only checks the syntax of the
+//! head and finds where the body ends, then the start line, headers and body are borrowed from the parsed
+//! data. Nothing is allocated, headers are looked up by scanning the head, so views suit tools that only
+//! read a few headers per message. Views are converted to owned messages with `to_owned()` when needed.
+//!
+//! ```
+//! use wrequest::view::RequestView;
+//!
+//! let data = b"POST /users?notify=true HTTP/1.1\r\nHost: service.com\r\nContent-Length: 4\r\n\r\nJohn\
+//!              GET /users HTTP/1.1\r\nHost: service.com\r\n\r\n";
+//!
+//! let first = RequestView::parse(data).unwrap();
+//! assert_eq!(first.method(), "POST");
+//! assert_eq!(first.path(), "/users");
+//! assert_eq!(first.header("host"), Some("service.com"));
+//! assert_eq!(first.body(), b"John");
+//!
+//! // Pipelined messages
+//! let second = RequestView::parse(&data[first.len()..]).unwrap();
+//! assert_eq!(second.method(), "GET");
+//!
+//! let request = first.to_owned().unwrap();
+//! assert_eq!(request.params().get("notify"), Some("true"));
+//! ```
+
+use std::io::{Error, ErrorKind};
+use std::str::from_utf8;
+use crate::{HttpStatusCode, HttpVersion, Request, Response, is_valid_header_name};
+use crate::transfer::{CONTENT_LENGTH, TRANSFER_ENCODING};
+use crate::wire::{self, next_chunk, parse_version, trailer_len};
+
+/// Borrowed request, see the [module documentation](self)
+#[derive(Copy, Clone, Debug)]
+pub struct RequestView<'a> {
+    head: Head<'a>,
+    method: &'a str,
+    target: &'a str,
+    version: HttpVersion
+}
+
+impl<'a> RequestView<'a> {
+    /// Parses the request at the start of `data`, which may be followed by other data, like pipelined
+    /// requests. Requests without framing headers have no body.
+    ///
+    /// Invalid requests are refused with an `ErrorKind::InvalidData` error and incomplete ones with an
+    /// `ErrorKind::UnexpectedEof` error, as [wire::parse_request] does.
+    pub fn parse(data: &'a [u8]) -> Result<RequestView<'a>, Error> {
+        let mut head = Head::parse(data)?;
+        let mut parts = head.start_line.splitn(3, ' ');
+        let (method, target, version) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(method), Some(target), Some(version)) if is_valid_header_name(method) && !target.is_empty() =>
+                (method, target, version),
+            _ => return Err(invalid_data(format!("Invalid request line {:?}", head.start_line)))
+        };
+        let version = parse_version(version)?;
+        head.frame_body(false)?;
+        Ok(RequestView { head, method, target, version })
+    }
+
+    /// Gets the method, as sent
+    pub fn method(&self) -> &'a str {
+        self.method
+    }
+
+    /// Gets the request target, with its query string
+    pub fn target(&self) -> &'a str {
+        self.target
+    }
+
+    /// Gets the path of the target, without its query string
+    pub fn path(&self) -> &'a str {
+        self.target.split_once('?').map_or(self.target, |(path, _)| path)
+    }
+
+    /// Gets the query string of the target, without the `?`
+    pub fn query(&self) -> Option<&'a str> {
+        self.target.split_once('?').map(|(_, query)| query)
+    }
+
+    /// Gets the protocol version
+    pub fn version(&self) -> HttpVersion {
+        self.version
+    }
+
+    /// Gets the value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.head.values(name).next()
+    }
+
+    /// Gets the values of all the headers named `name` (case-insensitive), in order
+    pub fn header_values<'b>(&self, name: &'b str) -> impl Iterator<Item = &'a str> + 'b
+    where 'a: 'b {
+        self.head.values(name)
+    }
+
+    /// Gets the header lines, names and values, in order
+    pub fn headers(&self) -> HeaderLines<'a> {
+        self.head.lines()
+    }
+
+    /// Gets the body as sent, with its chunked framing if any, see [RequestView::chunks]
+    pub fn body(&self) -> &'a [u8] {
+        self.head.body()
+    }
+
+    /// Checks the body has a chunked transfer coding
+    pub fn is_chunked(&self) -> bool {
+        self.head.chunked
+    }
+
+    /// Gets the data of the chunks of a chunked body, or the whole body as a single chunk otherwise
+    pub fn chunks(&self) -> Chunks<'a> {
+        self.head.chunks()
+    }
+
+    /// Length of the message, head and body, in the parsed data
+    pub fn len(&self) -> usize {
+        self.head.end
+    }
+
+    /// Checks the message is empty, which never happens as messages have a start line
+    pub fn is_empty(&self) -> bool {
+        self.head.end == 0
+    }
+
+    /// Parses the viewed data into an owned request, see [wire::parse_request]
+    pub fn to_owned(&self) -> Result<Request, Error> {
+        wire::parse_request(&self.head.data[..self.head.end])
+    }
+}
+
+/// Borrowed response, see the [module documentation](self)
+#[derive(Copy, Clone, Debug)]
+pub struct ResponseView<'a> {
+    head: Head<'a>,
+    status: HttpStatusCode,
+    reason: &'a str,
+    version: HttpVersion
+}
+
+impl<'a> ResponseView<'a> {
+    /// Parses the response at the start of `data`. Responses without framing headers take the remaining
+    /// data as body, while interim (`1xx`), `204 No Content` and `304 Not Modified` responses have no body.
+    ///
+    /// Invalid responses are refused with an `ErrorKind::InvalidData` error and incomplete ones with an
+    /// `ErrorKind::UnexpectedEof` error, as [wire::parse_response] does.
+    pub fn parse(data: &'a [u8]) -> Result<ResponseView<'a>, Error> {
+        let mut head = Head::parse(data)?;
+        let mut parts = head.start_line.splitn(3, ' ');
+        let (version, status, reason) = match (parts.next(), parts.next()) {
+            (Some(version), Some(status)) => (version, status, parts.next().unwrap_or("")),
+            _ => return Err(invalid_data(format!("Invalid status line {:?}", head.start_line)))
+        };
+        let version = parse_version(version)?;
+        if status.len() != 3 || !status.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(invalid_data(format!("Invalid status code {:?}", status)));
+        }
+        let status: HttpStatusCode = status.parse().map_err(invalid_data)?;
+        if wire::allows_body(status) {
+            head.frame_body(true)?;
+        }
+        Ok(ResponseView { head, status, reason, version })
+    }
+
+    /// Gets the status code
+    pub fn status_code(&self) -> HttpStatusCode {
+        self.status
+    }
+
+    /// Gets the reason phrase, as sent
+    pub fn reason(&self) -> &'a str {
+        self.reason
+    }
+
+    /// Gets the protocol version
+    pub fn version(&self) -> HttpVersion {
+        self.version
+    }
+
+    /// Gets the value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.head.values(name).next()
+    }
+
+    /// Gets the values of all the headers named `name` (case-insensitive), in order
+    pub fn header_values<'b>(&self, name: &'b str) -> impl Iterator<Item = &'a str> + 'b
+    where 'a: 'b {
+        self.head.values(name)
+    }
+
+    /// Gets the header lines, names and values, in order
+    pub fn headers(&self) -> HeaderLines<'a> {
+        self.head.lines()
+    }
+
+    /// Gets the body as sent, with its chunked framing if any, see [ResponseView::chunks]
+    pub fn body(&self) -> &'a [u8] {
+        self.head.body()
+    }
+
+    /// Checks the body has a chunked transfer coding
+    pub fn is_chunked(&self) -> bool {
+        self.head.chunked
+    }
+
+    /// Gets the data of the chunks of a chunked body, or the whole body as a single chunk otherwise
+    pub fn chunks(&self) -> Chunks<'a> {
+        self.head.chunks()
+    }
+
+    /// Length of the message, head and body, in the parsed data
+    pub fn len(&self) -> usize {
+        self.head.end
+    }
+
+    /// Checks the message is empty, which never happens as messages have a start line
+    pub fn is_empty(&self) -> bool {
+        self.head.end == 0
+    }
+
+    /// Parses the viewed data into an owned response, see [wire::parse_response]
+    pub fn to_owned(&self) -> Result<Response, Error> {
+        wire::parse_response(&self.head.data[..self.head.end])
+    }
+}
+
+/// Iterator over the header lines of a view, names and values
+#[derive(Clone)]
+pub struct HeaderLines<'a> {
+    lines: std::str::Split<'a, &'static str>
+}
+
+impl<'a> Iterator for HeaderLines<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.by_ref().find(|line| !line.is_empty()).map(split_field)
+    }
+}
+
+/// Iterator over the data of the chunks of a view body
+#[derive(Clone)]
+pub struct Chunks<'a> {
+    /// Remaining body, with its framing if chunked
+    data: &'a [u8],
+    chunked: bool
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.chunked {
+            let data = std::mem::take(&mut self.data);
+            return (!data.is_empty()).then_some(data);
+        }
+        // The framing was checked by the parser
+        let (chunk, read) = next_chunk(self.data, usize::MAX).ok()?;
+        self.data = &self.data[read..];
+        chunk
+    }
+}
+
+/// Start line, header fields and body framing of a message
+#[derive(Copy, Clone, Debug)]
+struct Head<'a> {
+    data: &'a [u8],
+    start_line: &'a str,
+    /// Header lines, separated by CRLF
+    fields: &'a str,
+    body_start: usize,
+    /// Position where the message ends
+    end: usize,
+    chunked: bool
+}
+
+impl<'a> Head<'a> {
+    /// Parses and checks the head, the message ends with it until the body is framed
+    fn parse(data: &'a [u8]) -> Result<Head<'a>, Error> {
+        let end = data.windows(4).position(|w| w == b"\r\n\r\n")
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "Incomplete message head"))?;
+        let text = from_utf8(&data[..end]).map_err(|_| invalid_data("Message head is not UTF-8"))?;
+        let (start_line, fields) = text.split_once("\r\n").unwrap_or((text, ""));
+        for line in fields.split("\r\n").filter(|line| !line.is_empty()) {
+            if line.starts_with([' ', '\t']) {
+                return Err(invalid_data("Obsolete header line folding"));
+            }
+            let name = line.split_once(':').map(|(name, _)| name)
+                .ok_or_else(|| invalid_data(format!("Invalid header line {:?}", line)))?;
+            if !is_valid_header_name(name) {
+                return Err(invalid_data(format!("Invalid header name {:?}", name)));
+            }
+        }
+        Ok(Head { data, start_line, fields, body_start: end + 4, end: end + 4, chunked: false })
+    }
+
+    fn lines(&self) -> HeaderLines<'a> {
+        HeaderLines { lines: self.fields.split("\r\n") }
+    }
+
+    fn values<'b>(&self, name: &'b str) -> impl Iterator<Item = &'a str> + 'b
+    where 'a: 'b {
+        self.lines().filter(move |(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, value)| value)
+    }
+
+    fn body(&self) -> &'a [u8] {
+        &self.data[self.body_start..self.end]
+    }
+
+    fn chunks(&self) -> Chunks<'a> {
+        Chunks { data: self.body(), chunked: self.chunked }
+    }
+
+    /// Finds where the body ends from the framing headers. Messages without them have no body, but
+    /// responses (`to_end`), whose body is the remaining data.
+    fn frame_body(&mut self, to_end: bool) -> Result<(), Error> {
+        let rest = &self.data[self.body_start..];
+        let last_coding = self.values(TRANSFER_ENCODING).flat_map(|codings| codings.split(','))
+            .map(|coding| coding.split(';').next().unwrap_or("").trim())
+            .filter(|coding| !coding.is_empty())
+            .last();
+        let mut lengths = self.values(CONTENT_LENGTH).flat_map(|lengths| lengths.split(',')).map(str::trim);
+
+        // Ambiguous framings are a request smuggling vector (RFC 9112, section 6.3)
+        if let Some(coding) = last_coding {
+            if lengths.next().is_some() {
+                return Err(invalid_data("Content-Length and Transfer-Encoding headers"));
+            }
+            if !coding.eq_ignore_ascii_case("chunked") {
+                if to_end {
+                    self.end = self.data.len();
+                    return Ok(());
+                }
+                return Err(invalid_data("Request transfer coding is not chunked"));
+            }
+            let mut pos = 0;
+            loop {
+                let (chunk, read) = next_chunk(&rest[pos..], usize::MAX)?;
+                pos += read;
+                if chunk.is_none() {
+                    break;
+                }
+            }
+            self.chunked = true;
+            self.end = self.body_start + pos + trailer_len(&rest[pos..])?;
+            return Ok(());
+        }
+
+        if let Some(first) = lengths.next() {
+            let invalid = || invalid_data(format!("Invalid Content-Length {:?}", first));
+            if lengths.any(|length| length != first) || first.is_empty() || !first.bytes().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let length: usize = first.parse().map_err(|_| invalid())?;
+            if rest.len() < length {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "Incomplete message body"));
+            }
+            self.end = self.body_start + length;
+        } else if to_end {
+            self.end = self.data.len();
+        }
+        Ok(())
+    }
+}
+
+fn split_field(line: &str) -> (&str, &str) {
+    let (name, value) = line.split_once(':').unwrap_or((line, ""));
+    (name, value.trim_matches([' ', '\t']))
+}
+
+fn invalid_data<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,762 @@
+// Copyright 2022 Juan A. Cáceres (cacexp@gmail.com)
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! HTTP/1.1 wire format
+//!
+//! Serialization and parsing of whole messages in the HTTP/1.1 message syntax (RFC 9112). Request cookies
+//! are sent in a `Cookie` header and response cookies and authorization guides in `Set-Cookie`,
+//! `WWW-Authenticate` and `Proxy-Authenticate` headers. Parsed bodies are limited to the
+//! [maximum body size](crate::HttpMessage::max_body_size) and heads to [MAX_HEADERS] header lines.
+//!
+//! ```
+//! use wrequest::{Request, HTTP_200_OK};
+//! use wrequest::wire;
+//!
+//! let mut request = Request::post("https://service.com/users");
+//! request.insert_param("notify", "true");
+//! request.insert_header("Content-Type", "text/plain")
+//!        .set_body(b"John".to_vec()).unwrap();
+//!
+//! let data = wire::request_to_bytes(&request).unwrap();
+//! assert_eq!(data, b"POST /users?notify=true HTTP/1.1\r\nContent-Type: text/plain\r\n\
+//!                    Host: service.com\r\nContent-Length: 4\r\n\r\nJohn");
+//!
+//! let parsed = wire::parse_request(&data).unwrap();
+//! assert_eq!(parsed.params().get("notify"), Some("true"));
+//! assert_eq!(parsed.body().unwrap(), b"John");
+//! ```
+
+use std::io::{Error, ErrorKind, Write};
+use std::str::FromStr;
+use wcookie::SetCookie;
+use crate::transfer::{TransferCoding, CONTENT_LENGTH, TRANSFER_ENCODING};
+use crate::{HttpMessage, HttpVersion, Request, Response, HttpStatusCode, reason_phrase, uri, parse_cookie_header, set_cookie_header,
+    BodyTooLarge};
+
+/// Maximum number of header lines of a parsed message head
+pub const MAX_HEADERS: usize = 1024;
+
+/// `Host` header name
+const HOST: &str = "Host";
+/// `Cookie` header name
+const COOKIE: &str = "Cookie";
+/// `Set-Cookie` header name
+const SET_COOKIE: &str = "Set-Cookie";
+/// `WWW-Authenticate` header name
+const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+/// `Proxy-Authenticate` header name
+const PROXY_AUTHENTICATE: &str = "Proxy-Authenticate";
+
+/// Final response preceded by its interim (`1xx`) responses, like `103 Early Hints`
+#[derive(Clone)]
+pub struct ResponseSequence {
+    /// Interim responses, in order
+    pub interim: Vec<Response>,
+    /// Final response
+    pub response: Response
+}
+
+impl ResponseSequence {
+    /// Creates a sequence with the final response only
+    pub fn new(response: Response) -> ResponseSequence {
+        ResponseSequence { interim: Vec::new(), response }
+    }
+
+    /// Adds an interim response, after the previous ones
+    pub fn with_interim(mut self, interim: Response) -> Self {
+        self.interim.push(interim);
+        self
+    }
+}
+
+/// Writes a request. The target is in origin form (path and query string, with the request params) and a
+/// `Host` header is added if the request has an absolute URL and no such header. A `Content-Length` header
+/// is added for bodies unless the request has one or a `Transfer-Encoding` header.
+///
+/// Requests with ambiguous framing (see [HttpMessage::check_framing]) or whose last transfer coding is not
+/// `chunked` are refused with an `ErrorKind::InvalidInput` error.
+pub fn write_request<W: Write>(request: &Request, out: &mut W) -> Result<(), Error> {
+    out.write_all(request_head(request)?.as_bytes())?;
+    write_body(request, true, out)
+}
+
+/// Start line and headers of a request, with its framing headers
+fn request_head(request: &Request) -> Result<String, Error> {
+    check_framing(request)?;
+    if request.headers().contains_key(TRANSFER_ENCODING) && !is_chunked(request.headers().get(TRANSFER_ENCODING)) {
+        return Err(Error::new(ErrorKind::InvalidInput, "Request transfer coding is not chunked"));
+    }
+    format_request_head(request)
+}
+
+/// Start line and headers of a request, without checking its framing. Targets with whitespace or control
+/// characters and header values with control characters are refused with an `ErrorKind::InvalidInput` error,
+/// as they would inject other lines in the head.
+pub(crate) fn format_request_head(request: &Request) -> Result<String, Error> {
+    let url = request.full_url();
+    let (target, host) = match url.find("://") {
+        Some(pos) => {
+            let rest = &url[pos + 3..];
+            let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+            let target = rest[end..].split('#').next().unwrap_or("");
+            let authority = &rest[..end];
+            let host = authority.rfind('@').map_or(authority, |at| &authority[at + 1..]);
+            (if target.starts_with('?') || target.is_empty() { format!("/{}", target) } else { target.to_string() },
+             Some(host))
+        },
+        None => (url.clone(), None)
+    };
+
+    if target.bytes().any(|b| b == b' ' || b.is_ascii_control()) {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid request target {:?}", target)));
+    }
+    let mut head = format!("{} {} {}\r\n", request.method(), target, wire_version(request));
+    write_headers(&mut head, request, |name| !name.eq_ignore_ascii_case(COOKIE))?;
+    if let Some(host) = host.filter(|_| !request.headers().contains_key(HOST)) {
+        push_header(&mut head, HOST, host)?;
+    }
+    let cookies = [request.headers().get(COOKIE).map(str::to_string), request.cookie_header()];
+    let cookies: Vec<String> = cookies.into_iter().flatten().collect();
+    if !cookies.is_empty() {
+        push_header(&mut head, COOKIE, &cookies.join("; "))?;
+    }
+    finish_head(head, request, true)
+}
+
+/// Writes a request whose body is only sent once the server accepts it, when the request expects a
+/// `100 Continue` response (see [Request::expects_continue]). The head is written and flushed, then
+/// `confirm` is called with the sink, for example to read the server answer from the same stream. It must
+/// return `true` if a `100 Continue` response was received (or it timed out waiting for it), or `false`
+/// if a final response was received instead. Returns whether the body was written.
+///
+/// Requests that do not expect a `100 Continue` response are written whole, without calling `confirm`.
+///
+/// ```
+/// use wrequest::Request;
+/// use wrequest::wire;
+///
+/// let mut request = Request::put("https://service.com/artifacts/1");
+/// request.set_expect_continue().set_body(b"large artifact".to_vec()).unwrap();
+///
+/// let mut out = Vec::new();
+/// let sent = wire::write_request_with_continue(&request, &mut out, |head| {
+///     assert!(head.ends_with(b"Expect: 100-continue\r\nHost: service.com\r\nContent-Length: 14\r\n\r\n"));
+///     Ok(false) // 417 Expectation Failed received
+/// }).unwrap();
+///
+/// assert!(!sent);
+/// assert!(!out.ends_with(b"large artifact"));
+/// ```
+pub fn write_request_with_continue<W, F>(request: &Request, out: &mut W, confirm: F) -> Result<bool, Error>
+where W: Write,
+      F: FnOnce(&mut W) -> Result<bool, Error> {
+    if !request.expects_continue() {
+        write_request(request, out)?;
+        return Ok(true);
+    }
+    out.write_all(request_head(request)?.as_bytes())?;
+    out.flush()?;
+    if !confirm(out)? {
+        return Ok(false);
+    }
+    write_body(request, true, out)?;
+    Ok(true)
+}
+
+/// Writes a response. A `Content-Length` header is added for bodies unless the response has one or a
+/// `Transfer-Encoding` header, or its status code does not allow a body.
+///
+/// Responses with ambiguous framing (see [HttpMessage::check_framing]) or a status code that is not a three-digit
+/// code are refused with an `ErrorKind::InvalidInput` error.
+pub fn write_response<W: Write>(response: &Response, out: &mut W) -> Result<(), Error> {
+    check_framing(response)?;
+    out.write_all(format_response_head(response)?.as_bytes())?;
+    write_body(response, allows_body(response.status_code()), out)
+}
+
+/// Status line and headers of a response, with its framing headers. Header values with control characters
+/// are refused with an `ErrorKind::InvalidInput` error.
+pub(crate) fn format_response_head(response: &Response) -> Result<String, Error> {
+    let status = response.status_code();
+    if !(100..=999).contains(&status) {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Status code {} is not a three-digit code", status)));
+    }
+    let mut head = format!("{} {} {}\r\n", wire_version(response), status, reason_phrase(status).unwrap_or(""));
+    write_headers(&mut head, response, |_| true)?;
+    for cookie in response.cookies() {
+        push_header(&mut head, SET_COOKIE, &set_cookie_header(cookie))?;
+    }
+    for auth in response.auth_headers() {
+        push_header(&mut head, WWW_AUTHENTICATE, auth)?;
+    }
+    for auth in response.proxy_auth_headers() {
+        push_header(&mut head, PROXY_AUTHENTICATE, auth)?;
+    }
+    finish_head(head, response, allows_body(status))
+}
+
+/// Writes the interim responses of a sequence followed by its final response
+pub fn write_response_sequence<W: Write>(sequence: &ResponseSequence, out: &mut W) -> Result<(), Error> {
+    for interim in sequence.interim.iter() {
+        if !is_interim(interim.status_code()) {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                format!("Status {} is not an interim response", interim.status_code())));
+        }
+        write_response(interim, out)?;
+    }
+    write_response(&sequence.response, out)
+}
+
+/// Serializes a request, see [`write_request`]
+pub fn request_to_bytes(request: &Request) -> Result<Vec<u8>, Error> {
+    let mut result = Vec::new();
+    write_request(request, &mut result)?;
+    Ok(result)
+}
+
+/// Serializes a response, see [`write_response`]
+pub fn response_to_bytes(response: &Response) -> Result<Vec<u8>, Error> {
+    let mut result = Vec::new();
+    write_response(response, &mut result)?;
+    Ok(result)
+}
+
+fn write_headers<F: Fn(&str) -> bool>(head: &mut String, message: &HttpMessage, filter: F) -> Result<(), Error> {
+    for (name, value) in message.headers().iter().filter(|(name, _)| filter(name)) {
+        push_header(head, name, value)?;
+    }
+    Ok(())
+}
+
+fn push_header(head: &mut String, name: &str, value: &str) -> Result<(), Error> {
+    if !crate::is_valid_header_value(value) {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("Invalid {} header value {:?}", name, value)));
+    }
+    head.push_str(name);
+    head.push_str(": ");
+    head.push_str(value);
+    head.push_str("\r\n");
+    Ok(())
+}
+
+/// Adds the `Content-Length` header of the body, if needed, and ends the head
+fn finish_head(mut head: String, message: &HttpMessage, allows_body: bool) -> Result<String, Error> {
+    let framed = message.headers().contains_key(TRANSFER_ENCODING) || message.headers().contains_key(CONTENT_LENGTH);
+    if let Some(body) = message.body().filter(|_| allows_body) {
+        if !framed {
+            push_header(&mut head, CONTENT_LENGTH, &body.len().to_string())?;
+        }
+    }
+    head.push_str("\r\n");
+    Ok(head)
+}
+
+fn write_body<W: Write>(message: &HttpMessage, allows_body: bool, out: &mut W) -> Result<(), Error> {
+    let body = message.body().filter(|_| allows_body);
+    let chunked = is_chunked(message.headers().get(TRANSFER_ENCODING));
+    match body {
+        Some(body) if chunked => {
+            if !body.is_empty() {
+                write!(out, "{:X}\r\n", body.len())?;
+                out.write_all(body)?;
+                out.write_all(b"\r\n")?;
+            }
+            out.write_all(b"0\r\n\r\n")
+        },
+        Some(body) => out.write_all(body),
+        None if chunked => out.write_all(b"0\r\n\r\n"),
+        None => Ok(())
+    }
+}
+
+/// Length of the body as written by [write_body], with the chunked framing if any
+pub(crate) fn encoded_body_len(message: &HttpMessage, allows_body: bool) -> usize {
+    let length = message.body().filter(|_| allows_body).map_or(0, |body| body.len());
+    if !is_chunked(message.headers().get(TRANSFER_ENCODING)) {
+        return length;
+    }
+    let last_chunk = b"0\r\n\r\n".len();
+    match length {
+        0 => last_chunk,
+        length => format!("{:X}", length).len() + 2 + length + 2 + last_chunk
+    }
+}
+
+/// Checks `chunked` is the last transfer coding
+fn is_chunked(transfer_encoding: Option<&str>) -> bool {
+    transfer_encoding.and_then(|codings| codings.rsplit(',').next())
+        .is_some_and(|coding| TransferCoding::parse(coding) == TransferCoding::Chunked)
+}
+
+/// Refuses to write messages with ambiguous framing
+fn check_framing(message: &HttpMessage) -> Result<(), Error> {
+    message.check_framing().map_err(|e| Error::new(ErrorKind::InvalidInput, e.to_string()))
+}
+
+/// Checks a status code is an interim one (`1xx`)
+pub(crate) fn is_interim(status: HttpStatusCode) -> bool {
+    (100..200).contains(&status)
+}
+
+/// Checks the body of a response ends when the connection is closed, as it has a body and no framing headers
+/// (or a last transfer coding other than `chunked`)
+pub(crate) fn is_close_delimited(response: &Response) -> bool {
+    allows_body(response.status_code()) && !is_chunked(response.headers().get(TRANSFER_ENCODING))
+        && (response.headers().contains_key(TRANSFER_ENCODING) || !response.headers().contains_key(CONTENT_LENGTH))
+}
+
+/// Checks the responses with a status code may have a body
+pub(crate) fn allows_body(status: HttpStatusCode) -> bool {
+    !is_interim(status) && status != 204 && status != 304
+}
+
+/// Parses a whole request. The target query string is decoded into the request params and the `Cookie`
+/// headers into the request cookies. Repeated headers are joined with commas.
+pub fn parse_request(data: &[u8]) -> Result<Request, Error> {
+    let (request, end) = parse_request_at(data)?;
+    check_end(data, end)?;
+    Ok(request)
+}
+
+/// Parses the request at the start of `data`, returning the position where it ends
+pub(crate) fn parse_request_at(data: &[u8]) -> Result<(Request, usize), Error> {
+    let head = parse_head(data)?;
+    let mut parts = head.start_line.splitn(3, ' ');
+    let (method, target, version) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if !target.is_empty() => (method, target, version),
+        _ => return Err(invalid_data(format!("Invalid request line {:?}", head.start_line)))
+    };
+    let version = parse_version(version)?;
+
+    let (path, query) = uri::split_query(target);
+    let mut result = Request::new(method.parse()?, path.to_string());
+    result.set_version(version);
+    if let Some(query) = query {
+        for (key, value) in uri::parse_query(query) {
+            result.insert_param(key, value);
+        }
+    }
+    for (name, value) in head.headers.iter() {
+        if name.eq_ignore_ascii_case(COOKIE) {
+            for (key, cookie) in head.values(COOKIE).flat_map(parse_cookie_header) {
+                result.insert_cookie(key, cookie);
+            }
+        } else {
+            result.try_insert_header(name.to_string(), value.clone()).map_err(invalid_data)?;
+        }
+    }
+
+    let (body, end) = read_body(data, &head, &result, false)?;
+    if let Some(body) = body {
+        result.set_body(body)?;
+    }
+    Ok((result, end))
+}
+
+/// Parses a whole response. `Set-Cookie` headers are parsed into the response cookies (invalid ones are
+/// ignored), `WWW-Authenticate` and `Proxy-Authenticate` headers into the authorization guides and repeated
+/// headers are joined with commas. Responses without `Content-Length` nor `Transfer-Encoding` headers
+/// take the remaining data as body.
+pub fn parse_response(data: &[u8]) -> Result<Response, Error> {
+    let (response, end) = parse_response_at(data, false)?;
+    check_end(data, end)?;
+    Ok(response)
+}
+
+/// Parses the interim responses, if any, followed by the final response
+pub fn parse_response_sequence(data: &[u8]) -> Result<ResponseSequence, Error> {
+    let mut interim = Vec::new();
+    let mut start = 0;
+    loop {
+        let (response, end) = parse_response_at(&data[start..], false)?;
+        start += end;
+        if is_interim(response.status_code()) {
+            interim.push(response);
+        } else {
+            check_end(data, start)?;
+            return Ok(ResponseSequence { interim, response });
+        }
+    }
+}
+
+/// Parses the response at the start of `data`, returning the position where it ends. Responses to `HEAD`
+/// requests (`head`) have no body, whatever their headers.
+pub(crate) fn parse_response_at(data: &[u8], head_request: bool) -> Result<(Response, usize), Error> {
+    let head = parse_head(data)?;
+    let mut parts = head.start_line.splitn(3, ' ');
+    let (version, status) = match (parts.next(), parts.next()) {
+        (Some(version), Some(status)) => (version, status),
+        _ => return Err(invalid_data(format!("Invalid status line {:?}", head.start_line)))
+    };
+    let version = parse_version(version)?;
+    if status.len() != 3 || !status.bytes().all(|c| c.is_ascii_digit()) {
+        return Err(invalid_data(format!("Invalid status code {:?}", status)));
+    }
+    let status: HttpStatusCode = status.parse().map_err(invalid_data)?;
+
+    let mut result = Response::new(status);
+    result.set_version(version);
+    for (name, value) in head.headers.iter() {
+        if name.eq_ignore_ascii_case(SET_COOKIE) {
+            for value in head.values(SET_COOKIE) {
+                if let Ok(cookie) = SetCookie::from_str(value) {
+                    result.insert_cookie(cookie);
+                }
+            }
+        } else if name.eq_ignore_ascii_case(WWW_AUTHENTICATE) {
+            for value in head.values(WWW_AUTHENTICATE) {
+                result.insert_auth_headers(value);
+            }
+        } else if name.eq_ignore_ascii_case(PROXY_AUTHENTICATE) {
+            for value in head.values(PROXY_AUTHENTICATE) {
+                result.insert_proxy_auth_header(value);
+            }
+        } else {
+            result.try_insert_header(name.to_string(), value.clone()).map_err(invalid_data)?;
+        }
+    }
+
+    if !allows_body(status) || head_request {
+        return Ok((result, head.body_start));
+    }
+    let (body, end) = read_body(data, &head, &result, true)?;
+    if let Some(body) = body {
+        result.set_body(body)?;
+    }
+    Ok((result, end))
+}
+
+/// Start line and headers of a message
+struct Head<'a> {
+    start_line: &'a str,
+    /// Headers with the values of repeated ones joined with commas, in order of first appearance
+    headers: Vec<(&'a str, String)>,
+    /// Header lines, for headers that cannot be joined
+    lines: Vec<(&'a str, &'a str)>,
+    /// Position of the body
+    body_start: usize
+}
+
+impl<'a> Head<'a> {
+    fn get(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+    }
+
+    fn values<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'a str> + 'b {
+        self.lines.iter().filter(move |(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, value)| *value)
+    }
+}
+
+fn too_many_headers() -> Error {
+    invalid_data(format!("Message head with more than {} headers", MAX_HEADERS))
+}
+
+#[cfg(not(feature = "fast-parse"))]
+fn parse_head(data: &[u8]) -> Result<Head<'_>, Error> {
+    let end = data.windows(4).position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "Incomplete message head"))?;
+    let text = std::str::from_utf8(&data[..end]).map_err(|_| invalid_data("Message head is not UTF-8"))?;
+    let mut lines = text.split("\r\n");
+    let start_line = lines.next().unwrap_or("");
+
+    let mut head = Head { start_line, headers: Vec::new(), lines: Vec::new(), body_start: end + 4 };
+    for line in lines {
+        if head.lines.len() == MAX_HEADERS {
+            return Err(too_many_headers());
+        }
+        if line.starts_with([' ', '\t']) {
+            return Err(invalid_data("Obsolete header line folding"));
+        }
+        let (name, value) = line.split_once(':')
+            .ok_or_else(|| invalid_data(format!("Invalid header line {:?}", line)))?;
+        // Whitespace between the name and the colon must be rejected (RFC 9112, section 5.1)
+        if !crate::is_valid_header_name(name) {
+            return Err(invalid_data(format!("Invalid header name {:?}", name)));
+        }
+        let value = value.trim_matches([' ', '\t']);
+        head.lines.push((name, value));
+        match head.headers.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some((_, joined)) => {
+                joined.push_str(", ");
+                joined.push_str(value);
+            },
+            None => head.headers.push((name, value.to_string()))
+        }
+    }
+    Ok(head)
+}
+
+/// Parses the message head with httparse (feature `fast-parse`), the start line is split off and parsed by
+/// the callers as [parse_head] does
+#[cfg(feature = "fast-parse")]
+fn parse_head(data: &[u8]) -> Result<Head<'_>, Error> {
+    /// Headers parsed without allocating the httparse header array
+    const INLINE_HEADERS: usize = 64;
+
+    let line_end = data.windows(2).position(|w| w == b"\r\n")
+        .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "Incomplete message head"))?;
+    let start_line = std::str::from_utf8(&data[..line_end]).map_err(|_| invalid_data("Message head is not UTF-8"))?;
+    let fields = &data[line_end + 2..];
+
+    let mut inline = [httparse::EMPTY_HEADER; INLINE_HEADERS];
+    let mut allocated;
+    let parsed = match httparse::parse_headers(fields, &mut inline) {
+        Err(httparse::Error::TooManyHeaders) => {
+            // Only the header lines up to the end of the head are counted, the body is not scanned
+            let end = fields.windows(4).position(|w| w == b"\r\n\r\n")
+                .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "Incomplete message head"))?;
+            let count = fields[..end + 2].windows(2).filter(|w| w == b"\r\n").count();
+            if count > MAX_HEADERS {
+                return Err(too_many_headers());
+            }
+            allocated = vec![httparse::EMPTY_HEADER; count];
+            httparse::parse_headers(fields, &mut allocated)
+        },
+        parsed => parsed
+    };
+    let (consumed, lines) = match parsed.map_err(|e| invalid_data(format!("Invalid message head: {}", e)))? {
+        httparse::Status::Complete(complete) => complete,
+        httparse::Status::Partial => return Err(Error::new(ErrorKind::UnexpectedEof, "Incomplete message head"))
+    };
+
+    let mut head = Head { start_line, headers: Vec::with_capacity(lines.len()), lines: Vec::with_capacity(lines.len()),
+        body_start: line_end + 2 + consumed };
+    for line in lines.iter() {
+        let value = std::str::from_utf8(line.value).map_err(|_| invalid_data("Message head is not UTF-8"))?;
+        head.lines.push((line.name, value));
+        match head.headers.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(line.name)) {
+            Some((_, joined)) => {
+                joined.push_str(", ");
+                joined.push_str(value);
+            },
+            None => head.headers.push((line.name, value.to_string()))
+        }
+    }
+    Ok(head)
+}
+
+pub(crate) fn parse_version(version: &str) -> Result<HttpVersion, Error> {
+    match version {
+        "HTTP/1.1" => Ok(HttpVersion::Http11),
+        "HTTP/1.0" => Ok(HttpVersion::Http10),
+        _ => Err(invalid_data(format!("Unsupported HTTP version {:?}", version)))
+    }
+}
+
+/// Version written in the start line, HTTP/1.1 for messages of later versions
+fn wire_version(message: &HttpMessage) -> HttpVersion {
+    message.version().min(HttpVersion::Http11)
+}
+
+fn check_end(data: &[u8], end: usize) -> Result<(), Error> {
+    if end < data.len() {
+        return Err(invalid_data("Unexpected data after the message"));
+    }
+    Ok(())
+}
+
+/// Reads the body framed by the message headers, returning it and the position where the message ends.
+/// Messages without framing headers have no body, but responses, whose body is the remaining data.
+fn read_body(data: &[u8], head: &Head, message: &HttpMessage, to_end: bool) -> Result<(Option<Vec<u8>>, usize), Error> {
+    let limit = message.max_body_size();
+    let start = head.body_start;
+    let transfer_encoding = head.get(TRANSFER_ENCODING);
+    let content_length = head.get(CONTENT_LENGTH);
+
+    // Ambiguous framings are a request smuggling vector (RFC 9112, section 6.3)
+    message.check_framing()?;
+    if let Some(codings) = transfer_encoding {
+        if !is_chunked(Some(codings)) {
+            if to_end {
+                return read_to_end(data, start, limit);
+            }
+            return Err(invalid_data("Request transfer coding is not chunked"));
+        }
+        let (body, end) = read_chunked(&data[start..], limit)?;
+        return Ok((Some(body), start + end));
+    }
+
+    if let Some(length) = content_length {
+        // Repeated equal values are joined, RFC 9112 allows them
+        let mut values = length.split(',').map(str::trim);
+        let first = values.next().unwrap_or("");
+        if values.any(|value| value != first) || !first.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(invalid_data(format!("Invalid Content-Length {:?}", length)));
+        }
+        let length: usize = first.parse().map_err(|_| invalid_data(format!("Invalid Content-Length {:?}", length)))?;
+        if length > limit {
+            return Err(BodyTooLarge::error(limit, Some(length)));
+        }
+        if data.len() - start < length {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Incomplete message body"));
+        }
+        let body = (length > 0).then(|| data[start..start + length].to_vec());
+        return Ok((body, start + length));
+    }
+
+    if to_end {
+        read_to_end(data, start, limit)
+    } else {
+        Ok((None, start))
+    }
+}
+
+/// Body of a message whose head is complete but its body is not, see [pending_body]
+pub(crate) enum PendingBody {
+    /// `Content-Length` body, the message ends at the offset
+    Length(usize),
+    /// Chunked body, starting at the offset
+    Chunked(usize)
+}
+
+/// Gets how the body of the message at the start of `data` is delimited, once [parse_request_at] or
+/// [parse_response_at] failed as the message is incomplete, so the rest can be awaited without parsing it again.
+/// `None` if the framing is not known.
+pub(crate) fn pending_body(data: &[u8]) -> Result<Option<PendingBody>, Error> {
+    let head = parse_head(data)?;
+    if head.get(TRANSFER_ENCODING).is_some() {
+        return Ok(is_chunked(head.get(TRANSFER_ENCODING)).then_some(PendingBody::Chunked(head.body_start)));
+    }
+    Ok(head.get(CONTENT_LENGTH)
+        .and_then(|length| length.split(',').next())
+        .and_then(|length| length.trim().parse::<usize>().ok())
+        .and_then(|length| head.body_start.checked_add(length))
+        .map(PendingBody::Length))
+}
+
+fn read_to_end(data: &[u8], start: usize, limit: usize) -> Result<(Option<Vec<u8>>, usize), Error> {
+    let length = data.len() - start;
+    if length > limit {
+        return Err(BodyTooLarge::error(limit, Some(length)));
+    }
+    Ok(((length > 0).then(|| data[start..].to_vec()), data.len()))
+}
+
+/// Decodes a chunked body, returning it and its encoded length. Trailer fields are ignored.
+fn read_chunked(data: &[u8], limit: usize) -> Result<(Vec<u8>, usize), Error> {
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        let (chunk, read) = next_chunk(&data[pos..], limit - body.len())?;
+        pos += read;
+        match chunk {
+            Some(chunk) => body.extend_from_slice(chunk),
+            None => return Ok((body, pos + trailer_len(&data[pos..])?))
+        }
+    }
+}
+
+/// Reads the chunk at the start of `data`, returning its data, `None` for the last chunk, and the length
+/// read, with the framing. Chunks larger than `limit` are refused with a [BodyTooLarge] error. Chunk
+/// extensions are ignored.
+pub(crate) fn next_chunk(data: &[u8], limit: usize) -> Result<(Option<&[u8]>, usize), Error> {
+    let incomplete = || Error::new(ErrorKind::UnexpectedEof, "Incomplete chunked body");
+    let line_end = find_crlf(data).ok_or_else(incomplete)?;
+    let line = std::str::from_utf8(&data[..line_end]).map_err(|_| invalid_data("Invalid chunk size"))?;
+    let size = line.split(';').next().unwrap_or("").trim();
+    if size.is_empty() || !size.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid_data(format!("Invalid chunk size {:?}", size)));
+    }
+    // Sizes that do not fit in memory are larger than any limit
+    let size = usize::from_str_radix(size, 16).unwrap_or(usize::MAX);
+    let start = line_end + 2;
+    if size == 0 {
+        return Ok((None, start));
+    }
+    if size > limit {
+        return Err(BodyTooLarge::error(limit, None));
+    }
+    let end = start.checked_add(size).filter(|end| end.saturating_add(2) <= data.len()).ok_or_else(incomplete)?;
+    if &data[end..end + 2] != b"\r\n" {
+        return Err(invalid_data("Chunk not ended by CRLF"));
+    }
+    Ok((Some(&data[start..end]), end + 2))
+}
+
+/// Length of the trailer section after the last chunk, up to its empty line. Trailer fields are ignored.
+pub(crate) fn trailer_len(data: &[u8]) -> Result<usize, Error> {
+    let mut pos = 0;
+    loop {
+        let line_end = find_crlf(&data[pos..])
+            .ok_or_else(|| Error::new(ErrorKind::UnexpectedEof, "Incomplete chunked body"))?;
+        pos += line_end + 2;
+        if line_end == 0 {
+            return Ok(pos);
+        }
+    }
+}
+
+fn find_crlf(data: &[u8]) -> Option<usize> {
+    data.windows(2).position(|w| w == b"\r\n")
+}
+
+fn invalid_data<E>(e: E) -> Error
+where E: Into<Box<dyn std::error::Error + Send + Sync>> {
+    Error::new(ErrorKind::InvalidData, e)
+}
+
+impl TryFrom<&[u8]> for Request {
+    type Error = Error;
+
+    /// Parses a whole request, see [`parse_request`]
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        parse_request(data)
+    }
+}
+
+impl FromStr for Request {
+    type Err = Error;
+
+    /// Parses a whole request, see [`parse_request`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_request(s.as_bytes())
+    }
+}
+
+impl TryFrom<&Request> for Vec<u8> {
+    type Error = Error;
+
+    /// Serializes a request, see [`write_request`]
+    fn try_from(request: &Request) -> Result<Self, Self::Error> {
+        request_to_bytes(request)
+    }
+}
+
+impl TryFrom<&[u8]> for Response {
+    type Error = Error;
+
+    /// Parses a whole response, see [`parse_response`]
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        parse_response(data)
+    }
+}
+
+impl FromStr for Response {
+    type Err = Error;
+
+    /// Parses a whole response, see [`parse_response`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_response(s.as_bytes())
+    }
+}
+
+impl TryFrom<&Response> for Vec<u8> {
+    type Error = Error;
+
+    /// Serializes a response, see [`write_response`]
+    fn try_from(response: &Response) -> Result<Self, Self::Error> {
+        response_to_bytes(response)
+    }
+}